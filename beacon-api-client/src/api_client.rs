@@ -15,11 +15,13 @@ use ethereum_consensus::{
     altair::SyncCommitteeMessage,
     builder::SignedValidatorRegistration,
     capella::{SignedBlsToExecutionChange, Withdrawal},
+    clock::SystemClock,
     networking::PeerId,
     phase0::{AttestationData, Fork, ProposerSlashing, SignedVoluntaryExit},
     primitives::{
         BlobIndex, Bytes32, CommitteeIndex, Epoch, RandaoReveal, Root, Slot, ValidatorIndex,
     },
+    state_transition::Context,
     Fork as Version,
 };
 use http::StatusCode;
@@ -132,6 +134,13 @@ impl<C: ClientTypes> Client<C> {
         Ok(details.data)
     }
 
+    // Build a clock for the node's network using the genesis time it reports, which is useful
+    // for networks where the genesis time is not known ahead of time.
+    pub async fn get_clock(&self, context: &Context) -> Result<SystemClock, Error> {
+        let details = self.get_genesis_details().await?;
+        Ok(context.clock_at(details.genesis_time))
+    }
+
     pub async fn get_state_root(&self, state_id: StateId) -> Result<Root, Error> {
         let path = format!("eth/v1/beacon/states/{state_id}/root");
         let root: Value<RootData> = self.get(&path).await?;
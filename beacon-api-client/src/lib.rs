@@ -1,6 +1,7 @@
 mod api_client;
 mod api_error;
 mod cli;
+pub mod remote_signer;
 mod serde;
 mod types;
 
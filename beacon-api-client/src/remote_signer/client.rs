@@ -0,0 +1,338 @@
+use crate::remote_signer::{
+    AggregationSlot, BeaconBlockData, DepositData, Error, ForkInfo, RandaoReveal, SigningMessage,
+    SigningRequest, SigningResponse, SyncCommitteeMessageData,
+};
+use ethereum_consensus::{
+    altair::{ContributionAndProof, SyncAggregatorSelectionData},
+    builder::ValidatorRegistration,
+    phase0::{
+        compute_epoch_at_slot, AggregateAndProof, AttestationData, BeaconBlockHeader,
+        DepositMessage, VoluntaryExit,
+    },
+    primitives::{BlsPublicKey, BlsSignature, Epoch, Root, Slot},
+    state_transition::Context,
+    Fork as Version,
+};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use url::Url;
+
+/// A client for a remote signer implementing the Web3Signer signing API.
+///
+/// Signing roots are computed locally and sent along with each request so that the remote
+/// signer rejects any message it would sign with a different domain.
+#[derive(Clone)]
+pub struct Client<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    pub http: reqwest::Client,
+    pub endpoint: Url,
+}
+
+impl<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const SYNC_SUBCOMMITTEE_SIZE: usize,
+    >
+    Client<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >
+{
+    pub fn new_with_client<U: Into<Url>>(client: reqwest::Client, endpoint: U) -> Self {
+        Self { http: client, endpoint: endpoint.into() }
+    }
+
+    pub fn new<U: Into<Url>>(endpoint: U) -> Self {
+        let client = reqwest::Client::new();
+        Self::new_with_client(client, endpoint)
+    }
+
+    async fn error_from(response: reqwest::Response) -> Error {
+        let status = response.status();
+        match response.text().await {
+            Ok(message) => Error::Response { status, message },
+            Err(err) => err.into(),
+        }
+    }
+
+    pub async fn upcheck(&self) -> Result<(), Error> {
+        let target = self.endpoint.join("upcheck")?;
+        let response = self.http.get(target).send().await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from(response).await)
+        }
+        Ok(())
+    }
+
+    pub async fn public_keys(&self) -> Result<Vec<BlsPublicKey>, Error> {
+        let target = self.endpoint.join("api/v1/eth2/publicKeys")?;
+        let response = self.http.get(target).send().await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from(response).await)
+        }
+        Ok(response.json().await?)
+    }
+
+    pub async fn sign(
+        &self,
+        public_key: &BlsPublicKey,
+        request: &SigningRequest<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            SYNC_SUBCOMMITTEE_SIZE,
+        >,
+    ) -> Result<BlsSignature, Error> {
+        let target = self.endpoint.join(&format!("api/v1/eth2/sign/{public_key:?}"))?;
+        let response =
+            self.http.post(target).header(ACCEPT, "application/json").json(request).send().await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from(response).await)
+        }
+        // some signers ignore the `Accept` header and respond with the bare signature
+        let is_json = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.starts_with("application/json"))
+            .unwrap_or_default();
+        if is_json {
+            let response: SigningResponse = response.json().await?;
+            Ok(response.signature)
+        } else {
+            let signature = response.text().await?;
+            Ok(serde_json::from_value(serde_json::Value::String(signature.trim().to_string()))?)
+        }
+    }
+
+    pub async fn sign_message(
+        &self,
+        public_key: &BlsPublicKey,
+        mut message: SigningMessage<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            SYNC_SUBCOMMITTEE_SIZE,
+        >,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let signing_root = Some(message.signing_root(context)?);
+        let request = SigningRequest { signing_root, message };
+        self.sign(public_key, &request).await
+    }
+
+    /// Signs `beacon_block`, given in full for `phase0` and `altair` and as a header for later
+    /// forks.
+    pub async fn sign_block(
+        &self,
+        public_key: &BlsPublicKey,
+        beacon_block: BeaconBlockData<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+        >,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(beacon_block.slot(), context);
+        let message = SigningMessage::BlockV2 {
+            fork_info: ForkInfo::new(epoch, genesis_validators_root, context),
+            beacon_block,
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    /// Signs the block with `block_header` from the fork `version`.
+    ///
+    /// The signing API only accepts block headers from `bellatrix` onwards so earlier versions
+    /// are rejected.
+    pub async fn sign_block_header(
+        &self,
+        public_key: &BlsPublicKey,
+        version: Version,
+        block_header: BeaconBlockHeader,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        if version < Version::Bellatrix {
+            return Err(Error::UnsupportedBlockHeader(version))
+        }
+        let beacon_block = BeaconBlockData::from_header(version, block_header);
+        self.sign_block(public_key, beacon_block, genesis_validators_root, context).await
+    }
+
+    pub async fn sign_randao_reveal(
+        &self,
+        public_key: &BlsPublicKey,
+        epoch: Epoch,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let message = SigningMessage::RandaoReveal {
+            fork_info: ForkInfo::new(epoch, genesis_validators_root, context),
+            randao_reveal: RandaoReveal { epoch },
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_attestation_data(
+        &self,
+        public_key: &BlsPublicKey,
+        attestation: AttestationData,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let message = SigningMessage::Attestation {
+            fork_info: ForkInfo::new(attestation.target.epoch, genesis_validators_root, context),
+            attestation,
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_aggregate_selection_proof(
+        &self,
+        public_key: &BlsPublicKey,
+        slot: Slot,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(slot, context);
+        let message = SigningMessage::AggregationSlot {
+            fork_info: ForkInfo::new(epoch, genesis_validators_root, context),
+            aggregation_slot: AggregationSlot { slot },
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_aggregate_and_proof(
+        &self,
+        public_key: &BlsPublicKey,
+        aggregate_and_proof: AggregateAndProof<MAX_VALIDATORS_PER_COMMITTEE>,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(aggregate_and_proof.aggregate.data.slot, context);
+        let message = SigningMessage::AggregateAndProof {
+            fork_info: ForkInfo::new(epoch, genesis_validators_root, context),
+            aggregate_and_proof,
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_sync_committee_message(
+        &self,
+        public_key: &BlsPublicKey,
+        slot: Slot,
+        beacon_block_root: Root,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(slot, context);
+        let message = SigningMessage::SyncCommitteeMessage {
+            fork_info: ForkInfo::new(epoch, genesis_validators_root, context),
+            sync_committee_message: SyncCommitteeMessageData { beacon_block_root, slot },
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_sync_committee_selection_proof(
+        &self,
+        public_key: &BlsPublicKey,
+        sync_aggregator_selection_data: SyncAggregatorSelectionData,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(sync_aggregator_selection_data.slot, context);
+        let message = SigningMessage::SyncCommitteeSelectionProof {
+            fork_info: ForkInfo::new(epoch, genesis_validators_root, context),
+            sync_aggregator_selection_data,
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_contribution_and_proof(
+        &self,
+        public_key: &BlsPublicKey,
+        contribution_and_proof: ContributionAndProof<SYNC_SUBCOMMITTEE_SIZE>,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(contribution_and_proof.contribution.slot, context);
+        let message = SigningMessage::SyncCommitteeContributionAndProof {
+            fork_info: ForkInfo::new(epoch, genesis_validators_root, context),
+            contribution_and_proof,
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_voluntary_exit(
+        &self,
+        public_key: &BlsPublicKey,
+        voluntary_exit: VoluntaryExit,
+        current_epoch: Epoch,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let message = SigningMessage::VoluntaryExit {
+            fork_info: ForkInfo::new(current_epoch, genesis_validators_root, context),
+            voluntary_exit,
+        };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_validator_registration(
+        &self,
+        public_key: &BlsPublicKey,
+        validator_registration: ValidatorRegistration,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let message = SigningMessage::ValidatorRegistration { validator_registration };
+        self.sign_message(public_key, message, context).await
+    }
+
+    pub async fn sign_deposit_message(
+        &self,
+        public_key: &BlsPublicKey,
+        deposit_message: DepositMessage,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let deposit = DepositData {
+            public_key: deposit_message.public_key,
+            withdrawal_credentials: deposit_message.withdrawal_credentials,
+            amount: deposit_message.amount,
+            genesis_fork_version: context.genesis_fork_version,
+        };
+        let message = SigningMessage::Deposit { deposit };
+        self.sign_message(public_key, message, context).await
+    }
+}
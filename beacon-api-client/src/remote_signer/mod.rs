@@ -0,0 +1,780 @@
+//! Support for remote signers implementing the
+//! [Web3Signer](https://consensys.github.io/web3signer/web3signer-eth2.html) signing API.
+mod client;
+#[cfg(feature = "remote-signer-server")]
+mod server;
+
+pub use client::Client;
+#[cfg(feature = "remote-signer-server")]
+pub use server::Server;
+
+use ethereum_consensus::{
+    altair::{self, ContributionAndProof, SyncAggregatorSelectionData},
+    builder::{compute_builder_domain, ValidatorRegistration},
+    phase0::{
+        self, compute_domain, compute_epoch_at_slot, AggregateAndProof, AttestationData,
+        BeaconBlockHeader, DepositMessage, Fork, VoluntaryExit,
+    },
+    primitives::{BlsPublicKey, Bytes32, Domain, DomainType, Epoch, Gwei, Root, Slot, Version},
+    signer::compute_voluntary_exit_domain,
+    signing::compute_signing_root,
+    state_transition::Context,
+    Fork as ForkName,
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("could not parse URL: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("could not send request: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Consensus(#[from] ethereum_consensus::Error),
+    #[error("remote signer responded with status {status}: {message}")]
+    Response { status: http::StatusCode, message: String },
+    #[error(
+        "signing root {provided:?} does not match the signing root {expected:?} of the message"
+    )]
+    SigningRootMismatch { expected: Root, provided: Root },
+    #[error("fork {provided:?} does not match the fork {expected:?} of the chain at its epoch")]
+    ForkMismatch { expected: Fork, provided: Fork },
+    #[error("blocks from {0} must be signed in full rather than as a block header")]
+    UnsupportedBlockHeader(ForkName),
+}
+
+/// The fork of the chain a message is signed for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkInfo {
+    pub fork: Fork,
+    pub genesis_validators_root: Root,
+}
+
+impl ForkInfo {
+    /// Returns the `ForkInfo` of the chain at `epoch`, according to the fork schedule in
+    /// `context`.
+    pub fn new(epoch: Epoch, genesis_validators_root: Root, context: &Context) -> Self {
+        Self { fork: context.fork_schedule().fork_data(epoch), genesis_validators_root }
+    }
+
+    // Mirrors `get_domain` from the consensus specs, using `self.fork` in place of the fork of a
+    // `BeaconState`.
+    pub fn domain(
+        &self,
+        domain_type: DomainType,
+        epoch: Epoch,
+        context: &Context,
+    ) -> Result<Domain, ethereum_consensus::Error> {
+        let fork_version = if epoch < self.fork.epoch {
+            self.fork.previous_version
+        } else {
+            self.fork.current_version
+        };
+        compute_domain(domain_type, Some(fork_version), Some(self.genesis_validators_root), context)
+    }
+}
+
+/// A block to sign, given in full for `phase0` and `altair` and as a header for later forks.
+#[derive(Debug, Clone)]
+pub enum BeaconBlockData<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+> {
+    Phase0(
+        phase0::BeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+        >,
+    ),
+    Altair(
+        altair::BeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+        >,
+    ),
+    Header {
+        version: ForkName,
+        block_header: BeaconBlockHeader,
+    },
+}
+
+impl<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+    >
+    BeaconBlockData<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >
+{
+    pub fn from_header(version: ForkName, block_header: BeaconBlockHeader) -> Self {
+        Self::Header { version, block_header }
+    }
+
+    pub fn version(&self) -> ForkName {
+        match self {
+            Self::Phase0(_) => ForkName::Phase0,
+            Self::Altair(_) => ForkName::Altair,
+            Self::Header { version, .. } => *version,
+        }
+    }
+
+    pub fn slot(&self) -> Slot {
+        match self {
+            Self::Phase0(block) => block.slot,
+            Self::Altair(block) => block.slot,
+            Self::Header { block_header, .. } => block_header.slot,
+        }
+    }
+}
+
+// The JSON representation of `BeaconBlockData`, carrying a `block` up to `altair` and a
+// `block_header` from `bellatrix` onwards.
+#[derive(Serialize, Deserialize)]
+struct BeaconBlockDataJson<T> {
+    #[serde(with = "fork_name")]
+    version: ForkName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block_header: Option<BeaconBlockHeader>,
+}
+
+impl<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+    > Serialize
+    for BeaconBlockData<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let version = self.version();
+        match self {
+            Self::Phase0(block) => {
+                BeaconBlockDataJson { version, block: Some(block), block_header: None }
+                    .serialize(serializer)
+            }
+            Self::Altair(block) => {
+                BeaconBlockDataJson { version, block: Some(block), block_header: None }
+                    .serialize(serializer)
+            }
+            Self::Header { block_header, .. } => BeaconBlockDataJson::<()> {
+                version,
+                block: None,
+                block_header: Some(block_header.clone()),
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<
+        'de,
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+    > Deserialize<'de>
+    for BeaconBlockData<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = BeaconBlockDataJson::<serde_json::Value>::deserialize(deserializer)?;
+        match (data.version, data.block, data.block_header) {
+            (ForkName::Phase0, Some(block), None) => {
+                Ok(Self::Phase0(serde_json::from_value(block).map_err(D::Error::custom)?))
+            }
+            (ForkName::Altair, Some(block), None) => {
+                Ok(Self::Altair(serde_json::from_value(block).map_err(D::Error::custom)?))
+            }
+            (version, None, Some(block_header)) if version >= ForkName::Bellatrix => {
+                Ok(Self::Header { version, block_header })
+            }
+            (version, ..) if version >= ForkName::Bellatrix => Err(D::Error::custom(format!(
+                "blocks from {version} must be given as a `block_header`"
+            ))),
+            (version, ..) => Err(D::Error::custom(format!(
+                "blocks from {version} must be given as a full `block`"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregationSlot {
+    #[serde(with = "crate::serde::as_str")]
+    pub slot: Slot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RandaoReveal {
+    #[serde(with = "crate::serde::as_str")]
+    pub epoch: Epoch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncCommitteeMessageData {
+    pub beacon_block_root: Root,
+    #[serde(with = "crate::serde::as_str")]
+    pub slot: Slot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositData {
+    #[serde(rename = "pubkey")]
+    pub public_key: BlsPublicKey,
+    pub withdrawal_credentials: Bytes32,
+    #[serde(with = "crate::serde::as_str")]
+    pub amount: Gwei,
+    #[serde(with = "crate::serde::as_hex")]
+    pub genesis_fork_version: Version,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SigningMessage<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    #[serde(rename = "BLOCK_V2")]
+    BlockV2 {
+        fork_info: ForkInfo,
+        beacon_block: BeaconBlockData<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+        >,
+    },
+    Attestation {
+        fork_info: ForkInfo,
+        attestation: AttestationData,
+    },
+    AggregationSlot {
+        fork_info: ForkInfo,
+        aggregation_slot: AggregationSlot,
+    },
+    AggregateAndProof {
+        fork_info: ForkInfo,
+        aggregate_and_proof: AggregateAndProof<MAX_VALIDATORS_PER_COMMITTEE>,
+    },
+    RandaoReveal {
+        fork_info: ForkInfo,
+        randao_reveal: RandaoReveal,
+    },
+    VoluntaryExit {
+        fork_info: ForkInfo,
+        voluntary_exit: VoluntaryExit,
+    },
+    SyncCommitteeMessage {
+        fork_info: ForkInfo,
+        sync_committee_message: SyncCommitteeMessageData,
+    },
+    SyncCommitteeSelectionProof {
+        fork_info: ForkInfo,
+        sync_aggregator_selection_data: SyncAggregatorSelectionData,
+    },
+    SyncCommitteeContributionAndProof {
+        fork_info: ForkInfo,
+        contribution_and_proof: ContributionAndProof<SYNC_SUBCOMMITTEE_SIZE>,
+    },
+    ValidatorRegistration {
+        validator_registration: ValidatorRegistration,
+    },
+    Deposit {
+        deposit: DepositData,
+    },
+}
+
+impl<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const SYNC_SUBCOMMITTEE_SIZE: usize,
+    >
+    SigningMessage<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >
+{
+    /// Computes the signing root of the message.
+    pub fn signing_root(&mut self, context: &Context) -> Result<Root, Error> {
+        let signing_root = match self {
+            Self::BlockV2 { fork_info, beacon_block } => {
+                let epoch = compute_epoch_at_slot(beacon_block.slot(), context);
+                let domain = fork_info.domain(DomainType::BeaconProposer, epoch, context)?;
+                match beacon_block {
+                    BeaconBlockData::Phase0(block) => compute_signing_root(block, domain)?,
+                    BeaconBlockData::Altair(block) => compute_signing_root(block, domain)?,
+                    BeaconBlockData::Header { block_header, .. } => {
+                        compute_signing_root(block_header, domain)?
+                    }
+                }
+            }
+            Self::Attestation { fork_info, attestation } => {
+                let epoch = attestation.target.epoch;
+                let domain = fork_info.domain(DomainType::BeaconAttester, epoch, context)?;
+                compute_signing_root(attestation, domain)?
+            }
+            Self::AggregationSlot { fork_info, aggregation_slot } => {
+                let epoch = compute_epoch_at_slot(aggregation_slot.slot, context);
+                let domain = fork_info.domain(DomainType::SelectionProof, epoch, context)?;
+                compute_signing_root(&mut aggregation_slot.slot, domain)?
+            }
+            Self::AggregateAndProof { fork_info, aggregate_and_proof } => {
+                let epoch = compute_epoch_at_slot(aggregate_and_proof.aggregate.data.slot, context);
+                let domain = fork_info.domain(DomainType::AggregateAndProof, epoch, context)?;
+                compute_signing_root(aggregate_and_proof, domain)?
+            }
+            Self::RandaoReveal { fork_info, randao_reveal } => {
+                let domain = fork_info.domain(DomainType::Randao, randao_reveal.epoch, context)?;
+                compute_signing_root(&mut randao_reveal.epoch, domain)?
+            }
+            Self::VoluntaryExit { fork_info, voluntary_exit } => {
+                // NOTE: the domain is derived from the fork schedule in `context` so `fork_info`
+                // must describe the same chain
+                let expected = context.fork_schedule().fork_data(fork_info.fork.epoch);
+                if fork_info.fork != expected {
+                    return Err(Error::ForkMismatch { expected, provided: fork_info.fork.clone() })
+                }
+                // `fork_info` describes the fork the exit is processed in
+                let domain = compute_voluntary_exit_domain(
+                    voluntary_exit.epoch,
+                    fork_info.fork.epoch,
+                    fork_info.genesis_validators_root,
+                    context,
+                )?;
+                compute_signing_root(voluntary_exit, domain)?
+            }
+            Self::SyncCommitteeMessage { fork_info, sync_committee_message } => {
+                let epoch = compute_epoch_at_slot(sync_committee_message.slot, context);
+                let domain = fork_info.domain(DomainType::SyncCommittee, epoch, context)?;
+                compute_signing_root(&mut sync_committee_message.beacon_block_root, domain)?
+            }
+            Self::SyncCommitteeSelectionProof { fork_info, sync_aggregator_selection_data } => {
+                let epoch = compute_epoch_at_slot(sync_aggregator_selection_data.slot, context);
+                let domain =
+                    fork_info.domain(DomainType::SyncCommitteeSelectionProof, epoch, context)?;
+                compute_signing_root(sync_aggregator_selection_data, domain)?
+            }
+            Self::SyncCommitteeContributionAndProof { fork_info, contribution_and_proof } => {
+                let epoch =
+                    compute_epoch_at_slot(contribution_and_proof.contribution.slot, context);
+                let domain = fork_info.domain(DomainType::ContributionAndProof, epoch, context)?;
+                compute_signing_root(contribution_and_proof, domain)?
+            }
+            Self::ValidatorRegistration { validator_registration } => {
+                let domain = compute_builder_domain(context)?;
+                compute_signing_root(validator_registration, domain)?
+            }
+            Self::Deposit { deposit } => {
+                let domain = compute_domain(
+                    DomainType::Deposit,
+                    Some(deposit.genesis_fork_version),
+                    None,
+                    context,
+                )?;
+                let mut deposit_message = DepositMessage {
+                    public_key: deposit.public_key.clone(),
+                    withdrawal_credentials: deposit.withdrawal_credentials.clone(),
+                    amount: deposit.amount,
+                };
+                compute_signing_root(&mut deposit_message, domain)?
+            }
+        };
+        Ok(signing_root)
+    }
+}
+
+/// The body of a request to `/api/v1/eth2/sign/{identifier}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningRequest<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    #[serde(rename = "signingRoot", default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<Root>,
+    #[serde(flatten)]
+    pub message: SigningMessage<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >,
+}
+
+impl<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const SYNC_SUBCOMMITTEE_SIZE: usize,
+    >
+    SigningRequest<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >
+{
+    /// Returns the root to sign for this request, checking it against any provided
+    /// `signing_root`.
+    pub fn signing_root(&mut self, context: &Context) -> Result<Root, Error> {
+        let expected = self.message.signing_root(context)?;
+        match self.signing_root {
+            Some(provided) if provided != expected => {
+                Err(Error::SigningRootMismatch { expected, provided })
+            }
+            _ => Ok(expected),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningResponse {
+    pub signature: ethereum_consensus::primitives::BlsSignature,
+}
+
+mod fork_name {
+    use ethereum_consensus::Fork;
+    use serde::{de::Deserializer, Deserialize, Serializer};
+
+    pub fn serialize<S: Serializer>(fork: &Fork, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&fork.to_string().to_uppercase())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Fork, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Fork::ALL
+            .into_iter()
+            .find(|fork| fork.to_string().eq_ignore_ascii_case(&name))
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown fork `{name}`")))
+    }
+}
+
+pub mod mainnet {
+    use ethereum_consensus::altair::mainnet::{
+        MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS, MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS, SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    };
+
+    pub type BeaconBlockData = super::BeaconBlockData<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >;
+    pub type SigningMessage = super::SigningMessage<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+    pub type SigningRequest = super::SigningRequest<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+    pub type Client = super::Client<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+    #[cfg(feature = "remote-signer-server")]
+    pub type Server = super::Server<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+}
+
+pub mod minimal {
+    use ethereum_consensus::altair::minimal::{
+        MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS, MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS, SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    };
+
+    pub type BeaconBlockData = super::BeaconBlockData<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >;
+    pub type SigningMessage = super::SigningMessage<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+    pub type SigningRequest = super::SigningRequest<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+    pub type Client = super::Client<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+    #[cfg(feature = "remote-signer-server")]
+    pub type Server = super::Server<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_request_roundtrip() {
+        let input = r#"{
+            "type": "RANDAO_REVEAL",
+            "fork_info": {
+                "fork": {
+                    "previous_version": "0x00000000",
+                    "current_version": "0x01000000",
+                    "epoch": "10"
+                },
+                "genesis_validators_root": "0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"
+            },
+            "randao_reveal": {
+                "epoch": "9"
+            }
+        }"#;
+        let context = Context::for_mainnet();
+        let mut request: mainnet::SigningRequest = serde_json::from_str(input).unwrap();
+        assert!(request.signing_root.is_none());
+        let SigningMessage::RandaoReveal { ref fork_info, ref randao_reveal } = request.message
+        else {
+            panic!("expected a randao reveal")
+        };
+        assert_eq!(randao_reveal.epoch, 9);
+        // epoch 9 is before the fork at epoch 10 so the previous version is used
+        let domain = fork_info.domain(DomainType::Randao, 9, &context).unwrap();
+        let expected = compute_domain(
+            DomainType::Randao,
+            Some(fork_info.fork.previous_version),
+            Some(fork_info.genesis_validators_root),
+            &context,
+        )
+        .unwrap();
+        assert_eq!(domain, expected);
+
+        let signing_root = request.signing_root(&context).unwrap();
+        request.signing_root = Some(signing_root);
+        let encoding = serde_json::to_string(&request).unwrap();
+        let mut recovered: mainnet::SigningRequest = serde_json::from_str(&encoding).unwrap();
+        assert_eq!(recovered.signing_root(&context).unwrap(), signing_root);
+
+        recovered.signing_root = Some(Root::default());
+        assert!(matches!(recovered.signing_root(&context), Err(Error::SigningRootMismatch { .. })));
+    }
+
+    #[test]
+    fn test_full_block_signing_root() {
+        let context = Context::for_mainnet();
+        let genesis_validators_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+        let mut block = ethereum_consensus::phase0::mainnet::BeaconBlock::default();
+        block.slot = 33;
+        let message = mainnet::SigningMessage::BlockV2 {
+            fork_info: ForkInfo::new(1, genesis_validators_root, &context),
+            beacon_block: BeaconBlockData::Phase0(block.clone()),
+        };
+        let request = mainnet::SigningRequest { signing_root: None, message };
+        let encoding = serde_json::to_value(&request).unwrap();
+        assert_eq!(encoding["beacon_block"]["version"], "PHASE0");
+        assert_eq!(encoding["beacon_block"]["block"]["slot"], "33");
+
+        let mut recovered: mainnet::SigningRequest = serde_json::from_value(encoding).unwrap();
+        let domain = compute_domain(
+            DomainType::BeaconProposer,
+            Some(context.genesis_fork_version),
+            Some(genesis_validators_root),
+            &context,
+        )
+        .unwrap();
+        let expected = compute_signing_root(&mut block, domain).unwrap();
+        assert_eq!(recovered.signing_root(&context).unwrap(), expected);
+
+        // the signing root of a full block can not be substituted
+        recovered.signing_root = Some(Root::default());
+        assert!(matches!(recovered.signing_root(&context), Err(Error::SigningRootMismatch { .. })));
+
+        let mut mismatched = serde_json::to_value(&request).unwrap();
+        mismatched["beacon_block"]["version"] = "BELLATRIX".into();
+        assert!(serde_json::from_value::<mainnet::SigningRequest>(mismatched).is_err());
+        let header = BeaconBlockHeader::default();
+        let header = mainnet::BeaconBlockData::from_header(ForkName::Bellatrix, header);
+        let mut header = serde_json::to_value(&header).unwrap();
+        header["version"] = "ALTAIR".into();
+        assert!(serde_json::from_value::<mainnet::BeaconBlockData>(header).is_err());
+    }
+
+    #[test]
+    fn test_voluntary_exit_fork_mismatch() {
+        let context = Context::for_mainnet();
+        let genesis_validators_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+        let mut message = mainnet::SigningMessage::VoluntaryExit {
+            fork_info: ForkInfo::new(context.capella_fork_epoch, genesis_validators_root, &context),
+            voluntary_exit: VoluntaryExit::default(),
+        };
+        message.signing_root(&context).unwrap();
+
+        // a fork from another network
+        let SigningMessage::VoluntaryExit { ref mut fork_info, .. } = message else {
+            panic!("expected a voluntary exit")
+        };
+        fork_info.fork.current_version = [9u8; 4];
+        assert!(matches!(message.signing_root(&context), Err(Error::ForkMismatch { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_sign_block_header_before_bellatrix() {
+        let context = Context::for_mainnet();
+        let client = mainnet::Client::new(url::Url::parse("http://127.0.0.1:9000").unwrap());
+        let result = client
+            .sign_block_header(
+                &BlsPublicKey::default(),
+                ForkName::Altair,
+                BeaconBlockHeader::default(),
+                Root::default(),
+                &context,
+            )
+            .await;
+        assert!(matches!(result, Err(Error::UnsupportedBlockHeader(ForkName::Altair))));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::remote_signer::{Error, SigningRequest, SigningResponse};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use ethereum_consensus::{
+    crypto::SecretKey, primitives::BlsPublicKey, signer::Signer, state_transition::Context,
+};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+struct Keys {
+    secret_keys: HashMap<BlsPublicKey, SecretKey>,
+    context: Context,
+}
+
+/// A minimal server implementing the Web3Signer signing API with in-memory secret keys.
+///
+/// Intended for local testing: it signs any well-formed request and keeps no slashing
+/// protection records.
+#[derive(Clone)]
+pub struct Server<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    state: Arc<Keys>,
+}
+
+impl<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const SYNC_SUBCOMMITTEE_SIZE: usize,
+    >
+    Server<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+    >
+{
+    pub fn new(secret_keys: impl IntoIterator<Item = SecretKey>, context: Context) -> Self {
+        let secret_keys = secret_keys
+            .into_iter()
+            .map(|secret_key| (secret_key.public_key(), secret_key))
+            .collect();
+        Self { state: Arc::new(Keys { secret_keys, context }) }
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/upcheck", get(upcheck))
+            .route("/api/v1/eth2/publicKeys", get(public_keys))
+            .route(
+                "/api/v1/eth2/sign/:identifier",
+                post(
+                    sign::<
+                        MAX_PROPOSER_SLASHINGS,
+                        MAX_VALIDATORS_PER_COMMITTEE,
+                        MAX_ATTESTER_SLASHINGS,
+                        MAX_ATTESTATIONS,
+                        MAX_DEPOSITS,
+                        MAX_VOLUNTARY_EXITS,
+                        SYNC_COMMITTEE_SIZE,
+                        SYNC_SUBCOMMITTEE_SIZE,
+                    >,
+                ),
+            )
+            .with_state(self.state.clone())
+    }
+
+    pub async fn run(self, address: SocketAddr) -> Result<(), hyper::Error> {
+        axum::Server::bind(&address).serve(self.router().into_make_service()).await
+    }
+}
+
+async fn upcheck() -> &'static str {
+    "OK"
+}
+
+async fn public_keys(State(state): State<Arc<Keys>>) -> Json<Vec<BlsPublicKey>> {
+    Json(state.secret_keys.keys().cloned().collect())
+}
+
+async fn sign<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    State(state): State<Arc<Keys>>,
+    Path(identifier): Path<String>,
+    Json(mut request): Json<
+        SigningRequest<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            SYNC_SUBCOMMITTEE_SIZE,
+        >,
+    >,
+) -> Result<Json<SigningResponse>, Response> {
+    let public_key: BlsPublicKey = serde_json::from_value(serde_json::Value::String(identifier))
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()).into_response())?;
+    let Some(secret_key) = state.secret_keys.get(&public_key) else {
+        let message = format!("no key found for public key {public_key:?}");
+        return Err((StatusCode::NOT_FOUND, message).into_response())
+    };
+    let signing_root = request.signing_root(&state.context).map_err(|err| {
+        let status = match err {
+            Error::SigningRootMismatch { .. } | Error::ForkMismatch { .. } => {
+                StatusCode::BAD_REQUEST
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, err.to_string()).into_response()
+    })?;
+    let signature = secret_key
+        .sign_root(signing_root)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response())?;
+    Ok(Json(SigningResponse { signature }))
+}
@@ -14,6 +14,11 @@ use ethereum_consensus::{
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt, str::FromStr};
 
+pub use ethereum_consensus::{
+    altair::validator::SyncCommitteeDuty,
+    phase0::validator::{AttestationDuty, ProposerDuty},
+};
+
 #[derive(Serialize, Deserialize)]
 pub struct VersionData {
     pub version: String,
@@ -412,44 +417,6 @@ pub enum HealthStatus {
     Unknown,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct AttestationDuty {
-    #[serde(rename = "pubkey")]
-    pub public_key: BlsPublicKey,
-    #[serde(with = "crate::serde::as_str")]
-    pub validator_index: ValidatorIndex,
-    #[serde(with = "crate::serde::as_str")]
-    pub committee_index: CommitteeIndex,
-    #[serde(with = "crate::serde::as_str")]
-    pub committee_length: usize,
-    #[serde(with = "crate::serde::as_str")]
-    pub committees_at_slot: usize,
-    #[serde(with = "crate::serde::as_str")]
-    pub validator_committee_index: usize,
-    #[serde(with = "crate::serde::as_str")]
-    pub slot: Slot,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ProposerDuty {
-    #[serde(rename = "pubkey")]
-    pub public_key: BlsPublicKey,
-    #[serde(with = "crate::serde::as_str")]
-    pub validator_index: ValidatorIndex,
-    #[serde(with = "crate::serde::as_str")]
-    pub slot: Slot,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct SyncCommitteeDuty {
-    #[serde(rename = "pubkey")]
-    pub public_key: BlsPublicKey,
-    #[serde(with = "crate::serde::as_str")]
-    pub validator_index: ValidatorIndex,
-    #[serde(with = "crate::serde::seq_of_str")]
-    pub validator_sync_committee_indices: Vec<usize>,
-}
-
 #[derive(Serialize, Deserialize)]
 pub struct CommitteeDescriptor {
     #[serde(with = "crate::serde::as_str")]
@@ -0,0 +1,49 @@
+use beacon_api_client::remote_signer::{mainnet, Error};
+use ethereum_consensus::{
+    crypto::SecretKey, phase0::AttestationData, primitives::Root, signer::Signer,
+    state_transition::Context,
+};
+use http::StatusCode;
+use url::Url;
+
+#[tokio::test]
+async fn test_sign_with_remote_signer() {
+    let context = Context::for_mainnet();
+    let secret_key = SecretKey::key_gen(&[7u8; 32]).unwrap();
+    let public_key = secret_key.public_key();
+    let genesis_validators_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+
+    let server = mainnet::Server::new([secret_key.clone()], context.clone());
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let router = server.router();
+    tokio::spawn(async move {
+        axum::Server::from_tcp(listener).unwrap().serve(router.into_make_service()).await
+    });
+
+    let client = mainnet::Client::new(Url::parse(&format!("http://{address}")).unwrap());
+    client.upcheck().await.unwrap();
+    assert_eq!(client.public_keys().await.unwrap(), vec![public_key.clone()]);
+
+    let mut attestation_data = AttestationData::default();
+    attestation_data.target.epoch = context.capella_fork_epoch;
+    let signature = client
+        .sign_attestation_data(
+            &public_key,
+            attestation_data.clone(),
+            genesis_validators_root,
+            &context,
+        )
+        .await
+        .unwrap();
+    let expected = secret_key
+        .sign_attestation_data(&mut attestation_data, genesis_validators_root, &context)
+        .unwrap();
+    assert_eq!(signature, expected);
+
+    let unknown_key = SecretKey::key_gen(&[8u8; 32]).unwrap().public_key();
+    assert!(matches!(
+        client.sign_randao_reveal(&unknown_key, 0, genesis_validators_root, &context).await,
+        Err(Error::Response { status: StatusCode::NOT_FOUND, .. })
+    ));
+}
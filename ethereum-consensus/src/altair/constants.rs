@@ -11,3 +11,4 @@ pub const PARTICIPATION_FLAG_WEIGHTS: [u64; 3] =
     [TIMELY_SOURCE_WEIGHT, TIMELY_TARGET_WEIGHT, TIMELY_HEAD_WEIGHT];
 
 pub const SYNC_COMMITTEE_SUBNET_COUNT: usize = 4;
+pub const TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE: usize = 2usize.pow(4);
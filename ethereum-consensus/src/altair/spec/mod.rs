@@ -8,9 +8,9 @@ pub use crate::{
         },
         constants::{
             PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT, SYNC_COMMITTEE_SUBNET_COUNT,
-            SYNC_REWARD_WEIGHT, TIMELY_HEAD_FLAG_INDEX, TIMELY_HEAD_WEIGHT,
-            TIMELY_SOURCE_FLAG_INDEX, TIMELY_SOURCE_WEIGHT, TIMELY_TARGET_FLAG_INDEX,
-            TIMELY_TARGET_WEIGHT, WEIGHT_DENOMINATOR,
+            SYNC_REWARD_WEIGHT, TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE, TIMELY_HEAD_FLAG_INDEX,
+            TIMELY_HEAD_WEIGHT, TIMELY_SOURCE_FLAG_INDEX, TIMELY_SOURCE_WEIGHT,
+            TIMELY_TARGET_FLAG_INDEX, TIMELY_TARGET_WEIGHT, WEIGHT_DENOMINATOR,
         },
         epoch_processing::{
             get_base_reward, process_epoch, process_inactivity_updates,
@@ -34,8 +34,11 @@ pub use crate::{
         },
         sync::{SyncAggregate, SyncCommittee},
         validator::{
-            ContributionAndProof, SignedContributionAndProof, SyncAggregatorSelectionData,
-            SyncCommitteeContribution, SyncCommitteeMessage,
+            compute_subnets_for_sync_committee, compute_sync_committee_period,
+            get_sync_committee_duties, get_sync_subcommittee_pubkeys,
+            is_assigned_to_sync_committee, is_sync_committee_aggregator, ContributionAndProof,
+            SignedContributionAndProof, SyncAggregatorSelectionData, SyncCommitteeContribution,
+            SyncCommitteeDuty, SyncCommitteeMessage,
         },
     },
     error::*,
@@ -44,14 +47,17 @@ pub use crate::{
         beacon_state::{Fork, ForkData, HistoricalBatch, HistoricalSummary},
         constants::{
             BASE_REWARDS_PER_EPOCH, DEPOSIT_CONTRACT_TREE_DEPTH, DEPOSIT_DATA_LIST_BOUND,
-            JUSTIFICATION_BITS_LENGTH,
+            JUSTIFICATION_BITS_LENGTH, TARGET_AGGREGATORS_PER_COMMITTEE,
         },
         operations::{
             Attestation, AttestationData, AttesterSlashing, Checkpoint, Deposit, DepositData,
             DepositMessage, Eth1Data, IndexedAttestation, PendingAttestation, ProposerSlashing,
             SignedVoluntaryExit, VoluntaryExit,
         },
-        validator::{AggregateAndProof, Eth1Block, SignedAggregateAndProof, Validator},
+        validator::{
+            AggregateAndProof, AttestationDuty, Eth1Block, ProposerDuty, SignedAggregateAndProof,
+            Validator,
+        },
     },
     primitives::*,
     signing::*,
@@ -1970,3 +1976,217 @@ pub fn state_transition<
     process_slots(state, signed_block.message.slot, context)?;
     state_transition_block_in_slot(state, signed_block, validation, context)
 }
+pub fn get_slot_signature<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    slot: Slot,
+    private_key: &BlsSecretKey,
+    context: &Context,
+) -> Result<BlsSignature> {
+    let epoch = compute_epoch_at_slot(slot, context);
+    let domain = get_domain(state, DomainType::SelectionProof, Some(epoch), context)?;
+    let mut slot = slot;
+    sign_with_domain(&mut slot, private_key, domain)
+}
+pub fn is_aggregator<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    slot: Slot,
+    committee_index: CommitteeIndex,
+    slot_signature: &BlsSignature,
+    context: &Context,
+) -> Result<bool> {
+    let committee = get_beacon_committee(state, slot, committee_index, context)?;
+    let modulo = cmp::max(1, committee.len() / TARGET_AGGREGATORS_PER_COMMITTEE) as u64;
+    let digest = hash(slot_signature.as_ref());
+    let value = u64::from_le_bytes(digest[..8].try_into().expect("correct size"));
+    Ok(value % modulo == 0)
+}
+pub fn get_committee_assignment<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    epoch: Epoch,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<Option<(Vec<ValidatorIndex>, CommitteeIndex, Slot)>> {
+    let previous_epoch = get_previous_epoch(state, context);
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < previous_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch });
+    }
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let committee_count_per_slot = get_committee_count_per_slot(state, epoch, context);
+    for slot in start_slot..start_slot + context.slots_per_epoch {
+        for index in 0..committee_count_per_slot {
+            let committee = get_beacon_committee(state, slot, index, context)?;
+            if committee.contains(&validator_index) {
+                return Ok(Some((committee, index, slot)));
+            }
+        }
+    }
+    Ok(None)
+}
+pub fn get_attestation_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    epoch: Epoch,
+    validator_indices: &[ValidatorIndex],
+    context: &Context,
+) -> Result<Vec<AttestationDuty>> {
+    let previous_epoch = get_previous_epoch(state, context);
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < previous_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch });
+    }
+    let mut targets = HashSet::with_capacity(validator_indices.len());
+    for &index in validator_indices {
+        if index >= state.validators.len() {
+            return Err(Error::OutOfBounds { requested: index, bound: state.validators.len() });
+        }
+        targets.insert(index);
+    }
+    let mut duties = vec![];
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let committees_at_slot = get_committee_count_per_slot(state, epoch, context);
+    for slot in start_slot..start_slot + context.slots_per_epoch {
+        for committee_index in 0..committees_at_slot {
+            let committee = get_beacon_committee(state, slot, committee_index, context)?;
+            for (validator_committee_index, validator_index) in committee.iter().enumerate() {
+                if targets.contains(validator_index) {
+                    duties.push(AttestationDuty {
+                        public_key: state.validators[*validator_index].public_key.clone(),
+                        validator_index: *validator_index,
+                        committee_index,
+                        committee_length: committee.len(),
+                        committees_at_slot,
+                        validator_committee_index,
+                        slot,
+                    });
+                }
+            }
+        }
+    }
+    Ok(duties)
+}
+pub fn get_proposer_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    epoch: Epoch,
+    context: &Context,
+) -> Result<Vec<ProposerDuty>> {
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < current_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch });
+    }
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let advanced_state;
+    let state = if epoch > current_epoch {
+        let mut state = state.clone();
+        process_slots(&mut state, start_slot, context)?;
+        advanced_state = state;
+        &advanced_state
+    } else {
+        state
+    };
+    let seed = get_seed(state, epoch, DomainType::BeaconProposer, context);
+    let indices = get_active_validator_indices(state, epoch);
+    let mut input = [0u8; 40];
+    input[..32].copy_from_slice(seed.as_ref());
+    (start_slot..start_slot + context.slots_per_epoch)
+        .map(|slot| {
+            input[32..40].copy_from_slice(&slot.to_le_bytes());
+            let seed = hash(input);
+            let validator_index = compute_proposer_index(state, &indices, &seed, context)?;
+            Ok(ProposerDuty {
+                public_key: state.validators[validator_index].public_key.clone(),
+                validator_index,
+                slot,
+            })
+        })
+        .collect()
+}
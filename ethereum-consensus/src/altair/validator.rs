@@ -1,7 +1,17 @@
 use crate::{
-    primitives::{BlsSignature, Root, Slot, ValidatorIndex},
+    altair::{
+        beacon_state::BeaconState,
+        compute_epoch_at_slot,
+        constants::{SYNC_COMMITTEE_SUBNET_COUNT, TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE},
+        get_current_epoch,
+    },
+    crypto::hash,
+    error::Error,
+    primitives::{BlsPublicKey, BlsSignature, Epoch, Root, Slot, SubnetId, ValidatorIndex},
     ssz::prelude::*,
+    state_transition::{Context, Result},
 };
+use std::{cmp, collections::HashSet};
 
 #[derive(Debug, Default, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
 pub struct SyncCommitteeMessage {
@@ -40,6 +50,357 @@ pub struct SignedContributionAndProof<const SYNC_SUBCOMMITTEE_SIZE: usize> {
 
 #[derive(Debug, Default, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
 pub struct SyncAggregatorSelectionData {
+    #[serde(with = "crate::serde::as_str")]
     pub slot: Slot,
+    #[serde(with = "crate::serde::as_str")]
     pub subcommittee_index: u64,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SyncCommitteeDuty {
+    #[serde(rename = "pubkey")]
+    pub public_key: BlsPublicKey,
+    #[serde(with = "crate::serde::as_str")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "crate::serde::seq_of_str")]
+    pub validator_sync_committee_indices: Vec<usize>,
+}
+
+pub fn compute_sync_committee_period(epoch: Epoch, context: &Context) -> u64 {
+    epoch / context.epochs_per_sync_committee_period
+}
+
+pub fn is_sync_committee_aggregator(signature: &BlsSignature, context: &Context) -> bool {
+    let modulo = cmp::max(
+        1,
+        context.sync_committee_size /
+            SYNC_COMMITTEE_SUBNET_COUNT /
+            TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE,
+    ) as u64;
+    let digest = hash(signature.as_ref());
+    let value = u64::from_le_bytes(digest[..8].try_into().expect("correct size"));
+    value % modulo == 0
+}
+
+// Return the sync committee subnets the validator at `validator_index` should participate in,
+// with respect to the sync committee signing for the slot after `state.slot`.
+pub fn compute_subnets_for_sync_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<HashSet<SubnetId>> {
+    // Committees assigned to `slot` sign for `slot - 1`, which creates the exceptional logic
+    // below when transitioning between sync committee periods.
+    let next_slot_epoch = compute_epoch_at_slot(state.slot + 1, context);
+    let current_period = compute_sync_committee_period(get_current_epoch(state, context), context);
+    let sync_committee =
+        if current_period == compute_sync_committee_period(next_slot_epoch, context) {
+            &state.current_sync_committee
+        } else {
+            &state.next_sync_committee
+        };
+
+    let target_public_key = &state
+        .validators
+        .get(validator_index)
+        .ok_or(Error::OutOfBounds { requested: validator_index, bound: state.validators.len() })?
+        .public_key;
+    let sync_subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+    let subnets = sync_committee
+        .public_keys
+        .iter()
+        .enumerate()
+        .filter(|(_, public_key)| *public_key == target_public_key)
+        .map(|(index, _)| (index / sync_subcommittee_size) as SubnetId)
+        .collect();
+    Ok(subnets)
+}
+
+pub fn get_sync_subcommittee_pubkeys<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    subcommittee_index: usize,
+    context: &Context,
+) -> Vec<BlsPublicKey> {
+    // Committees assigned to `slot` sign for `slot - 1`, which creates the exceptional logic
+    // below when transitioning between sync committee periods.
+    let next_slot_epoch = compute_epoch_at_slot(state.slot + 1, context);
+    let current_period = compute_sync_committee_period(get_current_epoch(state, context), context);
+    let sync_committee =
+        if current_period == compute_sync_committee_period(next_slot_epoch, context) {
+            &state.current_sync_committee
+        } else {
+            &state.next_sync_committee
+        };
+
+    let sync_subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+    let i = subcommittee_index * sync_subcommittee_size;
+    sync_committee.public_keys.iter().skip(i).take(sync_subcommittee_size).cloned().collect()
+}
+
+pub fn is_assigned_to_sync_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    epoch: Epoch,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<bool> {
+    let sync_committee_period = compute_sync_committee_period(epoch, context);
+    let current_epoch = get_current_epoch(state, context);
+    let current_sync_committee_period = compute_sync_committee_period(current_epoch, context);
+    let next_sync_committee_period = current_sync_committee_period + 1;
+
+    let public_key = &state
+        .validators
+        .get(validator_index)
+        .ok_or(Error::OutOfBounds { requested: validator_index, bound: state.validators.len() })?
+        .public_key;
+    if sync_committee_period == current_sync_committee_period {
+        Ok(state.current_sync_committee.public_keys.contains(public_key))
+    } else if sync_committee_period == next_sync_committee_period {
+        Ok(state.next_sync_committee.public_keys.contains(public_key))
+    } else {
+        Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch })
+    }
+}
+
+// Return the sync committee duties in `epoch` for each of the validators in `validator_indices`
+// that are members of the relevant sync committee, in the same shape as the beacon node API.
+// `epoch` must fall in either the current or the next sync committee period of `state`.
+pub fn get_sync_committee_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    epoch: Epoch,
+    validator_indices: &[ValidatorIndex],
+    context: &Context,
+) -> Result<Vec<SyncCommitteeDuty>> {
+    let current_epoch = get_current_epoch(state, context);
+    let current_period = compute_sync_committee_period(current_epoch, context);
+    let period = compute_sync_committee_period(epoch, context);
+    let sync_committee = if period == current_period {
+        &state.current_sync_committee
+    } else if period == current_period + 1 {
+        &state.next_sync_committee
+    } else {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch })
+    };
+
+    let mut duties = vec![];
+    for &validator_index in validator_indices {
+        let public_key = &state
+            .validators
+            .get(validator_index)
+            .ok_or(Error::OutOfBounds {
+                requested: validator_index,
+                bound: state.validators.len(),
+            })?
+            .public_key;
+        let validator_sync_committee_indices = sync_committee
+            .public_keys
+            .iter()
+            .enumerate()
+            .filter(|(_, member)| *member == public_key)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if !validator_sync_committee_indices.is_empty() {
+            duties.push(SyncCommitteeDuty {
+                public_key: public_key.clone(),
+                validator_index,
+                validator_sync_committee_indices,
+            });
+        }
+    }
+    Ok(duties)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        altair::{minimal, SyncCommittee},
+        crypto::SecretKey,
+        phase0::Validator,
+    };
+
+    #[test]
+    fn test_sync_committee_duties() {
+        let context = Context::for_minimal();
+        let sync_committee_size = context.sync_committee_size;
+        let public_keys = (0..2 * sync_committee_size)
+            .map(|i| SecretKey::key_gen(&[i as u8 + 1; 32]).unwrap().public_key())
+            .collect::<Vec<_>>();
+        // the first half of the validators make up the current sync committee and the second half
+        // make up the next one
+        let current_period = 1;
+        let mut state = minimal::BeaconState {
+            slot: (current_period * context.epochs_per_sync_committee_period + 2) *
+                context.slots_per_epoch,
+            ..Default::default()
+        };
+        state.validators = public_keys
+            .iter()
+            .map(|public_key| Validator { public_key: public_key.clone(), ..Default::default() })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        state.current_sync_committee = SyncCommittee {
+            public_keys: public_keys[..sync_committee_size].to_vec().try_into().unwrap(),
+            ..Default::default()
+        };
+        state.next_sync_committee = SyncCommittee {
+            public_keys: public_keys[sync_committee_size..].to_vec().try_into().unwrap(),
+            ..Default::default()
+        };
+
+        let current_epoch = get_current_epoch(&state, &context);
+        let next_period_epoch = (current_period + 1) * context.epochs_per_sync_committee_period;
+        let validator_indices = [1, sync_committee_size + 2];
+        for (epoch, validator_index, sync_committee_index) in
+            [(current_epoch, 1, 1), (next_period_epoch, sync_committee_size + 2, 2)]
+        {
+            let duties =
+                get_sync_committee_duties(&state, epoch, &validator_indices, &context).unwrap();
+            assert_eq!(duties.len(), 1);
+            assert_eq!(duties[0].validator_index, validator_index);
+            assert_eq!(duties[0].public_key, public_keys[validator_index]);
+            assert_eq!(duties[0].validator_sync_committee_indices, vec![sync_committee_index]);
+            for &index in &validator_indices {
+                let is_assigned =
+                    is_assigned_to_sync_committee(&state, epoch, index, &context).unwrap();
+                assert_eq!(is_assigned, index == validator_index);
+            }
+        }
+
+        let previous_period_epoch = current_epoch - context.epochs_per_sync_committee_period;
+        for epoch in
+            [previous_period_epoch, next_period_epoch + context.epochs_per_sync_committee_period]
+        {
+            assert!(get_sync_committee_duties(&state, epoch, &validator_indices, &context).is_err());
+            assert!(is_assigned_to_sync_committee(&state, epoch, 1, &context).is_err());
+        }
+    }
+
+    #[test]
+    fn test_is_sync_committee_aggregator() {
+        let context = Context::for_mainnet();
+        // sync subcommittees of 128 validators select aggregators with a modulo of 8
+        for (signature, expected) in [
+            (
+                "0x8e946b4daaa1c55bfe458ad6dc49a51b4a7a2c6b493aaf0c91e6a168245f5bf5670d7a11562b9b9fb0b0c32fdcad0d081408cc140ccddabc8dd59f3be290073248edffb1e6e82d29f915667605b8c17072ada018ee860d7bb8915a061daf17a2",
+                true,
+            ),
+            (
+                "0x85146861f89f5cac0d7137aa3b6dcf12d6164ef8f09a635d02fa84e8f994062e7333115e25476ff48eb9927a112354d90c58dde1f3f80712cf8ba53315882d53cf5697055a400907fb3ddb8fc31d3ff477d8436a1366ec54222ee1e8f542e133",
+                false,
+            ),
+        ] {
+            let signature = crate::serde::try_bytes_from_hex_str(signature).unwrap();
+            let signature = BlsSignature::try_from(signature.as_ref()).unwrap();
+            assert_eq!(is_sync_committee_aggregator(&signature, &context), expected);
+            // every member of the smaller sync subcommittees of the minimal preset is an aggregator
+            assert!(is_sync_committee_aggregator(&signature, &Context::for_minimal()));
+        }
+    }
+
+    #[test]
+    fn test_compute_subnets_for_sync_committee() {
+        let context = Context::for_minimal();
+        let public_keys = (0..3)
+            .map(|i| SecretKey::key_gen(&[i as u8 + 1; 32]).unwrap().public_key())
+            .collect::<Vec<_>>();
+        let mut state = minimal::BeaconState::default();
+        state.validators = public_keys
+            .iter()
+            .map(|public_key| Validator { public_key: public_key.clone(), ..Default::default() })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        // the subcommittees of the minimal preset have 8 members
+        let mut current_sync_committee = vec![public_keys[0].clone(); context.sync_committee_size];
+        current_sync_committee[3] = public_keys[1].clone();
+        current_sync_committee[20] = public_keys[1].clone();
+        let mut next_sync_committee = vec![public_keys[0].clone(); context.sync_committee_size];
+        next_sync_committee[31] = public_keys[1].clone();
+        state.current_sync_committee.public_keys = current_sync_committee.try_into().unwrap();
+        state.next_sync_committee.public_keys = next_sync_committee.try_into().unwrap();
+
+        let slots_per_period = context.epochs_per_sync_committee_period * context.slots_per_epoch;
+        // the committee of the next period signs from the last slot of the current period
+        for (slot, expected) in [(10, vec![0, 2]), (slots_per_period - 1, vec![3])] {
+            state.slot = slot;
+            let subnets = compute_subnets_for_sync_committee(&state, 1, &context).unwrap();
+            assert_eq!(subnets, HashSet::from_iter(expected));
+            assert!(compute_subnets_for_sync_committee(&state, 2, &context).unwrap().is_empty());
+        }
+        assert!(compute_subnets_for_sync_committee(&state, 3, &context).is_err());
+    }
+}
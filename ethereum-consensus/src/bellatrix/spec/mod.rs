@@ -3,9 +3,9 @@ pub use crate::{
     altair::{
         constants::{
             PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT, SYNC_COMMITTEE_SUBNET_COUNT,
-            SYNC_REWARD_WEIGHT, TIMELY_HEAD_FLAG_INDEX, TIMELY_HEAD_WEIGHT,
-            TIMELY_SOURCE_FLAG_INDEX, TIMELY_SOURCE_WEIGHT, TIMELY_TARGET_FLAG_INDEX,
-            TIMELY_TARGET_WEIGHT, WEIGHT_DENOMINATOR,
+            SYNC_REWARD_WEIGHT, TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE, TIMELY_HEAD_FLAG_INDEX,
+            TIMELY_HEAD_WEIGHT, TIMELY_SOURCE_FLAG_INDEX, TIMELY_SOURCE_WEIGHT,
+            TIMELY_TARGET_FLAG_INDEX, TIMELY_TARGET_WEIGHT, WEIGHT_DENOMINATOR,
         },
         light_client::{
             LightClientBootstrap, LightClientFinalityUpdate, LightClientHeader,
@@ -17,7 +17,7 @@ pub use crate::{
         sync::{SyncAggregate, SyncCommittee},
         validator::{
             ContributionAndProof, SignedContributionAndProof, SyncAggregatorSelectionData,
-            SyncCommitteeContribution, SyncCommitteeMessage,
+            SyncCommitteeContribution, SyncCommitteeDuty, SyncCommitteeMessage,
         },
     },
     bellatrix::{
@@ -45,14 +45,17 @@ pub use crate::{
         beacon_state::{Fork, ForkData, HistoricalBatch, HistoricalSummary},
         constants::{
             BASE_REWARDS_PER_EPOCH, DEPOSIT_CONTRACT_TREE_DEPTH, DEPOSIT_DATA_LIST_BOUND,
-            JUSTIFICATION_BITS_LENGTH,
+            JUSTIFICATION_BITS_LENGTH, TARGET_AGGREGATORS_PER_COMMITTEE,
         },
         operations::{
             Attestation, AttestationData, AttesterSlashing, Checkpoint, Deposit, DepositData,
             DepositMessage, Eth1Data, IndexedAttestation, PendingAttestation, ProposerSlashing,
             SignedVoluntaryExit, VoluntaryExit,
         },
-        validator::{AggregateAndProof, Eth1Block, SignedAggregateAndProof, Validator},
+        validator::{
+            AggregateAndProof, AttestationDuty, Eth1Block, ProposerDuty, SignedAggregateAndProof,
+            Validator,
+        },
     },
     primitives::*,
     signing::*,
@@ -2912,4 +2915,451 @@ pub fn process_slot<
     state.block_roots[root_index as usize] = previous_block_root;
     Ok(())
 }
+pub fn compute_sync_committee_period(epoch: Epoch, context: &Context) -> u64 {
+    epoch / context.epochs_per_sync_committee_period
+}
+pub fn is_sync_committee_aggregator(signature: &BlsSignature, context: &Context) -> bool {
+    let modulo = cmp::max(
+        1,
+        context.sync_committee_size /
+            SYNC_COMMITTEE_SUBNET_COUNT /
+            TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE,
+    ) as u64;
+    let digest = hash(signature.as_ref());
+    let value = u64::from_le_bytes(digest[..8].try_into().expect("correct size"));
+    value % modulo == 0
+}
+pub fn compute_subnets_for_sync_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<HashSet<SubnetId>> {
+    let next_slot_epoch = compute_epoch_at_slot(state.slot + 1, context);
+    let current_period = compute_sync_committee_period(get_current_epoch(state, context), context);
+    let sync_committee =
+        if current_period == compute_sync_committee_period(next_slot_epoch, context) {
+            &state.current_sync_committee
+        } else {
+            &state.next_sync_committee
+        };
+    let target_public_key = &state
+        .validators
+        .get(validator_index)
+        .ok_or(Error::OutOfBounds { requested: validator_index, bound: state.validators.len() })?
+        .public_key;
+    let sync_subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+    let subnets = sync_committee
+        .public_keys
+        .iter()
+        .enumerate()
+        .filter(|(_, public_key)| *public_key == target_public_key)
+        .map(|(index, _)| (index / sync_subcommittee_size) as SubnetId)
+        .collect();
+    Ok(subnets)
+}
+pub fn get_sync_subcommittee_pubkeys<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    subcommittee_index: usize,
+    context: &Context,
+) -> Vec<BlsPublicKey> {
+    let next_slot_epoch = compute_epoch_at_slot(state.slot + 1, context);
+    let current_period = compute_sync_committee_period(get_current_epoch(state, context), context);
+    let sync_committee =
+        if current_period == compute_sync_committee_period(next_slot_epoch, context) {
+            &state.current_sync_committee
+        } else {
+            &state.next_sync_committee
+        };
+    let sync_subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+    let i = subcommittee_index * sync_subcommittee_size;
+    sync_committee.public_keys.iter().skip(i).take(sync_subcommittee_size).cloned().collect()
+}
+pub fn is_assigned_to_sync_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    epoch: Epoch,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<bool> {
+    let sync_committee_period = compute_sync_committee_period(epoch, context);
+    let current_epoch = get_current_epoch(state, context);
+    let current_sync_committee_period = compute_sync_committee_period(current_epoch, context);
+    let next_sync_committee_period = current_sync_committee_period + 1;
+    let public_key = &state
+        .validators
+        .get(validator_index)
+        .ok_or(Error::OutOfBounds { requested: validator_index, bound: state.validators.len() })?
+        .public_key;
+    if sync_committee_period == current_sync_committee_period {
+        Ok(state.current_sync_committee.public_keys.contains(public_key))
+    } else if sync_committee_period == next_sync_committee_period {
+        Ok(state.next_sync_committee.public_keys.contains(public_key))
+    } else {
+        Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch })
+    }
+}
+pub fn get_sync_committee_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    epoch: Epoch,
+    validator_indices: &[ValidatorIndex],
+    context: &Context,
+) -> Result<Vec<SyncCommitteeDuty>> {
+    let current_epoch = get_current_epoch(state, context);
+    let current_period = compute_sync_committee_period(current_epoch, context);
+    let period = compute_sync_committee_period(epoch, context);
+    let sync_committee = if period == current_period {
+        &state.current_sync_committee
+    } else if period == current_period + 1 {
+        &state.next_sync_committee
+    } else {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch })
+    };
+    let mut duties = vec![];
+    for &validator_index in validator_indices {
+        let public_key = &state
+            .validators
+            .get(validator_index)
+            .ok_or(Error::OutOfBounds {
+                requested: validator_index,
+                bound: state.validators.len(),
+            })?
+            .public_key;
+        let validator_sync_committee_indices = sync_committee
+            .public_keys
+            .iter()
+            .enumerate()
+            .filter(|(_, member)| *member == public_key)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if !validator_sync_committee_indices.is_empty() {
+            duties.push(SyncCommitteeDuty {
+                public_key: public_key.clone(),
+                validator_index,
+                validator_sync_committee_indices,
+            });
+        }
+    }
+    Ok(duties)
+}
+pub fn get_slot_signature<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    private_key: &BlsSecretKey,
+    context: &Context,
+) -> Result<BlsSignature> {
+    let epoch = compute_epoch_at_slot(slot, context);
+    let domain = get_domain(state, DomainType::SelectionProof, Some(epoch), context)?;
+    let mut slot = slot;
+    sign_with_domain(&mut slot, private_key, domain)
+}
+pub fn is_aggregator<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    committee_index: CommitteeIndex,
+    slot_signature: &BlsSignature,
+    context: &Context,
+) -> Result<bool> {
+    let committee = get_beacon_committee(state, slot, committee_index, context)?;
+    let modulo = cmp::max(1, committee.len() / TARGET_AGGREGATORS_PER_COMMITTEE) as u64;
+    let digest = hash(slot_signature.as_ref());
+    let value = u64::from_le_bytes(digest[..8].try_into().expect("correct size"));
+    Ok(value % modulo == 0)
+}
+pub fn get_committee_assignment<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    epoch: Epoch,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<Option<(Vec<ValidatorIndex>, CommitteeIndex, Slot)>> {
+    let previous_epoch = get_previous_epoch(state, context);
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < previous_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch });
+    }
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let committee_count_per_slot = get_committee_count_per_slot(state, epoch, context);
+    for slot in start_slot..start_slot + context.slots_per_epoch {
+        for index in 0..committee_count_per_slot {
+            let committee = get_beacon_committee(state, slot, index, context)?;
+            if committee.contains(&validator_index) {
+                return Ok(Some((committee, index, slot)));
+            }
+        }
+    }
+    Ok(None)
+}
+pub fn get_attestation_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    epoch: Epoch,
+    validator_indices: &[ValidatorIndex],
+    context: &Context,
+) -> Result<Vec<AttestationDuty>> {
+    let previous_epoch = get_previous_epoch(state, context);
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < previous_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch });
+    }
+    let mut targets = HashSet::with_capacity(validator_indices.len());
+    for &index in validator_indices {
+        if index >= state.validators.len() {
+            return Err(Error::OutOfBounds { requested: index, bound: state.validators.len() });
+        }
+        targets.insert(index);
+    }
+    let mut duties = vec![];
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let committees_at_slot = get_committee_count_per_slot(state, epoch, context);
+    for slot in start_slot..start_slot + context.slots_per_epoch {
+        for committee_index in 0..committees_at_slot {
+            let committee = get_beacon_committee(state, slot, committee_index, context)?;
+            for (validator_committee_index, validator_index) in committee.iter().enumerate() {
+                if targets.contains(validator_index) {
+                    duties.push(AttestationDuty {
+                        public_key: state.validators[*validator_index].public_key.clone(),
+                        validator_index: *validator_index,
+                        committee_index,
+                        committee_length: committee.len(),
+                        committees_at_slot,
+                        validator_committee_index,
+                        slot,
+                    });
+                }
+            }
+        }
+    }
+    Ok(duties)
+}
+pub fn get_proposer_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    epoch: Epoch,
+    context: &Context,
+) -> Result<Vec<ProposerDuty>> {
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < current_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch });
+    }
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let advanced_state;
+    let state = if epoch > current_epoch {
+        let mut state = state.clone();
+        process_slots(&mut state, start_slot, context)?;
+        advanced_state = state;
+        &advanced_state
+    } else {
+        state
+    };
+    let seed = get_seed(state, epoch, DomainType::BeaconProposer, context);
+    let indices = get_active_validator_indices(state, epoch);
+    let mut input = [0u8; 40];
+    input[..32].copy_from_slice(seed.as_ref());
+    (start_slot..start_slot + context.slots_per_epoch)
+        .map(|slot| {
+            input[32..40].copy_from_slice(&slot.to_le_bytes());
+            let seed = hash(input);
+            let validator_index = compute_proposer_index(state, &indices, &seed, context)?;
+            Ok(ProposerDuty {
+                public_key: state.validators[validator_index].public_key.clone(),
+                validator_index,
+                slot,
+            })
+        })
+        .collect()
+}
 pub use crate::bellatrix::execution_engine::ExecutionEngine;
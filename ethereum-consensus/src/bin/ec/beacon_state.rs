@@ -0,0 +1,205 @@
+use ethereum_consensus::{
+    primitives::Slot, ssz::prelude::*, state_transition::Context, types::BeaconState, Fork,
+};
+use std::{fs, path::Path};
+
+// NOTE: `genesis_time` and `genesis_validators_root` precede the `slot` in every fork
+const SLOT_OFFSET: usize = 8 + 32;
+
+/// Reads the SSZ bytes in the file at `path`, decompressing them if the file ends in
+/// `.ssz_snappy` (using the snappy block format found in the consensus spec tests).
+pub fn read_ssz_bytes(path: &Path) -> eyre::Result<Vec<u8>> {
+    let data = fs::read(path)?;
+    if is_ssz_snappy(path) {
+        Ok(snap::raw::Decoder::new().decompress_vec(&data)?)
+    } else {
+        Ok(data)
+    }
+}
+
+/// Writes `bytes` to the file at `path`, compressing them if the file ends in `.ssz_snappy`.
+pub fn write_ssz_bytes(path: &Path, bytes: &[u8]) -> eyre::Result<()> {
+    if is_ssz_snappy(path) {
+        fs::write(path, snap::raw::Encoder::new().compress_vec(bytes)?)?;
+    } else {
+        fs::write(path, bytes)?;
+    }
+    Ok(())
+}
+
+pub fn is_json(path: &Path) -> bool {
+    path.extension().map(|extension| extension == "json").unwrap_or_default()
+}
+
+fn is_ssz_snappy(path: &Path) -> bool {
+    path.extension().map(|extension| extension == "ssz_snappy").unwrap_or_default()
+}
+
+/// Parses JSON `bytes` holding either the `data` of a beacon API response with its `version`, or
+/// a bare object whose fork is selected by the slot found at `slot_pointer`.
+pub fn parse_json(
+    bytes: &[u8],
+    slot_pointer: &str,
+    context: &Context,
+) -> eyre::Result<(Fork, serde_json::Value)> {
+    let mut value: serde_json::Value = serde_json::from_slice(bytes)?;
+    if let Some(version) = value.get("version") {
+        let fork = serde_json::from_value(version.clone())?;
+        let Some(data) = value.get_mut("data") else {
+            eyre::bail!("JSON input with a `version` is missing its `data`")
+        };
+        return Ok((fork, data.take()))
+    }
+    let Some(slot) = value.pointer(slot_pointer) else {
+        eyre::bail!("JSON input is missing `{slot_pointer}`")
+    };
+    let slot: Slot = match slot {
+        serde_json::Value::String(slot) => slot.parse()?,
+        slot => serde_json::from_value(slot.clone())?,
+    };
+    Ok((context.fork_for(slot), value))
+}
+
+pub fn fork_for_state(bytes: &[u8], context: &Context) -> eyre::Result<Fork> {
+    let Some(slot) = bytes.get(SLOT_OFFSET..SLOT_OFFSET + 8) else {
+        eyre::bail!("input of {} bytes is too short to be a `BeaconState`", bytes.len())
+    };
+    let slot = u64::from_le_bytes(slot.try_into().expect("is correct length"));
+    Ok(context.fork_for(slot))
+}
+
+/// Decodes a `BeaconState` from SSZ `bytes`, using the slot of the state to select its fork.
+pub fn decode<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    bytes: &[u8],
+    context: &Context,
+) -> eyre::Result<
+    BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+> {
+    let state = match fork_for_state(bytes, context)? {
+        Fork::Phase0 => BeaconState::Phase0(Deserialize::deserialize(bytes)?),
+        Fork::Altair => BeaconState::Altair(Deserialize::deserialize(bytes)?),
+        Fork::Bellatrix => BeaconState::Bellatrix(Deserialize::deserialize(bytes)?),
+        Fork::Capella => BeaconState::Capella(Deserialize::deserialize(bytes)?),
+        Fork::Deneb => BeaconState::Deneb(Deserialize::deserialize(bytes)?),
+    };
+    Ok(state)
+}
+
+/// Loads a `BeaconState` from the file at `path`, holding either JSON or (snappy-compressed) SSZ.
+pub fn load<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    path: &Path,
+    context: &Context,
+) -> eyre::Result<
+    BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+> {
+    if !is_json(path) {
+        return decode(&read_ssz_bytes(path)?, context)
+    }
+    let (fork, value) = parse_json(&fs::read(path)?, "/slot", context)?;
+    let state = match fork {
+        Fork::Phase0 => BeaconState::Phase0(serde_json::from_value(value)?),
+        Fork::Altair => BeaconState::Altair(serde_json::from_value(value)?),
+        Fork::Bellatrix => BeaconState::Bellatrix(serde_json::from_value(value)?),
+        Fork::Capella => BeaconState::Capella(serde_json::from_value(value)?),
+        Fork::Deneb => BeaconState::Deneb(serde_json::from_value(value)?),
+    };
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::{altair, bellatrix, capella, deneb, phase0, types::minimal};
+
+    #[test]
+    fn test_fork_for_state() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 1;
+        context.bellatrix_fork_epoch = 2;
+        context.capella_fork_epoch = 3;
+        context.deneb_fork_epoch = 4;
+        let slot_at = |epoch: u64| epoch * context.slots_per_epoch;
+
+        let states = [
+            minimal::BeaconState::Phase0(phase0::minimal::BeaconState {
+                slot: slot_at(0),
+                ..Default::default()
+            }),
+            minimal::BeaconState::Altair(altair::minimal::BeaconState {
+                slot: slot_at(1),
+                ..Default::default()
+            }),
+            minimal::BeaconState::Bellatrix(bellatrix::minimal::BeaconState {
+                slot: slot_at(2),
+                ..Default::default()
+            }),
+            minimal::BeaconState::Capella(capella::minimal::BeaconState {
+                slot: slot_at(3),
+                ..Default::default()
+            }),
+            minimal::BeaconState::Deneb(deneb::minimal::BeaconState {
+                slot: slot_at(4),
+                ..Default::default()
+            }),
+        ];
+        for (state, fork) in states.iter().zip(Fork::ALL) {
+            let mut bytes = serialize(state).unwrap();
+            assert_eq!(fork_for_state(&bytes, &context).unwrap(), fork);
+            let recovered: minimal::BeaconState = decode(&bytes, &context).unwrap();
+            assert_eq!(&recovered, state);
+
+            // the slot is read from `SLOT_OFFSET`
+            bytes[SLOT_OFFSET..SLOT_OFFSET + 8].copy_from_slice(&0u64.to_le_bytes());
+            assert_eq!(fork_for_state(&bytes, &context).unwrap(), Fork::Phase0);
+        }
+        assert!(fork_for_state(&[0u8; SLOT_OFFSET + 7], &context).is_err());
+    }
+}
@@ -1,18 +1,398 @@
-use clap::Args;
-use ethereum_consensus::crypto::SecretKey;
+use crate::{
+    network,
+    ssz::{hash_tree_root, parse_fork, Format},
+};
+use clap::{Args, Subcommand};
+use ethereum_consensus::{
+    crypto::{
+        aggregate, eth_aggregate_public_keys, fast_aggregate_verify, verify_signature, PublicKey,
+        SecretKey, Signature,
+    },
+    domains::DomainType,
+    phase0::{compute_domain, DepositMessage},
+    primitives::{Bytes32, Domain, Gwei, Root, Version},
+    signing::{verify_signed_data, SigningData},
+    ssz::prelude::*,
+    state_transition::Context,
+    Fork,
+};
 use rand::prelude::*;
+use std::path::PathBuf;
+
+fn parse_hex(input: &str) -> eyre::Result<Vec<u8>> {
+    Ok(hex::decode(input.strip_prefix("0x").unwrap_or(input))?)
+}
+
+fn parse_secret_key(input: &str) -> eyre::Result<SecretKey> {
+    Ok(SecretKey::try_from(parse_hex(input)?.as_ref())?)
+}
+
+fn parse_public_key(input: &str) -> eyre::Result<PublicKey> {
+    Ok(PublicKey::try_from(parse_hex(input)?.as_ref())?)
+}
+
+fn parse_signature(input: &str) -> eyre::Result<Signature> {
+    Ok(Signature::try_from(parse_hex(input)?.as_ref())?)
+}
+
+fn parse_fixed<const N: usize>(input: &str) -> eyre::Result<[u8; N]> {
+    let bytes = parse_hex(input)?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| eyre::eyre!("expected {N} bytes but found {}", bytes.len()))
+}
+
+fn parse_domain_type(input: &str) -> eyre::Result<DomainType> {
+    let name = input.trim_start_matches("DOMAIN_").to_lowercase().replace('-', "_");
+    let domain_type = match name.as_str() {
+        "beacon_proposer" => DomainType::BeaconProposer,
+        "beacon_attester" => DomainType::BeaconAttester,
+        "randao" => DomainType::Randao,
+        "deposit" => DomainType::Deposit,
+        "voluntary_exit" => DomainType::VoluntaryExit,
+        "selection_proof" => DomainType::SelectionProof,
+        "aggregate_and_proof" => DomainType::AggregateAndProof,
+        "sync_committee" => DomainType::SyncCommittee,
+        "sync_committee_selection_proof" => DomainType::SyncCommitteeSelectionProof,
+        "contribution_and_proof" => DomainType::ContributionAndProof,
+        "bls_to_execution_change" => DomainType::BlsToExecutionChange,
+        "blob_sidecar" => DomainType::BlobSidecar,
+        "application_builder" => DomainType::ApplicationBuilder,
+        _ => eyre::bail!("unknown domain type `{input}`"),
+    };
+    Ok(domain_type)
+}
 
 #[derive(Debug, Args)]
-#[clap(about = "generate a random BLS12-381 keypair")]
-pub struct Command;
+pub struct MessageArgs {
+    #[clap(
+        long,
+        required_unless_present_any = ["object_root", "object"],
+        conflicts_with_all = ["object_root", "object"],
+        help = "hex-encoded message to use as-is"
+    )]
+    message: Option<String>,
+    #[clap(long, help = "`hash_tree_root` of an SSZ object to compute the signing root for")]
+    object_root: Option<String>,
+    #[clap(
+        long,
+        conflicts_with = "object_root",
+        requires_all = ["container", "fork"],
+        help = "file with an SSZ object to compute the signing root for, in any `ec ssz` format"
+    )]
+    object: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "object",
+        help = "name of the container in `--object` as in the consensus specs, e.g. `VoluntaryExit`"
+    )]
+    container: Option<String>,
+    #[clap(
+        long,
+        requires = "object",
+        value_parser = parse_fork,
+        help = "fork of the container in `--object`, e.g. `deneb`"
+    )]
+    fork: Option<Fork>,
+    #[clap(
+        long,
+        requires = "object",
+        help = "format of `--object`, inferred from its extension by default"
+    )]
+    object_format: Option<Format>,
+    #[clap(long, conflicts_with = "domain_type", help = "hex-encoded domain for the signing root")]
+    domain: Option<String>,
+    #[clap(
+        long,
+        help = "domain type for the signing root, e.g. `beacon_attester` or `DOMAIN_DEPOSIT`"
+    )]
+    domain_type: Option<String>,
+    #[clap(long, help = "fork version for `--domain-type`, defaults to the genesis fork version")]
+    fork_version: Option<String>,
+    #[clap(long, help = "genesis validators root for `--domain-type`, defaults to zero")]
+    genesis_validators_root: Option<String>,
+    #[clap(
+        long,
+        default_value = "mainnet",
+        help = "network name, `minimal` or path to a custom config directory; its preset is used to decode `--object`"
+    )]
+    network: String,
+}
+
+impl MessageArgs {
+    fn domain(&self, context: &Context) -> eyre::Result<Domain> {
+        if let Some(domain) = self.domain.as_ref() {
+            return parse_fixed(domain)
+        }
+        let Some(domain_type) = self.domain_type.as_ref() else {
+            eyre::bail!("one of `--domain` or `--domain-type` is required to sign an object")
+        };
+        let domain_type = parse_domain_type(domain_type)?;
+        let fork_version: Option<Version> =
+            self.fork_version.as_deref().map(parse_fixed).transpose()?;
+        let genesis_validators_root = self
+            .genesis_validators_root
+            .as_deref()
+            .map(|root| -> eyre::Result<Root> { Ok(Root::try_from(parse_hex(root)?.as_ref())?) })
+            .transpose()?;
+        Ok(compute_domain(domain_type, fork_version, genesis_validators_root, context)?)
+    }
+
+    fn object_root(&self, context: &Context) -> eyre::Result<Root> {
+        if let Some(object_root) = self.object_root.as_deref() {
+            return Ok(Root::try_from(parse_hex(object_root)?.as_ref())?)
+        }
+        let object = self.object.as_deref().expect("clap requires one input");
+        let container = self.container.as_deref().expect("clap requires a container");
+        let fork = self.fork.expect("clap requires a fork");
+        let preset = network::preset_for(context)?;
+        let object_root = hash_tree_root(container, fork, preset, object, self.object_format)?;
+        eprintln!("object root: {object_root:?}");
+        Ok(object_root)
+    }
+
+    // Returns the message to sign, i.e. either the raw message or the signing root of the object.
+    fn resolve(&self) -> eyre::Result<Vec<u8>> {
+        if let Some(message) = self.message.as_ref() {
+            return parse_hex(message)
+        }
+        let context = network::context_for(self.network.clone())?;
+        let object_root = self.object_root(&context)?;
+        let mut signing_data = SigningData { object_root, domain: self.domain(&context)? };
+        let signing_root = signing_data.hash_tree_root()?;
+        eprintln!("signing root: {signing_root:?}");
+        Ok(signing_root.as_ref().to_vec())
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    #[clap(about = "generate a random BLS12-381 keypair")]
+    Random,
+    #[clap(about = "derive the public key of a secret key")]
+    PublicKey { secret_key: String },
+    #[clap(about = "sign a message or the signing root of an SSZ object")]
+    Sign {
+        secret_key: String,
+        #[clap(flatten)]
+        message: MessageArgs,
+    },
+    #[clap(about = "verify a signature over a message or the signing root of an SSZ object")]
+    Verify {
+        public_key: String,
+        signature: String,
+        #[clap(flatten)]
+        message: MessageArgs,
+    },
+    #[clap(about = "aggregate signatures")]
+    AggregateSignatures {
+        #[clap(required = true)]
+        signatures: Vec<String>,
+    },
+    #[clap(about = "aggregate public keys")]
+    AggregatePublicKeys {
+        #[clap(required = true)]
+        public_keys: Vec<String>,
+    },
+    #[clap(about = "verify an aggregate signature of many public keys over the same message")]
+    FastAggregateVerify {
+        signature: String,
+        #[clap(long, required = true, value_delimiter = ',')]
+        public_keys: Vec<String>,
+        #[clap(flatten)]
+        message: MessageArgs,
+    },
+    #[clap(
+        about = "verify the proof-of-possession of a public key given by the signature of its deposit"
+    )]
+    VerifyProofOfPossession {
+        public_key: String,
+        withdrawal_credentials: String,
+        amount: Gwei,
+        signature: String,
+        #[clap(
+            long,
+            default_value = "mainnet",
+            help = "network name, `minimal` or path to a custom config directory"
+        )]
+        network: String,
+    },
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "utilities for BLS12-381 keys and signatures")]
+pub struct Command {
+    #[clap(subcommand)]
+    pub command: Option<Commands>,
+}
 
 impl Command {
     pub fn execute(self) -> eyre::Result<()> {
-        let mut rng = thread_rng();
-        let secret_key = SecretKey::random(&mut rng).unwrap();
-        let public_key = secret_key.public_key();
-        println!("secret key: {secret_key:?}");
-        println!("public key: {public_key:?}");
+        match self.command.unwrap_or(Commands::Random) {
+            Commands::Random => {
+                let mut rng = thread_rng();
+                let secret_key = SecretKey::random(&mut rng).unwrap();
+                let public_key = secret_key.public_key();
+                println!("secret key: {secret_key:?}");
+                println!("public key: {public_key:?}");
+            }
+            Commands::PublicKey { secret_key } => {
+                let secret_key = parse_secret_key(&secret_key)?;
+                println!("{:?}", secret_key.public_key());
+            }
+            Commands::Sign { secret_key, message } => {
+                let secret_key = parse_secret_key(&secret_key)?;
+                let message = message.resolve()?;
+                println!("{:?}", secret_key.sign(&message));
+            }
+            Commands::Verify { public_key, signature, message } => {
+                let public_key = parse_public_key(&public_key)?;
+                let signature = parse_signature(&signature)?;
+                let message = message.resolve()?;
+                verify_signature(&public_key, &message, &signature)?;
+                println!("signature is valid");
+            }
+            Commands::AggregateSignatures { signatures } => {
+                let signatures = signatures
+                    .iter()
+                    .map(|signature| parse_signature(signature))
+                    .collect::<eyre::Result<Vec<_>>>()?;
+                println!("{:?}", aggregate(&signatures)?);
+            }
+            Commands::AggregatePublicKeys { public_keys } => {
+                let public_keys = public_keys
+                    .iter()
+                    .map(|public_key| parse_public_key(public_key))
+                    .collect::<eyre::Result<Vec<_>>>()?;
+                println!("{:?}", eth_aggregate_public_keys(&public_keys)?);
+            }
+            Commands::FastAggregateVerify { signature, public_keys, message } => {
+                let signature = parse_signature(&signature)?;
+                let public_keys = public_keys
+                    .iter()
+                    .map(|public_key| parse_public_key(public_key))
+                    .collect::<eyre::Result<Vec<_>>>()?;
+                let public_keys = public_keys.iter().collect::<Vec<_>>();
+                let message = message.resolve()?;
+                fast_aggregate_verify(&public_keys, &message, &signature)?;
+                println!("signature is valid");
+            }
+            Commands::VerifyProofOfPossession {
+                public_key,
+                withdrawal_credentials,
+                amount,
+                signature,
+                network,
+            } => {
+                let context = network::context_for(network)?;
+                let public_key = parse_public_key(&public_key)?;
+                let withdrawal_credentials =
+                    Bytes32::try_from(parse_hex(&withdrawal_credentials)?.as_ref())?;
+                let signature = parse_signature(&signature)?;
+                let mut deposit_message = DepositMessage {
+                    public_key: public_key.clone(),
+                    withdrawal_credentials,
+                    amount,
+                };
+                // NOTE: deposits are valid across forks so they always use the genesis fork version
+                let domain = compute_domain(DomainType::Deposit, None, None, &context)?;
+                verify_signed_data(&mut deposit_message, &signature, &public_key, domain)?;
+                println!("proof of possession is valid");
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::signing::compute_signing_root;
+
+    fn message_args(object_root: Root, domain_type: &str) -> MessageArgs {
+        MessageArgs {
+            message: None,
+            object_root: Some(hex::encode(object_root)),
+            object: None,
+            container: None,
+            fork: None,
+            object_format: None,
+            domain: None,
+            domain_type: Some(domain_type.to_string()),
+            fork_version: None,
+            genesis_validators_root: None,
+            network: "mainnet".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_domain_type() {
+        for input in ["DOMAIN_BEACON_ATTESTER", "beacon_attester", "beacon-attester"] {
+            let domain_type = parse_domain_type(input).unwrap();
+            assert_eq!(domain_type.as_bytes(), [1, 0, 0, 0]);
+        }
+        let domain_type = parse_domain_type("DOMAIN_DEPOSIT").unwrap();
+        assert_eq!(domain_type.as_bytes(), [3, 0, 0, 0]);
+        assert!(parse_domain_type("DOMAIN_UNKNOWN").is_err());
+    }
+
+    #[test]
+    fn test_resolve_deposit_signing_root() {
+        let context = Context::for_mainnet();
+        let secret_key = SecretKey::random(&mut thread_rng()).unwrap();
+        let mut deposit_message = DepositMessage {
+            public_key: secret_key.public_key(),
+            withdrawal_credentials: Bytes32::default(),
+            amount: 32_000_000_000,
+        };
+        let domain = compute_domain(DomainType::Deposit, None, None, &context).unwrap();
+        let expected = compute_signing_root(&mut deposit_message, domain).unwrap();
+
+        let object_root = deposit_message.hash_tree_root().unwrap();
+        let mut args = message_args(object_root, "deposit");
+        assert_eq!(args.domain(&context).unwrap(), domain);
+        let message = args.resolve().unwrap();
+        assert_eq!(message, expected.as_ref());
+
+        // a signature over the resolved message is a valid proof-of-possession
+        let signature = secret_key.sign(&message);
+        let public_key = secret_key.public_key();
+        verify_signed_data(&mut deposit_message, &signature, &public_key, domain).unwrap();
+
+        // an explicit domain gives the same signing root
+        args.domain_type = None;
+        args.domain = Some(format!("0x{}", hex::encode(domain)));
+        assert_eq!(args.resolve().unwrap(), expected.as_ref());
+
+        // as opposed to another domain type
+        let args = message_args(object_root, "DOMAIN_VOLUNTARY_EXIT");
+        assert_ne!(args.resolve().unwrap(), expected.as_ref());
+    }
+
+    #[test]
+    fn test_resolve_object_with_network_preset() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("block.ssz");
+        let mut block = ethereum_consensus::deneb::minimal::SignedBeaconBlock::default();
+        block.message.slot = 42;
+        std::fs::write(&path, serialize(&block).unwrap()).unwrap();
+        let object_root = block.hash_tree_root().unwrap();
+
+        let mut args = message_args(object_root, "beacon_proposer");
+        args.object_root = None;
+        args.object = Some(path);
+        args.container = Some("SignedBeaconBlock".to_string());
+        args.fork = Some(Fork::Deneb);
+        // the object does not decode with the types of the default `mainnet` network
+        assert!(args.resolve().is_err());
+
+        args.network = "minimal".to_string();
+        let context = Context::for_minimal();
+        assert_eq!(args.object_root(&context).unwrap(), object_root);
+        let domain = compute_domain(DomainType::BeaconProposer, None, None, &context).unwrap();
+        let mut signing_data = SigningData { object_root, domain };
+        let expected = signing_data.hash_tree_root().unwrap();
+        assert_eq!(args.resolve().unwrap(), expected.as_ref());
+    }
+}
@@ -1,4 +1,9 @@
+mod beacon_state;
 mod bls;
+mod network;
+mod ssz;
+mod state;
+mod transition;
 mod validator;
 
 use clap::{Parser, Subcommand};
@@ -7,6 +12,9 @@ use clap::{Parser, Subcommand};
 pub enum Commands {
     Validator(validator::Command),
     Bls(bls::Command),
+    State(state::Command),
+    Transition(transition::Command),
+    Ssz(ssz::Command),
 }
 
 #[derive(Debug, Parser)]
@@ -22,5 +30,8 @@ fn main() -> eyre::Result<()> {
     match cli.command {
         Commands::Validator(cmd) => cmd.execute(),
         Commands::Bls(cmd) => cmd.execute(),
+        Commands::State(cmd) => cmd.execute(),
+        Commands::Transition(cmd) => cmd.execute(),
+        Commands::Ssz(cmd) => cmd.execute(),
     }
 }
@@ -0,0 +1,44 @@
+use ethereum_consensus::{
+    networks::Network,
+    state_transition::{gnosis, mainnet, minimal, Context},
+};
+
+/// Builds the `Context` for `network`, which is either the name of a known network, `minimal` for
+/// the minimal preset and config, or the path to a custom config directory.
+pub fn context_for(network: String) -> eyre::Result<Context> {
+    if network == "minimal" {
+        return Ok(Context::for_minimal())
+    }
+    Ok(Context::try_from(Network::from(network))?)
+}
+
+/// Returns the `PRESET_BASE` of `context` after checking it can be used with the types of that
+/// compile-time preset, i.e. `mainnet`, `minimal` or `gnosis`.
+pub fn preset_for(context: &Context) -> eyre::Result<&str> {
+    match context.preset_base.as_str() {
+        "mainnet" => mainnet::check_compatibility(context)?,
+        "minimal" => minimal::check_compatibility(context)?,
+        "gnosis" => gnosis::check_compatibility(context)?,
+        other => eyre::bail!("unsupported preset `{other}`, expected mainnet, minimal or gnosis"),
+    }
+    Ok(&context.preset_base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_for() {
+        let mut context = context_for("minimal".to_string()).unwrap();
+        // values only read at runtime may be overridden
+        context.shuffle_round_count = 1;
+        assert_eq!(preset_for(&context).unwrap(), "minimal");
+
+        context.sync_committee_size = 512;
+        assert!(preset_for(&context).is_err());
+
+        context.preset_base = "custom".to_string();
+        assert!(preset_for(&context).is_err());
+    }
+}
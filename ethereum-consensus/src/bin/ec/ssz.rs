@@ -0,0 +1,444 @@
+use clap::{Args, Subcommand, ValueEnum};
+use ethereum_consensus::{
+    altair, bellatrix, capella, deneb, phase0,
+    primitives::Root,
+    ssz::prelude::{serialize, Deserialize, SimpleSerialize},
+    Fork,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// SSZ bytes
+    Ssz,
+    /// SSZ bytes compressed with the snappy block format, as found in the consensus spec tests
+    SszSnappy,
+    /// SSZ bytes compressed with the snappy frame format, as found in the networking protocols
+    SszSnappyFramed,
+    /// JSON following the beacon node API, optionally wrapped in its `version` and `data`
+    Json,
+    /// YAML, as found in the consensus spec tests
+    Yaml,
+}
+
+impl Format {
+    // NOTE: the snappy frame format has no conventional extension so must be requested explicitly
+    fn from_path(path: &Path) -> eyre::Result<Self> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        let format = match extension {
+            Some("ssz") => Self::Ssz,
+            Some("ssz_snappy") => Self::SszSnappy,
+            Some("json") => Self::Json,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => eyre::bail!(
+                "could not infer the format of {} from its extension, please provide it explicitly",
+                path.display()
+            ),
+        };
+        Ok(format)
+    }
+}
+
+fn read<T: SimpleSerialize + DeserializeOwned>(path: &Path, format: Format) -> eyre::Result<T> {
+    let data = fs::read(path)?;
+    let value = match format {
+        Format::Ssz => <T as Deserialize>::deserialize(&data)?,
+        Format::SszSnappy => {
+            let bytes = snap::raw::Decoder::new().decompress_vec(&data)?;
+            <T as Deserialize>::deserialize(&bytes)?
+        }
+        Format::SszSnappyFramed => {
+            let mut bytes = vec![];
+            snap::read::FrameDecoder::new(data.as_slice()).read_to_end(&mut bytes)?;
+            <T as Deserialize>::deserialize(&bytes)?
+        }
+        Format::Json => {
+            let mut value: serde_json::Value = serde_json::from_slice(&data)?;
+            if value.get("version").is_some() {
+                if let Some(data) = value.get_mut("data") {
+                    value = data.take();
+                }
+            }
+            serde_json::from_value(value)?
+        }
+        Format::Yaml => serde_yaml::from_slice(&data)?,
+    };
+    Ok(value)
+}
+
+fn write<T: SimpleSerialize + Serialize>(
+    value: &T,
+    path: &Path,
+    format: Format,
+) -> eyre::Result<()> {
+    match format {
+        Format::Ssz => fs::write(path, serialize(value)?)?,
+        Format::SszSnappy => {
+            fs::write(path, snap::raw::Encoder::new().compress_vec(&serialize(value)?)?)?
+        }
+        Format::SszSnappyFramed => {
+            let mut encoder = snap::write::FrameEncoder::new(fs::File::create(path)?);
+            encoder.write_all(&serialize(value)?)?;
+            encoder.flush()?;
+        }
+        Format::Json => fs::write(path, serde_json::to_string_pretty(value)?)?,
+        Format::Yaml => fs::write(path, serde_yaml::to_string(value)?)?,
+    }
+    Ok(())
+}
+
+// Calls `$f` with the type of `$container` at `$fork` for the preset named by the module `$preset`.
+// Containers that do not change across forks resolve to the type of the fork that introduced them.
+macro_rules! with_container_type {
+    ($preset:ident, $fork:expr, $container:expr, $f:ident($($arg:expr),*)) => {
+        match ($fork, $container) {
+            (Fork::Phase0, "BeaconState") => $f::<phase0::$preset::BeaconState>($($arg),*),
+            (Fork::Altair, "BeaconState") => $f::<altair::$preset::BeaconState>($($arg),*),
+            (Fork::Bellatrix, "BeaconState") => $f::<bellatrix::$preset::BeaconState>($($arg),*),
+            (Fork::Capella, "BeaconState") => $f::<capella::$preset::BeaconState>($($arg),*),
+            (Fork::Deneb, "BeaconState") => $f::<deneb::$preset::BeaconState>($($arg),*),
+            (Fork::Phase0, "BeaconBlockBody") => $f::<phase0::$preset::BeaconBlockBody>($($arg),*),
+            (Fork::Altair, "BeaconBlockBody") => $f::<altair::$preset::BeaconBlockBody>($($arg),*),
+            (Fork::Bellatrix, "BeaconBlockBody") => {
+                $f::<bellatrix::$preset::BeaconBlockBody>($($arg),*)
+            }
+            (Fork::Capella, "BeaconBlockBody") => $f::<capella::$preset::BeaconBlockBody>($($arg),*),
+            (Fork::Deneb, "BeaconBlockBody") => $f::<deneb::$preset::BeaconBlockBody>($($arg),*),
+            (Fork::Phase0, "BeaconBlock") => $f::<phase0::$preset::BeaconBlock>($($arg),*),
+            (Fork::Altair, "BeaconBlock") => $f::<altair::$preset::BeaconBlock>($($arg),*),
+            (Fork::Bellatrix, "BeaconBlock") => $f::<bellatrix::$preset::BeaconBlock>($($arg),*),
+            (Fork::Capella, "BeaconBlock") => $f::<capella::$preset::BeaconBlock>($($arg),*),
+            (Fork::Deneb, "BeaconBlock") => $f::<deneb::$preset::BeaconBlock>($($arg),*),
+            (Fork::Phase0, "SignedBeaconBlock") => {
+                $f::<phase0::$preset::SignedBeaconBlock>($($arg),*)
+            }
+            (Fork::Altair, "SignedBeaconBlock") => {
+                $f::<altair::$preset::SignedBeaconBlock>($($arg),*)
+            }
+            (Fork::Bellatrix, "SignedBeaconBlock") => {
+                $f::<bellatrix::$preset::SignedBeaconBlock>($($arg),*)
+            }
+            (Fork::Capella, "SignedBeaconBlock") => {
+                $f::<capella::$preset::SignedBeaconBlock>($($arg),*)
+            }
+            (Fork::Deneb, "SignedBeaconBlock") => {
+                $f::<deneb::$preset::SignedBeaconBlock>($($arg),*)
+            }
+            (Fork::Bellatrix, "ExecutionPayload") => {
+                $f::<bellatrix::$preset::ExecutionPayload>($($arg),*)
+            }
+            (Fork::Capella, "ExecutionPayload") => {
+                $f::<capella::$preset::ExecutionPayload>($($arg),*)
+            }
+            (Fork::Deneb, "ExecutionPayload") => $f::<deneb::$preset::ExecutionPayload>($($arg),*),
+            (Fork::Bellatrix, "ExecutionPayloadHeader") => {
+                $f::<bellatrix::$preset::ExecutionPayloadHeader>($($arg),*)
+            }
+            (Fork::Capella, "ExecutionPayloadHeader") => {
+                $f::<capella::$preset::ExecutionPayloadHeader>($($arg),*)
+            }
+            (Fork::Deneb, "ExecutionPayloadHeader") => {
+                $f::<deneb::$preset::ExecutionPayloadHeader>($($arg),*)
+            }
+            (Fork::Bellatrix, "BlindedBeaconBlockBody") => {
+                $f::<bellatrix::$preset::BlindedBeaconBlockBody>($($arg),*)
+            }
+            (Fork::Capella, "BlindedBeaconBlockBody") => {
+                $f::<capella::$preset::BlindedBeaconBlockBody>($($arg),*)
+            }
+            (Fork::Deneb, "BlindedBeaconBlockBody") => {
+                $f::<deneb::$preset::BlindedBeaconBlockBody>($($arg),*)
+            }
+            (Fork::Bellatrix, "BlindedBeaconBlock") => {
+                $f::<bellatrix::$preset::BlindedBeaconBlock>($($arg),*)
+            }
+            (Fork::Capella, "BlindedBeaconBlock") => {
+                $f::<capella::$preset::BlindedBeaconBlock>($($arg),*)
+            }
+            (Fork::Deneb, "BlindedBeaconBlock") => {
+                $f::<deneb::$preset::BlindedBeaconBlock>($($arg),*)
+            }
+            (Fork::Bellatrix, "SignedBlindedBeaconBlock") => {
+                $f::<bellatrix::$preset::SignedBlindedBeaconBlock>($($arg),*)
+            }
+            (Fork::Capella, "SignedBlindedBeaconBlock") => {
+                $f::<capella::$preset::SignedBlindedBeaconBlock>($($arg),*)
+            }
+            (Fork::Deneb, "SignedBlindedBeaconBlock") => {
+                $f::<deneb::$preset::SignedBlindedBeaconBlock>($($arg),*)
+            }
+            // NOTE: the light client containers change in capella, which this crate does not
+            // implement yet
+            (Fork::Altair | Fork::Bellatrix, "LightClientHeader") => {
+                $f::<altair::LightClientHeader>($($arg),*)
+            }
+            (Fork::Altair | Fork::Bellatrix, "LightClientBootstrap") => {
+                $f::<altair::$preset::LightClientBootstrap>($($arg),*)
+            }
+            (Fork::Altair | Fork::Bellatrix, "LightClientUpdate") => {
+                $f::<altair::$preset::LightClientUpdate>($($arg),*)
+            }
+            (Fork::Altair | Fork::Bellatrix, "LightClientFinalityUpdate") => {
+                $f::<altair::$preset::LightClientFinalityUpdate>($($arg),*)
+            }
+            (Fork::Altair | Fork::Bellatrix, "LightClientOptimisticUpdate") => {
+                $f::<altair::$preset::LightClientOptimisticUpdate>($($arg),*)
+            }
+            (Fork::Deneb, "BlobSidecar") => $f::<deneb::$preset::BlobSidecar>($($arg),*),
+            (Fork::Deneb, "BlobIdentifier") => $f::<deneb::BlobIdentifier>($($arg),*),
+            (fork, "Withdrawal") if fork >= Fork::Capella => $f::<capella::Withdrawal>($($arg),*),
+            (fork, "BlsToExecutionChange") if fork >= Fork::Capella => {
+                $f::<capella::BlsToExecutionChange>($($arg),*)
+            }
+            (fork, "SignedBlsToExecutionChange") if fork >= Fork::Capella => {
+                $f::<capella::SignedBlsToExecutionChange>($($arg),*)
+            }
+            (fork, "HistoricalSummary") if fork >= Fork::Capella => {
+                $f::<capella::HistoricalSummary>($($arg),*)
+            }
+            (fork, "PowBlock") if fork >= Fork::Bellatrix => $f::<bellatrix::PowBlock>($($arg),*),
+            (fork, "SyncAggregate") if fork >= Fork::Altair => {
+                $f::<altair::$preset::SyncAggregate>($($arg),*)
+            }
+            (fork, "SyncCommittee") if fork >= Fork::Altair => {
+                $f::<altair::$preset::SyncCommittee>($($arg),*)
+            }
+            (fork, "SyncCommitteeMessage") if fork >= Fork::Altair => {
+                $f::<altair::SyncCommitteeMessage>($($arg),*)
+            }
+            (fork, "SyncCommitteeContribution") if fork >= Fork::Altair => {
+                $f::<altair::$preset::SyncCommitteeContribution>($($arg),*)
+            }
+            (fork, "ContributionAndProof") if fork >= Fork::Altair => {
+                $f::<altair::$preset::ContributionAndProof>($($arg),*)
+            }
+            (fork, "SignedContributionAndProof") if fork >= Fork::Altair => {
+                $f::<altair::$preset::SignedContributionAndProof>($($arg),*)
+            }
+            (fork, "SyncAggregatorSelectionData") if fork >= Fork::Altair => {
+                $f::<altair::SyncAggregatorSelectionData>($($arg),*)
+            }
+            (_, "Attestation") => $f::<phase0::$preset::Attestation>($($arg),*),
+            (_, "IndexedAttestation") => $f::<phase0::$preset::IndexedAttestation>($($arg),*),
+            (_, "PendingAttestation") => $f::<phase0::$preset::PendingAttestation>($($arg),*),
+            (_, "AttesterSlashing") => $f::<phase0::$preset::AttesterSlashing>($($arg),*),
+            (_, "AggregateAndProof") => $f::<phase0::$preset::AggregateAndProof>($($arg),*),
+            (_, "SignedAggregateAndProof") => {
+                $f::<phase0::$preset::SignedAggregateAndProof>($($arg),*)
+            }
+            (_, "HistoricalBatch") => $f::<phase0::$preset::HistoricalBatch>($($arg),*),
+            (_, "AttestationData") => $f::<phase0::AttestationData>($($arg),*),
+            (_, "BeaconBlockHeader") => $f::<phase0::BeaconBlockHeader>($($arg),*),
+            (_, "SignedBeaconBlockHeader") => $f::<phase0::SignedBeaconBlockHeader>($($arg),*),
+            (_, "Checkpoint") => $f::<phase0::Checkpoint>($($arg),*),
+            (_, "Deposit") => $f::<phase0::Deposit>($($arg),*),
+            (_, "DepositData") => $f::<phase0::DepositData>($($arg),*),
+            (_, "DepositMessage") => $f::<phase0::DepositMessage>($($arg),*),
+            (_, "Eth1Block") => $f::<phase0::Eth1Block>($($arg),*),
+            (_, "Eth1Data") => $f::<phase0::Eth1Data>($($arg),*),
+            (_, "Fork") => $f::<phase0::Fork>($($arg),*),
+            (_, "ForkData") => $f::<phase0::ForkData>($($arg),*),
+            (_, "ProposerSlashing") => $f::<phase0::ProposerSlashing>($($arg),*),
+            (_, "SignedVoluntaryExit") => $f::<phase0::SignedVoluntaryExit>($($arg),*),
+            (_, "Validator") => $f::<phase0::Validator>($($arg),*),
+            (_, "VoluntaryExit") => $f::<phase0::VoluntaryExit>($($arg),*),
+            (fork, container) => eyre::bail!("unknown container `{container}` for fork {fork}"),
+        }
+    };
+}
+
+pub fn parse_fork(input: &str) -> Result<Fork, String> {
+    Fork::ALL
+        .into_iter()
+        .find(|fork| fork.to_string() == input)
+        .ok_or_else(|| format!("unknown fork `{input}`"))
+}
+
+#[derive(Debug, Args)]
+pub struct ContainerArgs {
+    #[clap(help = "name of the container as in the consensus specs, e.g. `SignedBeaconBlock`")]
+    container: String,
+    #[clap(long, value_parser = parse_fork, help = "fork of the container, e.g. `deneb`")]
+    fork: Fork,
+    #[clap(
+        long,
+        default_value = "mainnet",
+        value_parser = ["mainnet", "minimal", "gnosis"],
+        help = "preset of the container"
+    )]
+    preset: String,
+}
+
+impl ContainerArgs {
+    fn run(
+        &self,
+        input: &Path,
+        input_format: Format,
+        output: Option<(&Path, Format)>,
+    ) -> eyre::Result<()> {
+        let container = self.container.as_str();
+        match self.preset.as_str() {
+            "minimal" => {
+                with_container_type!(
+                    minimal,
+                    self.fork,
+                    container,
+                    convert(input, input_format, output)
+                )
+            }
+            "gnosis" => {
+                with_container_type!(
+                    gnosis,
+                    self.fork,
+                    container,
+                    convert(input, input_format, output)
+                )
+            }
+            _ => with_container_type!(
+                mainnet,
+                self.fork,
+                container,
+                convert(input, input_format, output)
+            ),
+        }
+    }
+}
+
+// Reads a `T` from `input`, prints its `hash_tree_root` and writes it to `output`, if provided.
+fn convert<T: SimpleSerialize + Serialize + DeserializeOwned>(
+    input: &Path,
+    input_format: Format,
+    output: Option<(&Path, Format)>,
+) -> eyre::Result<()> {
+    let mut value: T = read(input, input_format)?;
+    println!("{:?}", value.hash_tree_root()?);
+    if let Some((path, format)) = output {
+        write(&value, path, format)?;
+    }
+    Ok(())
+}
+
+// Reads a `T` from `input` and returns its `hash_tree_root`.
+fn root<T: SimpleSerialize + DeserializeOwned>(
+    input: &Path,
+    input_format: Format,
+) -> eyre::Result<Root> {
+    let mut value: T = read(input, input_format)?;
+    Ok(value.hash_tree_root()?)
+}
+
+/// Computes the `hash_tree_root` of the `container` at `fork` for `preset` read from `input`,
+/// inferring the format from its extension if `input_format` is not provided.
+pub fn hash_tree_root(
+    container: &str,
+    fork: Fork,
+    preset: &str,
+    input: &Path,
+    input_format: Option<Format>,
+) -> eyre::Result<Root> {
+    let input_format = input_format.map(Ok).unwrap_or_else(|| Format::from_path(input))?;
+    match preset {
+        "minimal" => with_container_type!(minimal, fork, container, root(input, input_format)),
+        "gnosis" => with_container_type!(gnosis, fork, container, root(input, input_format)),
+        _ => with_container_type!(mainnet, fork, container, root(input, input_format)),
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    #[clap(about = "convert a container between formats and print its `hash_tree_root`")]
+    Convert {
+        #[clap(flatten)]
+        container: ContainerArgs,
+        input: PathBuf,
+        output: PathBuf,
+        #[clap(long, help = "format of the input, inferred from its extension by default")]
+        input_format: Option<Format>,
+        #[clap(long, help = "format of the output, inferred from its extension by default")]
+        output_format: Option<Format>,
+    },
+    #[clap(about = "print the `hash_tree_root` of a container")]
+    HashTreeRoot {
+        #[clap(flatten)]
+        container: ContainerArgs,
+        input: PathBuf,
+        #[clap(long, help = "format of the input, inferred from its extension by default")]
+        input_format: Option<Format>,
+    },
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "utilities for SSZ containers")]
+pub struct Command {
+    #[clap(subcommand)]
+    pub command: Commands,
+}
+
+impl Command {
+    pub fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Commands::Convert { container, input, output, input_format, output_format } => {
+                let input_format =
+                    input_format.map(Ok).unwrap_or_else(|| Format::from_path(&input))?;
+                let output_format =
+                    output_format.map(Ok).unwrap_or_else(|| Format::from_path(&output))?;
+                container.run(&input, input_format, Some((&output, output_format)))
+            }
+            Commands::HashTreeRoot { container, input, input_format } => {
+                let input_format =
+                    input_format.map(Ok).unwrap_or_else(|| Format::from_path(&input))?;
+                container.run(&input, input_format, None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::ssz::prelude::Merkleized;
+
+    #[test]
+    fn test_format_roundtrips() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut block = deneb::minimal::SignedBeaconBlock::default();
+        block.message.slot = 42;
+        block.message.proposer_index = 7;
+        block.message.body.graffiti[0] = 0xec;
+        let expected = block.hash_tree_root().unwrap();
+
+        for (file_name, format) in [
+            ("block.ssz", Format::Ssz),
+            ("block.ssz_snappy", Format::SszSnappy),
+            ("block.ssz_snappy_framed", Format::SszSnappyFramed),
+            ("block.json", Format::Json),
+            ("block.yaml", Format::Yaml),
+        ] {
+            let path = temp_dir.path().join(file_name);
+            write(&block, &path, format).unwrap();
+            let root =
+                hash_tree_root("SignedBeaconBlock", Fork::Deneb, "minimal", &path, Some(format))
+                    .unwrap();
+            assert_eq!(root, expected, "{file_name}");
+        }
+
+        // the format is inferred from the extension when it has a conventional one
+        let path = temp_dir.path().join("block.ssz_snappy");
+        let root = hash_tree_root("SignedBeaconBlock", Fork::Deneb, "minimal", &path, None);
+        assert_eq!(root.unwrap(), expected);
+        let path = temp_dir.path().join("block.ssz_snappy_framed");
+        assert!(hash_tree_root("SignedBeaconBlock", Fork::Deneb, "minimal", &path, None).is_err());
+
+        // the same bytes do not decode as the container of another preset or fork
+        let path = temp_dir.path().join("block.ssz");
+        assert!(hash_tree_root("SignedBeaconBlock", Fork::Deneb, "mainnet", &path, None).is_err());
+        let result = hash_tree_root("SignedBeaconBlock", Fork::Capella, "minimal", &path, None);
+        assert!(result.is_err());
+        assert!(hash_tree_root("BlobSidecar", Fork::Capella, "minimal", &path, None).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+use crate::{beacon_state, network};
+use clap::{Args, Subcommand};
+use ethereum_consensus::{
+    altair, bellatrix,
+    capella::{
+        self,
+        helpers::{is_fully_withdrawable_validator, is_partially_withdrawable_validator},
+    },
+    deneb,
+    phase0::{
+        self, compute_epoch_at_slot, compute_start_slot_at_epoch, is_active_validator, Validator,
+    },
+    primitives::{
+        BlsPublicKey, CommitteeIndex, Epoch, Gwei, Slot, ValidatorIndex, FAR_FUTURE_EPOCH,
+    },
+    state_transition::Context,
+    types::{gnosis, mainnet, minimal, BeaconState},
+    Error,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+
+/// The status of a validator, as defined by the beacon node API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValidatorStatus {
+    PendingInitialized,
+    PendingQueued,
+    ActiveOngoing,
+    ActiveExiting,
+    ActiveSlashed,
+    ExitedUnslashed,
+    ExitedSlashed,
+    WithdrawalPossible,
+    WithdrawalDone,
+}
+
+impl ValidatorStatus {
+    pub fn new(validator: &Validator, balance: Gwei, epoch: Epoch) -> Self {
+        if epoch < validator.activation_epoch {
+            if validator.activation_eligibility_epoch == FAR_FUTURE_EPOCH {
+                Self::PendingInitialized
+            } else {
+                Self::PendingQueued
+            }
+        } else if epoch < validator.exit_epoch {
+            if validator.exit_epoch == FAR_FUTURE_EPOCH {
+                Self::ActiveOngoing
+            } else if validator.slashed {
+                Self::ActiveSlashed
+            } else {
+                Self::ActiveExiting
+            }
+        } else if epoch < validator.withdrawable_epoch {
+            if validator.slashed {
+                Self::ExitedSlashed
+            } else {
+                Self::ExitedUnslashed
+            }
+        } else if balance != 0 {
+            Self::WithdrawalPossible
+        } else {
+            Self::WithdrawalDone
+        }
+    }
+}
+
+impl fmt::Display for ValidatorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::PendingInitialized => "pending_initialized",
+            Self::PendingQueued => "pending_queued",
+            Self::ActiveOngoing => "active_ongoing",
+            Self::ActiveExiting => "active_exiting",
+            Self::ActiveSlashed => "active_slashed",
+            Self::ExitedUnslashed => "exited_unslashed",
+            Self::ExitedSlashed => "exited_slashed",
+            Self::WithdrawalPossible => "withdrawal_possible",
+            Self::WithdrawalDone => "withdrawal_done",
+        };
+        write!(f, "{status}")
+    }
+}
+
+// Matches the entries returned by the `/eth/v1/beacon/states/{state_id}/committees` endpoint.
+#[derive(Debug, Serialize)]
+struct Committee {
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    index: CommitteeIndex,
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    slot: Slot,
+    #[serde(with = "ethereum_consensus::serde::seq_of_str")]
+    validators: Vec<ValidatorIndex>,
+}
+
+fn collect_committees(
+    epoch: Epoch,
+    committees_per_slot: usize,
+    context: &Context,
+    get_beacon_committee: impl Fn(Slot, CommitteeIndex) -> Result<Vec<ValidatorIndex>, Error>,
+) -> eyre::Result<Vec<Committee>> {
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let mut committees = Vec::with_capacity(committees_per_slot * context.slots_per_epoch as usize);
+    for slot in start_slot..start_slot + context.slots_per_epoch {
+        for index in 0..committees_per_slot {
+            let validators = get_beacon_committee(slot, index)?;
+            committees.push(Committee { index, slot, validators });
+        }
+    }
+    Ok(committees)
+}
+
+// Returns the number of validators the withdrawal sweep visits before reaching `validator_index`,
+// if `state` is from a fork with withdrawals.
+fn withdrawal_sweep_position<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    validator_index: ValidatorIndex,
+) -> Option<usize> {
+    let next_validator_index = state.next_withdrawal_validator_index()?;
+    let validator_count = state.validators().len();
+    Some((validator_index + validator_count - next_validator_index) % validator_count)
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    #[clap(about = "print an overview of the state")]
+    Summary,
+    #[clap(about = "print the details of a validator in the state")]
+    Validator {
+        #[clap(help = "index or hex-encoded public key of the validator")]
+        id: String,
+    },
+    #[clap(about = "print the beacon committees for an epoch as JSON")]
+    Committees {
+        #[clap(help = "epoch between the previous and next epoch of the state, inclusive")]
+        epoch: Epoch,
+    },
+    #[clap(about = "print the block proposers for an epoch as JSON")]
+    Proposers {
+        #[clap(help = "either the current or the next epoch of the state")]
+        epoch: Epoch,
+    },
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "inspect an SSZ-encoded `BeaconState`")]
+pub struct Command {
+    #[clap(help = "path to the state, snappy-compressed if the file ends in `.ssz_snappy`")]
+    path: PathBuf,
+    #[clap(
+        long,
+        default_value = "mainnet",
+        help = "network name, `minimal` or path to a custom config directory"
+    )]
+    network: String,
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+impl Command {
+    pub fn execute(self) -> eyre::Result<()> {
+        let context = network::context_for(self.network)?;
+        let bytes = beacon_state::read_ssz_bytes(&self.path)?;
+        let command = self.command.unwrap_or(Commands::Summary);
+        match network::preset_for(&context)? {
+            "minimal" => {
+                let state: minimal::BeaconState = beacon_state::decode(&bytes, &context)?;
+                inspect(&state, command, &context)
+            }
+            "gnosis" => {
+                let state: gnosis::BeaconState = beacon_state::decode(&bytes, &context)?;
+                inspect(&state, command, &context)
+            }
+            "mainnet" => {
+                let state: mainnet::BeaconState = beacon_state::decode(&bytes, &context)?;
+                inspect(&state, command, &context)
+            }
+            _ => eyre::bail!("the network does not use the mainnet, minimal or gnosis presets"),
+        }
+    }
+}
+
+fn inspect<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    command: Commands,
+    context: &Context,
+) -> eyre::Result<()> {
+    let current_epoch = compute_epoch_at_slot(state.slot(), context);
+    match command {
+        Commands::Summary => {
+            println!("fork: {}", state.version());
+            println!("slot: {} (epoch {current_epoch})", state.slot());
+            println!("genesis validators root: {:?}", state.genesis_validators_root());
+            for (name, checkpoint) in [
+                ("finalized", state.finalized_checkpoint()),
+                ("current justified", state.current_justified_checkpoint()),
+                ("previous justified", state.previous_justified_checkpoint()),
+            ] {
+                println!("{name} checkpoint: {:?} @ epoch {}", checkpoint.root, checkpoint.epoch);
+            }
+
+            let mut statuses = BTreeMap::<ValidatorStatus, usize>::new();
+            let mut total_active_balance: Gwei = 0;
+            let mut fully_withdrawable = 0;
+            let mut partially_withdrawable = 0;
+            for (validator, &balance) in state.validators().iter().zip(state.balances().iter()) {
+                *statuses
+                    .entry(ValidatorStatus::new(validator, balance, current_epoch))
+                    .or_default() += 1;
+                if is_active_validator(validator, current_epoch) {
+                    total_active_balance += validator.effective_balance;
+                }
+                if is_fully_withdrawable_validator(validator, balance, current_epoch) {
+                    fully_withdrawable += 1;
+                } else if is_partially_withdrawable_validator(validator, balance, context) {
+                    partially_withdrawable += 1;
+                }
+            }
+            let total_active_balance =
+                Gwei::max(context.effective_balance_increment, total_active_balance);
+            println!("validators: {}", state.validators().len());
+            for (status, count) in statuses {
+                println!("  {status}: {count}");
+            }
+            println!("total active balance: {total_active_balance} Gwei");
+
+            if let (Some(next_withdrawal_index), Some(next_withdrawal_validator_index)) =
+                (state.next_withdrawal_index(), state.next_withdrawal_validator_index())
+            {
+                println!("next withdrawal index: {next_withdrawal_index}");
+                println!("next withdrawal validator index: {next_withdrawal_validator_index}");
+                println!("fully withdrawable validators: {fully_withdrawable}");
+                println!("partially withdrawable validators: {partially_withdrawable}");
+            }
+        }
+        Commands::Validator { id } => {
+            let validator_index = if id.starts_with("0x") {
+                let public_key = BlsPublicKey::try_from(hex::decode(&id[2..])?.as_ref())?;
+                let Some(index) = state
+                    .validators()
+                    .iter()
+                    .position(|validator| validator.public_key == public_key)
+                else {
+                    eyre::bail!("no validator with public key {id} in the state")
+                };
+                index
+            } else {
+                id.parse()?
+            };
+            let (Some(validator), Some(&balance)) =
+                (state.validators().get(validator_index), state.balances().get(validator_index))
+            else {
+                eyre::bail!("no validator with index {validator_index} in the state")
+            };
+
+            let format_epoch = |epoch: Epoch| {
+                if epoch == FAR_FUTURE_EPOCH {
+                    "far future".to_string()
+                } else {
+                    epoch.to_string()
+                }
+            };
+            println!("index: {validator_index}");
+            println!("public key: {:?}", validator.public_key);
+            println!("status: {}", ValidatorStatus::new(validator, balance, current_epoch));
+            println!("withdrawal credentials: {:?}", validator.withdrawal_credentials);
+            println!("balance: {balance} Gwei");
+            println!("effective balance: {} Gwei", validator.effective_balance);
+            println!("slashed: {}", validator.slashed);
+            println!(
+                "activation eligibility epoch: {}",
+                format_epoch(validator.activation_eligibility_epoch)
+            );
+            println!("activation epoch: {}", format_epoch(validator.activation_epoch));
+            println!("exit epoch: {}", format_epoch(validator.exit_epoch));
+            println!("withdrawable epoch: {}", format_epoch(validator.withdrawable_epoch));
+            if let Some(position) = withdrawal_sweep_position(state, validator_index) {
+                let withdrawal =
+                    if is_fully_withdrawable_validator(validator, balance, current_epoch) {
+                        "full withdrawal"
+                    } else if is_partially_withdrawable_validator(validator, balance, context) {
+                        "partial withdrawal"
+                    } else {
+                        "no withdrawal"
+                    };
+                println!("withdrawal sweep: {position} validators ahead ({withdrawal})");
+            }
+        }
+        Commands::Committees { epoch } => {
+            let previous_epoch = current_epoch.saturating_sub(1);
+            let next_epoch = current_epoch + 1;
+            if epoch < previous_epoch || epoch > next_epoch {
+                eyre::bail!(
+                    "committees can only be computed for epochs {previous_epoch} to {next_epoch} from a state at epoch {current_epoch}"
+                )
+            }
+            let committees = match state {
+                BeaconState::Phase0(state) => collect_committees(
+                    epoch,
+                    phase0::get_committee_count_per_slot(state, epoch, context),
+                    context,
+                    |slot, index| phase0::get_beacon_committee(state, slot, index, context),
+                )?,
+                BeaconState::Altair(state) => collect_committees(
+                    epoch,
+                    altair::get_committee_count_per_slot(state, epoch, context),
+                    context,
+                    |slot, index| altair::get_beacon_committee(state, slot, index, context),
+                )?,
+                BeaconState::Bellatrix(state) => collect_committees(
+                    epoch,
+                    bellatrix::get_committee_count_per_slot(state, epoch, context),
+                    context,
+                    |slot, index| bellatrix::get_beacon_committee(state, slot, index, context),
+                )?,
+                BeaconState::Capella(state) => collect_committees(
+                    epoch,
+                    capella::get_committee_count_per_slot(state, epoch, context),
+                    context,
+                    |slot, index| capella::get_beacon_committee(state, slot, index, context),
+                )?,
+                BeaconState::Deneb(state) => collect_committees(
+                    epoch,
+                    deneb::get_committee_count_per_slot(state, epoch, context),
+                    context,
+                    |slot, index| deneb::get_beacon_committee(state, slot, index, context),
+                )?,
+            };
+            println!("{}", serde_json::to_string_pretty(&committees)?);
+        }
+        Commands::Proposers { epoch } => {
+            let duties = match state {
+                BeaconState::Phase0(state) => phase0::get_proposer_duties(state, epoch, context)?,
+                BeaconState::Altair(state) => altair::get_proposer_duties(state, epoch, context)?,
+                BeaconState::Bellatrix(state) => {
+                    bellatrix::get_proposer_duties(state, epoch, context)?
+                }
+                BeaconState::Capella(state) => capella::get_proposer_duties(state, epoch, context)?,
+                BeaconState::Deneb(state) => deneb::get_proposer_duties(state, epoch, context)?,
+            };
+            println!("{}", serde_json::to_string_pretty(&duties)?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator_status() {
+        let pending = Validator {
+            activation_eligibility_epoch: FAR_FUTURE_EPOCH,
+            activation_epoch: FAR_FUTURE_EPOCH,
+            exit_epoch: FAR_FUTURE_EPOCH,
+            withdrawable_epoch: FAR_FUTURE_EPOCH,
+            ..Default::default()
+        };
+        assert_eq!(ValidatorStatus::new(&pending, 1, 0), ValidatorStatus::PendingInitialized);
+
+        let queued =
+            Validator { activation_eligibility_epoch: 1, activation_epoch: 5, ..pending.clone() };
+        assert_eq!(ValidatorStatus::new(&queued, 1, 4), ValidatorStatus::PendingQueued);
+        assert_eq!(ValidatorStatus::new(&queued, 1, 5), ValidatorStatus::ActiveOngoing);
+
+        let exiting = Validator { exit_epoch: 10, withdrawable_epoch: 20, ..queued };
+        assert_eq!(ValidatorStatus::new(&exiting, 1, 9), ValidatorStatus::ActiveExiting);
+        assert_eq!(ValidatorStatus::new(&exiting, 1, 10), ValidatorStatus::ExitedUnslashed);
+        assert_eq!(ValidatorStatus::new(&exiting, 1, 19), ValidatorStatus::ExitedUnslashed);
+        assert_eq!(ValidatorStatus::new(&exiting, 1, 20), ValidatorStatus::WithdrawalPossible);
+        assert_eq!(ValidatorStatus::new(&exiting, 0, 20), ValidatorStatus::WithdrawalDone);
+
+        let slashed = Validator { slashed: true, ..exiting };
+        assert_eq!(ValidatorStatus::new(&slashed, 1, 9), ValidatorStatus::ActiveSlashed);
+        assert_eq!(ValidatorStatus::new(&slashed, 1, 10), ValidatorStatus::ExitedSlashed);
+        assert_eq!(ValidatorStatus::new(&slashed, 1, 20), ValidatorStatus::WithdrawalPossible);
+
+        assert_eq!(ValidatorStatus::ActiveSlashed.to_string(), "active_slashed");
+    }
+
+    #[test]
+    fn test_withdrawal_sweep_position() {
+        let validators = vec![Validator::default(); 8];
+
+        let mut inner = capella::minimal::BeaconState::default();
+        inner.validators = validators.clone().try_into().unwrap();
+        inner.next_withdrawal_validator_index = 6;
+        let state = minimal::BeaconState::Capella(inner);
+        assert_eq!(withdrawal_sweep_position(&state, 6), Some(0));
+        assert_eq!(withdrawal_sweep_position(&state, 7), Some(1));
+        // the sweep wraps around to the start of the registry
+        assert_eq!(withdrawal_sweep_position(&state, 0), Some(2));
+        assert_eq!(withdrawal_sweep_position(&state, 5), Some(7));
+
+        let mut inner = bellatrix::minimal::BeaconState::default();
+        inner.validators = validators.try_into().unwrap();
+        let state = minimal::BeaconState::Bellatrix(inner);
+        assert_eq!(withdrawal_sweep_position(&state, 0), None);
+    }
+}
@@ -0,0 +1,513 @@
+use crate::{beacon_state, network};
+use clap::Args;
+use ethereum_consensus::{
+    altair, bellatrix, capella, deneb, phase0,
+    primitives::Slot,
+    ssz::prelude::*,
+    state_transition::Context,
+    types::{gnosis, mainnet, minimal, BeaconState, SignedBeaconBlock},
+    Error, Fork,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+// NOTE: the offset to the `message` and the `signature` precede the `slot` in every fork
+const BLOCK_SLOT_OFFSET: usize = 4 + 96;
+
+/// Loads a `SignedBeaconBlock` from the file at `path`, holding either JSON or (snappy-compressed)
+/// SSZ.
+pub fn load_block<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    path: &Path,
+    context: &Context,
+) -> eyre::Result<
+    SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+> {
+    if beacon_state::is_json(path) {
+        let (fork, value) = beacon_state::parse_json(&fs::read(path)?, "/message/slot", context)?;
+        let signed_block = match fork {
+            Fork::Phase0 => SignedBeaconBlock::Phase0(serde_json::from_value(value)?),
+            Fork::Altair => SignedBeaconBlock::Altair(serde_json::from_value(value)?),
+            Fork::Bellatrix => SignedBeaconBlock::Bellatrix(serde_json::from_value(value)?),
+            Fork::Capella => SignedBeaconBlock::Capella(serde_json::from_value(value)?),
+            Fork::Deneb => SignedBeaconBlock::Deneb(serde_json::from_value(value)?),
+        };
+        return Ok(signed_block)
+    }
+
+    let bytes = beacon_state::read_ssz_bytes(path)?;
+    let Some(slot) = bytes.get(BLOCK_SLOT_OFFSET..BLOCK_SLOT_OFFSET + 8) else {
+        eyre::bail!("input of {} bytes is too short to be a `SignedBeaconBlock`", bytes.len())
+    };
+    let slot = u64::from_le_bytes(slot.try_into().expect("is correct length"));
+    let signed_block = match context.fork_for(slot) {
+        Fork::Phase0 => SignedBeaconBlock::Phase0(Deserialize::deserialize(&bytes)?),
+        Fork::Altair => SignedBeaconBlock::Altair(Deserialize::deserialize(&bytes)?),
+        Fork::Bellatrix => SignedBeaconBlock::Bellatrix(Deserialize::deserialize(&bytes)?),
+        Fork::Capella => SignedBeaconBlock::Capella(Deserialize::deserialize(&bytes)?),
+        Fork::Deneb => SignedBeaconBlock::Deneb(Deserialize::deserialize(&bytes)?),
+    };
+    Ok(signed_block)
+}
+
+/// Advances `state` to `slot`, upgrading it at each fork boundary along the way.
+pub fn process_slots<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    context: &Context,
+) -> Result<(), Error> {
+    if slot < state.slot() {
+        return Err(Error::TransitionToPreviousSlot { current: state.slot(), requested: slot })
+    }
+    loop {
+        let next_fork_epoch = match state {
+            BeaconState::Phase0(_) => Some(context.altair_fork_epoch),
+            BeaconState::Altair(_) => Some(context.bellatrix_fork_epoch),
+            BeaconState::Bellatrix(_) => Some(context.capella_fork_epoch),
+            BeaconState::Capella(_) => Some(context.deneb_fork_epoch),
+            BeaconState::Deneb(_) => None,
+        };
+        let fork_slot = next_fork_epoch.map(|epoch| epoch.saturating_mul(context.slots_per_epoch));
+        let target_slot = fork_slot.map(|fork_slot| fork_slot.min(slot)).unwrap_or(slot);
+        if state.slot() < target_slot {
+            match state {
+                BeaconState::Phase0(state) => phase0::process_slots(state, target_slot, context)?,
+                BeaconState::Altair(state) => altair::process_slots(state, target_slot, context)?,
+                BeaconState::Bellatrix(state) => {
+                    bellatrix::process_slots(state, target_slot, context)?
+                }
+                BeaconState::Capella(state) => capella::process_slots(state, target_slot, context)?,
+                BeaconState::Deneb(state) => deneb::process_slots(state, target_slot, context)?,
+            }
+        }
+        if fork_slot != Some(target_slot) {
+            return Ok(())
+        }
+        *state = match &*state {
+            BeaconState::Phase0(state) => {
+                BeaconState::Altair(altair::upgrade_to_altair(state, context)?)
+            }
+            BeaconState::Altair(state) => {
+                BeaconState::Bellatrix(bellatrix::upgrade_to_bellatrix(state, context))
+            }
+            BeaconState::Bellatrix(state) => {
+                BeaconState::Capella(capella::upgrade_to_capella(state, context))
+            }
+            BeaconState::Capella(state) => {
+                BeaconState::Deneb(deneb::upgrade_to_deneb(state, context))
+            }
+            BeaconState::Deneb(_) => unreachable!("no fork follows deneb"),
+        };
+    }
+}
+
+// Applies `signed_block` to a `state` already advanced to the slot of the block, without checking
+// the resulting state root. Returns the time spent verifying the proposer signature and
+// processing the block, respectively.
+// NOTE: execution payloads are assumed to be valid as there is no execution engine to verify them
+fn apply_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    signed_block: &mut SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    verify_proposer_signature: bool,
+    context: &Context,
+) -> Result<(Duration, Duration), Error> {
+    let start = Instant::now();
+    if verify_proposer_signature {
+        match (&*state, &mut *signed_block) {
+            (BeaconState::Phase0(state), SignedBeaconBlock::Phase0(signed_block)) => {
+                phase0::verify_block_signature(state, signed_block, context)?
+            }
+            (BeaconState::Altair(state), SignedBeaconBlock::Altair(signed_block)) => {
+                altair::verify_block_signature(state, signed_block, context)?
+            }
+            (BeaconState::Bellatrix(state), SignedBeaconBlock::Bellatrix(signed_block)) => {
+                bellatrix::verify_block_signature(state, signed_block, context)?
+            }
+            (BeaconState::Capella(state), SignedBeaconBlock::Capella(signed_block)) => {
+                capella::verify_block_signature(state, signed_block, context)?
+            }
+            (BeaconState::Deneb(state), SignedBeaconBlock::Deneb(signed_block)) => {
+                deneb::verify_block_signature(state, signed_block, context)?
+            }
+            (state, signed_block) => {
+                return Err(Error::IncompatibleFork {
+                    source_fork: state.version(),
+                    destination_fork: signed_block.version(),
+                })
+            }
+        }
+    }
+    let signature_time = start.elapsed();
+
+    let start = Instant::now();
+    match (&mut *state, &mut *signed_block) {
+        (BeaconState::Phase0(state), SignedBeaconBlock::Phase0(signed_block)) => {
+            phase0::process_block(state, &mut signed_block.message, context)?
+        }
+        (BeaconState::Altair(state), SignedBeaconBlock::Altair(signed_block)) => {
+            altair::process_block(state, &mut signed_block.message, context)?
+        }
+        (BeaconState::Bellatrix(state), SignedBeaconBlock::Bellatrix(signed_block)) => {
+            let execution_engine = bellatrix::execution_engine::DefaultExecutionEngine::default();
+            bellatrix::process_block(state, &mut signed_block.message, &execution_engine, context)?
+        }
+        (BeaconState::Capella(state), SignedBeaconBlock::Capella(signed_block)) => {
+            let execution_engine = capella::execution_engine::DefaultExecutionEngine::default();
+            capella::process_block(state, &mut signed_block.message, &execution_engine, context)?
+        }
+        (BeaconState::Deneb(state), SignedBeaconBlock::Deneb(signed_block)) => {
+            let execution_engine = deneb::execution_engine::DefaultExecutionEngine::default();
+            deneb::process_block(state, &mut signed_block.message, &execution_engine, context)?
+        }
+        (state, signed_block) => {
+            return Err(Error::IncompatibleFork {
+                source_fork: state.version(),
+                destination_fork: signed_block.version(),
+            })
+        }
+    }
+    Ok((signature_time, start.elapsed()))
+}
+
+// Reports the full error, including the exact `InvalidBlock` variant, for the block at `path`.
+fn report(error: Error, path: &Path, slot: Slot) -> eyre::Report {
+    match error {
+        Error::InvalidBlock(invalid_block) => eyre::eyre!(
+            "block {} at slot {slot} is invalid: {invalid_block}\n{invalid_block:#?}",
+            path.display()
+        ),
+        error => eyre::eyre!(
+            "could not apply block {} at slot {slot}: {error}\n{error:#?}",
+            path.display()
+        ),
+    }
+}
+
+#[derive(Debug, Args)]
+#[clap(about = "apply signed blocks to a `BeaconState` and print the resulting state root")]
+pub struct Command {
+    #[clap(
+        long,
+        help = "path to the pre-state in JSON or SSZ, snappy-compressed if the file ends in `.ssz_snappy`"
+    )]
+    pre_state: PathBuf,
+    #[clap(
+        help = "paths to the signed blocks to apply in order, in the same formats as the pre-state"
+    )]
+    blocks: Vec<PathBuf>,
+    #[clap(
+        long,
+        default_value = "mainnet",
+        help = "network name, `minimal` or path to a custom config directory"
+    )]
+    network: String,
+    #[clap(
+        long,
+        help = "skip verification of the proposer signature of each block; signatures within blocks, e.g. of RANDAO reveals and attestations, are still verified"
+    )]
+    skip_proposer_signature: bool,
+    #[clap(long, help = "advance the state to this slot after applying any blocks")]
+    skip_to_slot: Option<Slot>,
+    #[clap(long, help = "path to write the post-state to, as JSON if the file ends in `.json`")]
+    post_state: Option<PathBuf>,
+}
+
+impl Command {
+    pub fn execute(self) -> eyre::Result<()> {
+        let context = network::context_for(self.network.clone())?;
+        match network::preset_for(&context)? {
+            "minimal" => {
+                let state: minimal::BeaconState = beacon_state::load(&self.pre_state, &context)?;
+                let blocks = self
+                    .blocks
+                    .iter()
+                    .map(|path| load_block(path, &context))
+                    .collect::<eyre::Result<Vec<minimal::SignedBeaconBlock>>>()?;
+                self.run(state, blocks, &context)
+            }
+            "gnosis" => {
+                let state: gnosis::BeaconState = beacon_state::load(&self.pre_state, &context)?;
+                let blocks = self
+                    .blocks
+                    .iter()
+                    .map(|path| load_block(path, &context))
+                    .collect::<eyre::Result<Vec<gnosis::SignedBeaconBlock>>>()?;
+                self.run(state, blocks, &context)
+            }
+            "mainnet" => {
+                let state: mainnet::BeaconState = beacon_state::load(&self.pre_state, &context)?;
+                let blocks = self
+                    .blocks
+                    .iter()
+                    .map(|path| load_block(path, &context))
+                    .collect::<eyre::Result<Vec<mainnet::SignedBeaconBlock>>>()?;
+                self.run(state, blocks, &context)
+            }
+            _ => eyre::bail!("the network does not use the mainnet, minimal or gnosis presets"),
+        }
+    }
+
+    fn run<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+    >(
+        &self,
+        mut state: BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        blocks: Vec<
+            SignedBeaconBlock<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >,
+        >,
+        context: &Context,
+    ) -> eyre::Result<()> {
+        println!("pre-state: {} at slot {}", state.version(), state.slot());
+        for (path, mut signed_block) in self.blocks.iter().zip(blocks) {
+            let slot = signed_block.message().slot();
+
+            let start = Instant::now();
+            process_slots(&mut state, slot, context).map_err(|error| report(error, path, slot))?;
+            let slots_time = start.elapsed();
+
+            let (signature_time, block_time) =
+                apply_block(&mut state, &mut signed_block, !self.skip_proposer_signature, context)
+                    .map_err(|error| report(error, path, slot))?;
+
+            let start = Instant::now();
+            let state_root = state.hash_tree_root()?;
+            let state_root_time = start.elapsed();
+
+            let expected_state_root = signed_block.message().state_root();
+            if state_root != expected_state_root {
+                eyre::bail!(
+                    "block {} at slot {slot} is invalid: post-state root {state_root:?} does not match the state root {expected_state_root:?} of the block",
+                    path.display()
+                )
+            }
+            println!(
+                "block {} at slot {slot}: slots {slots_time:?}, signature {signature_time:?}, block {block_time:?}, state root {state_root_time:?}",
+                path.display()
+            );
+        }
+
+        if let Some(slot) = self.skip_to_slot {
+            let start = Instant::now();
+            process_slots(&mut state, slot, context)?;
+            println!("skipped to slot {slot}: slots {:?}", start.elapsed());
+        }
+
+        println!("post-state root: {:?}", state.hash_tree_root()?);
+        if let Some(path) = self.post_state.as_ref() {
+            if beacon_state::is_json(path) {
+                fs::write(path, serde_json::to_string_pretty(&state)?)?;
+            } else {
+                beacon_state::write_ssz_bytes(path, &serialize(&state)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::{crypto::SecretKey, phase0::Validator};
+
+    #[test]
+    fn test_process_slots_across_forks() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 1;
+        context.bellatrix_fork_epoch = 2;
+        context.capella_fork_epoch = 2;
+        context.deneb_fork_epoch = 2;
+
+        let validators = (0..64u8)
+            .map(|i| Validator {
+                public_key: SecretKey::key_gen(&[i + 1; 32]).unwrap().public_key(),
+                effective_balance: context.max_effective_balance,
+                exit_epoch: u64::MAX,
+                withdrawable_epoch: u64::MAX,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let mut inner = phase0::minimal::BeaconState::default();
+        inner.slot = context.slots_per_epoch - 1;
+        inner.balances = vec![context.max_effective_balance; validators.len()].try_into().unwrap();
+        inner.validators = validators.try_into().unwrap();
+        let pre_state = minimal::BeaconState::Phase0(inner);
+
+        // stops at the target slot before reaching the next fork
+        let mut state = pre_state.clone();
+        process_slots(&mut state, context.slots_per_epoch - 1, &context).unwrap();
+        assert!(matches!(state, BeaconState::Phase0(_)));
+
+        // upgrades exactly at the fork slot
+        let mut state = pre_state.clone();
+        process_slots(&mut state, context.slots_per_epoch, &context).unwrap();
+        assert!(matches!(state, BeaconState::Altair(_)));
+        assert_eq!(state.slot(), context.slots_per_epoch);
+
+        // crosses altair, then bellatrix, capella and deneb at the same epoch
+        let mut state = pre_state.clone();
+        let slot = 2 * context.slots_per_epoch + 1;
+        process_slots(&mut state, slot, &context).unwrap();
+        let BeaconState::Deneb(inner) = &state else { panic!("expected a deneb state") };
+        assert_eq!(inner.slot, slot);
+        assert_eq!(inner.fork.current_version, context.deneb_fork_version);
+        assert_eq!(inner.fork.previous_version, context.capella_fork_version);
+        assert_eq!(inner.fork.epoch, 2);
+
+        assert!(matches!(
+            process_slots(&mut state, slot - 1, &context),
+            Err(Error::TransitionToPreviousSlot { .. })
+        ));
+    }
+}
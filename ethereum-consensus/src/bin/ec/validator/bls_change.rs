@@ -0,0 +1,112 @@
+use crate::{beacon_state, network, validator::deposit_data::bls_withdrawal_credentials};
+use ethereum_consensus::{
+    capella::{BlsToExecutionChange, SignedBlsToExecutionChange},
+    key_derivation::KeyPair,
+    phase0::Validator,
+    primitives::{ExecutionAddress, Root, ValidatorIndex, BLS_WITHDRAWAL_PREFIX},
+    signer::Signer,
+    state_transition::Context,
+    types::{gnosis, mainnet, minimal},
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path};
+
+pub type Validators = HashMap<ValidatorIndex, Validator>;
+
+#[derive(Deserialize)]
+struct ValidatorEntry {
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    index: ValidatorIndex,
+    validator: Validator,
+}
+
+// Accepts the response of the `/eth/v1/beacon/states/{state_id}/validators` endpoint, with or
+// without the enclosing `data` field.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ValidatorList {
+    Response { data: Vec<ValidatorEntry> },
+    Entries(Vec<ValidatorEntry>),
+}
+
+pub fn load_validators_from_file(path: &Path) -> eyre::Result<Validators> {
+    let list: ValidatorList = serde_json::from_slice(&fs::read(path)?)?;
+    let entries = match list {
+        ValidatorList::Response { data } => data,
+        ValidatorList::Entries(entries) => entries,
+    };
+    Ok(entries.into_iter().map(|entry| (entry.index, entry.validator)).collect())
+}
+
+pub fn parse_root(input: &str) -> eyre::Result<Root> {
+    let bytes = hex::decode(input.strip_prefix("0x").unwrap_or(input))?;
+    Root::try_from(bytes.as_ref())
+        .map_err(|_| eyre::eyre!("root must be 32 bytes but found {}", bytes.len()))
+}
+
+/// Loads the validators and the genesis validators root from the SSZ `BeaconState` at `path`.
+pub fn load_validators_from_state(
+    path: &Path,
+    context: &Context,
+) -> eyre::Result<(Validators, Root)> {
+    let bytes = beacon_state::read_ssz_bytes(path)?;
+    let (validators, genesis_validators_root): (Vec<Validator>, Root) =
+        match network::preset_for(context)? {
+            "minimal" => {
+                let state: minimal::BeaconState = beacon_state::decode(&bytes, context)?;
+                (state.validators().iter().cloned().collect(), state.genesis_validators_root())
+            }
+            "gnosis" => {
+                let state: gnosis::BeaconState = beacon_state::decode(&bytes, context)?;
+                (state.validators().iter().cloned().collect(), state.genesis_validators_root())
+            }
+            "mainnet" => {
+                let state: mainnet::BeaconState = beacon_state::decode(&bytes, context)?;
+                (state.validators().iter().cloned().collect(), state.genesis_validators_root())
+            }
+            _ => eyre::bail!("the network does not use the mainnet, minimal or gnosis presets"),
+        };
+    let validators = validators
+        .into_iter()
+        .enumerate()
+        .map(|(index, validator)| (index as ValidatorIndex, validator))
+        .collect();
+    Ok((validators, genesis_validators_root))
+}
+
+/// Signs a change to `execution_address` for the validator at `validator_index`, after checking
+/// its 0x00 withdrawal credentials commit to `withdrawal_key`.
+pub fn generate(
+    validator_index: ValidatorIndex,
+    withdrawal_key: &KeyPair,
+    execution_address: &ExecutionAddress,
+    validators: &Validators,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> eyre::Result<SignedBlsToExecutionChange> {
+    let Some(validator) = validators.get(&validator_index) else {
+        eyre::bail!("validator {validator_index} was not found")
+    };
+    if validator.withdrawal_credentials[0] != BLS_WITHDRAWAL_PREFIX {
+        eyre::bail!("validator {validator_index} does not have 0x00 withdrawal credentials")
+    }
+    let expected_withdrawal_credentials = bls_withdrawal_credentials(&withdrawal_key.public_key);
+    if validator.withdrawal_credentials != expected_withdrawal_credentials {
+        eyre::bail!(
+            "withdrawal credentials of validator {validator_index} do not match the withdrawal key at {}",
+            withdrawal_key.path
+        )
+    }
+
+    let mut message = BlsToExecutionChange {
+        validator_index,
+        from_bls_public_key: withdrawal_key.public_key.clone(),
+        to_execution_address: execution_address.clone(),
+    };
+    let signature = withdrawal_key.private_key.sign_bls_to_execution_change(
+        &mut message,
+        genesis_validators_root,
+        context,
+    )?;
+    Ok(SignedBlsToExecutionChange { message, signature })
+}
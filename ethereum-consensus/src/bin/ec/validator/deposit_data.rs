@@ -0,0 +1,147 @@
+use ethereum_consensus::{
+    crypto::hash,
+    key_derivation::KeyPair,
+    networks::Network,
+    phase0::{DepositData, DepositMessage},
+    primitives::{
+        BlsPublicKey, Bytes32, ExecutionAddress, Gwei, BLS_WITHDRAWAL_PREFIX,
+        ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+    },
+    signer::Signer,
+    ssz::prelude::*,
+    state_transition::Context,
+};
+use serde::Serialize;
+use std::path::Path;
+
+// NOTE: version of `staking-deposit-cli` whose output format we follow
+const DEPOSIT_CLI_VERSION: &str = "2.7.0";
+
+pub fn bls_withdrawal_credentials(public_key: &BlsPublicKey) -> Bytes32 {
+    let mut withdrawal_credentials = hash(&public_key[..]);
+    withdrawal_credentials[0] = BLS_WITHDRAWAL_PREFIX;
+    withdrawal_credentials
+}
+
+pub fn execution_withdrawal_credentials(execution_address: &ExecutionAddress) -> Bytes32 {
+    let mut withdrawal_credentials = Bytes32::default();
+    withdrawal_credentials[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
+    withdrawal_credentials[12..].copy_from_slice(&execution_address[..]);
+    withdrawal_credentials
+}
+
+/// An entry of the `deposit_data-*.json` file written by `staking-deposit-cli`.
+///
+/// Byte strings are hex-encoded without a `0x` prefix to match the format expected by the
+/// launchpad.
+#[derive(Debug, Serialize)]
+pub struct DepositDataEntry {
+    pubkey: String,
+    withdrawal_credentials: String,
+    amount: Gwei,
+    signature: String,
+    deposit_message_root: String,
+    deposit_data_root: String,
+    fork_version: String,
+    network_name: String,
+    deposit_cli_version: String,
+}
+
+// The launchpad expects the chain name, e.g. `mainnet`, so a custom network that still refers
+// to its config directory is named after the last component of that path.
+fn network_name(network: &Network) -> String {
+    match network {
+        Network::Custom(config) => Path::new(config)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| config.clone()),
+        network => network.to_string(),
+    }
+}
+
+// Uses 0x01 withdrawal credentials if `execution_address` is provided and otherwise 0x00
+// withdrawal credentials for `withdrawal_key`.
+pub fn generate(
+    signing_key: &KeyPair,
+    withdrawal_key: &KeyPair,
+    execution_address: Option<&ExecutionAddress>,
+    amount: Gwei,
+    context: &Context,
+) -> eyre::Result<DepositDataEntry> {
+    let withdrawal_credentials = match execution_address {
+        Some(execution_address) => execution_withdrawal_credentials(execution_address),
+        None => bls_withdrawal_credentials(&withdrawal_key.public_key),
+    };
+    let mut deposit_message = DepositMessage {
+        public_key: signing_key.public_key.clone(),
+        withdrawal_credentials,
+        amount,
+    };
+    let signature = signing_key.private_key.sign_deposit_message(&mut deposit_message, context)?;
+    let deposit_message_root = deposit_message.hash_tree_root()?;
+
+    let DepositMessage { public_key, withdrawal_credentials, amount } = deposit_message;
+    let mut deposit_data = DepositData { public_key, withdrawal_credentials, amount, signature };
+    let deposit_data_root = deposit_data.hash_tree_root()?;
+
+    Ok(DepositDataEntry {
+        pubkey: hex::encode(&deposit_data.public_key[..]),
+        withdrawal_credentials: hex::encode(&deposit_data.withdrawal_credentials[..]),
+        amount,
+        signature: hex::encode(&deposit_data.signature[..]),
+        deposit_message_root: hex::encode(deposit_message_root),
+        deposit_data_root: hex::encode(deposit_data_root),
+        fork_version: hex::encode(context.genesis_fork_version),
+        network_name: network_name(&context.name),
+        deposit_cli_version: DEPOSIT_CLI_VERSION.to_string(),
+    })
+}
+
+pub fn parse_execution_address(input: &str) -> eyre::Result<ExecutionAddress> {
+    let bytes = hex::decode(input.strip_prefix("0x").unwrap_or(input))?;
+    ExecutionAddress::try_from(bytes.as_ref())
+        .map_err(|_| eyre::eyre!("execution address must be 20 bytes but found {}", bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethereum_consensus::key_derivation::{
+        derive_master_sk, derive_validator_keys, seed_from_mnemonic, ValidatorKeys,
+    };
+
+    const MNEMONIC: &str = "sister protect peanut hill ready work profit fit wish want small inflict flip member tail between sick setup bright duck morning sell paper worry";
+
+    #[test]
+    fn test_network_name() {
+        assert_eq!(network_name(&Network::Holesky), "holesky");
+        assert_eq!(network_name(&Network::Custom("devnet".to_string())), "devnet");
+        assert_eq!(network_name(&Network::Custom("./configs/devnet-7/".to_string())), "devnet-7");
+    }
+
+    #[test]
+    fn test_generate() {
+        let seed = seed_from_mnemonic(MNEMONIC, None).unwrap();
+        let master_sk = derive_master_sk(&seed).unwrap();
+        let ValidatorKeys { signing, withdrawal } = derive_validator_keys(&master_sk, 0);
+        let context = Context::for_mainnet();
+
+        let entry = generate(&signing, &withdrawal, None, 32_000_000_000, &context).unwrap();
+        assert_eq!(entry.pubkey, "a9ad291a7d2aff6b60f2b902744413818b0fb48b55047249f8a0cde8eb14e519cc67945dd0f3eba1fcab43c00105bf84");
+        assert_eq!(
+            entry.withdrawal_credentials,
+            "00bd0b5a34de5fb17df08410b5e615dda87caf4fb72d0aac91ce5e52fc6aa8de"
+        );
+        assert_eq!(entry.signature, "a44cb8dfa4daddd3db516a3c70cba846063ff64a1b9f99c7741dfb5bcc66700bc4feac7ba1dfe188661741f99041a0ca0549a722e349fd8fec3a8669fcc32e6c09dc0c61d2b7de35b018066a5169b2c722c196b320be413f3bd205d7845ac4cc");
+        assert_eq!(
+            entry.deposit_message_root,
+            "a1efd638094e123456fe7baddc0f84236c9da953cdc39561c40e1ec2853bcb67"
+        );
+        assert_eq!(
+            entry.deposit_data_root,
+            "cff903dcd794ec2ee5897728d7c6d33518597b25cfc150455ef3621444753825"
+        );
+        assert_eq!(entry.fork_version, "00000000");
+        assert_eq!(entry.network_name, "mainnet");
+    }
+}
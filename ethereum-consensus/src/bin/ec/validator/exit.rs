@@ -0,0 +1,112 @@
+use ethereum_consensus::{
+    crypto::SecretKey,
+    keystore::Keystore,
+    phase0::{SignedVoluntaryExit, VoluntaryExit},
+    primitives::{Epoch, Root, Slot, ValidatorIndex},
+    signer::Signer,
+    state_transition::Context,
+};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+pub fn load_secret_key_from_keystore(path: &Path, passphrase: &str) -> eyre::Result<SecretKey> {
+    let keystore: Keystore = serde_json::from_slice(&fs::read(path)?)?;
+    Ok(keystore.decrypt(passphrase)?)
+}
+
+pub fn read_passphrase_file(path: &Path) -> eyre::Result<String> {
+    let passphrase = fs::read_to_string(path)?;
+    Ok(passphrase.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Signs an exit valid from `epoch` for a chain at `current_epoch`, using the EIP-7044 domain if
+/// the chain is in Deneb or later.
+pub fn sign(
+    secret_key: &SecretKey,
+    validator_index: ValidatorIndex,
+    epoch: Epoch,
+    current_epoch: Epoch,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> eyre::Result<SignedVoluntaryExit> {
+    let mut message = VoluntaryExit { epoch, validator_index };
+    let signature = secret_key.sign_voluntary_exit(
+        &mut message,
+        current_epoch,
+        genesis_validators_root,
+        context,
+    )?;
+    Ok(SignedVoluntaryExit { message, signature })
+}
+
+#[derive(Deserialize)]
+struct GenesisDetails {
+    genesis_validators_root: Root,
+}
+
+#[derive(Deserialize)]
+struct HeaderMessage {
+    #[serde(with = "ethereum_consensus::serde::as_str")]
+    slot: Slot,
+}
+
+#[derive(Deserialize)]
+struct SignedHeader {
+    message: HeaderMessage,
+}
+
+#[derive(Deserialize)]
+struct HeaderSummary {
+    header: SignedHeader,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+fn error_from(response: reqwest::blocking::Response) -> eyre::Report {
+    let status = response.status();
+    let message = response.text().unwrap_or_default();
+    eyre::eyre!("beacon node responded with status {status}: {message}")
+}
+
+pub fn fetch_genesis_validators_root(
+    client: &reqwest::blocking::Client,
+    beacon_node: &str,
+) -> eyre::Result<Root> {
+    let target = format!("{}/eth/v1/beacon/genesis", beacon_node.trim_end_matches('/'));
+    let response = client.get(target).send()?;
+    if !response.status().is_success() {
+        return Err(error_from(response))
+    }
+    let response: Response<GenesisDetails> = response.json()?;
+    Ok(response.data.genesis_validators_root)
+}
+
+pub fn fetch_head_slot(
+    client: &reqwest::blocking::Client,
+    beacon_node: &str,
+) -> eyre::Result<Slot> {
+    let target = format!("{}/eth/v1/beacon/headers/head", beacon_node.trim_end_matches('/'));
+    let response = client.get(target).send()?;
+    if !response.status().is_success() {
+        return Err(error_from(response))
+    }
+    let response: Response<HeaderSummary> = response.json()?;
+    Ok(response.data.header.message.slot)
+}
+
+pub fn submit(
+    client: &reqwest::blocking::Client,
+    beacon_node: &str,
+    exit: &SignedVoluntaryExit,
+) -> eyre::Result<()> {
+    let target =
+        format!("{}/eth/v1/beacon/pool/voluntary_exits", beacon_node.trim_end_matches('/'));
+    let response = client.post(target).json(exit).send()?;
+    if !response.status().is_success() {
+        return Err(error_from(response))
+    }
+    Ok(())
+}
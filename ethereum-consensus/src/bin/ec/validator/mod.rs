@@ -1,8 +1,22 @@
+mod bls_change;
+mod deposit_data;
+mod exit;
 mod keys;
 mod keystores;
 mod mnemonic;
 
+use crate::network;
 use clap::{Args, Subcommand};
+use ethereum_consensus::{
+    key_derivation::{
+        derive_key_pair, derive_master_sk, derive_validator_keys, seed_from_mnemonic, Path,
+    },
+    primitives::{Epoch, Gwei, ValidatorIndex},
+};
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
@@ -18,6 +32,129 @@ pub enum Commands {
         #[clap(help = "EIP-2334 index to stop key generation (exclusive)")]
         end: u32,
     },
+    #[clap(
+        about = "Generates deposit data that targets a format compatible with the `staking-deposit-cli` utility."
+    )]
+    DepositData {
+        #[clap(help = "BIP-39 mnemonic to use following EIP-2334")]
+        phrase: String,
+        #[clap(help = "EIP-2334 index to start key generation (inclusive)")]
+        start: u32,
+        #[clap(help = "EIP-2334 index to stop key generation (exclusive)")]
+        end: u32,
+        #[clap(
+            long,
+            default_value_t = 32_000_000_000,
+            help = "amount to deposit for each validator, in Gwei"
+        )]
+        amount: Gwei,
+        #[clap(
+            long,
+            help = "execution address for 0x01 withdrawal credentials; if missing, 0x00 withdrawal credentials are derived from the EIP-2334 withdrawal key"
+        )]
+        execution_address: Option<String>,
+        #[clap(
+            long,
+            default_value = "mainnet",
+            help = "network name, `minimal` or path to a custom config directory"
+        )]
+        network: String,
+        #[clap(
+            long,
+            default_value = ".",
+            help = "directory to write the `deposit_data-*.json` file into"
+        )]
+        output_dir: PathBuf,
+    },
+    #[clap(
+        about = "Generates signed BLS-to-execution changes for validators with 0x00 withdrawal credentials."
+    )]
+    BlsChange {
+        #[clap(help = "BIP-39 mnemonic to use following EIP-2334")]
+        phrase: String,
+        #[clap(help = "EIP-2334 index of the withdrawal key of the first validator")]
+        start: u32,
+        #[clap(
+            long,
+            required = true,
+            value_delimiter = ',',
+            help = "comma-separated indices of the validators to change, in EIP-2334 index order"
+        )]
+        validator_indices: Vec<ValidatorIndex>,
+        #[clap(long, help = "execution address to receive the withdrawals")]
+        execution_address: String,
+        #[clap(
+            long,
+            default_value = "mainnet",
+            help = "network name, `minimal` or path to a custom config directory"
+        )]
+        network: String,
+        #[clap(
+            long,
+            conflicts_with = "validators",
+            help = "path to an SSZ `BeaconState` to verify withdrawal credentials against"
+        )]
+        state: Option<PathBuf>,
+        #[clap(
+            long,
+            help = "path to the JSON response of `/eth/v1/beacon/states/{state_id}/validators` to verify withdrawal credentials against"
+        )]
+        validators: Option<PathBuf>,
+        #[clap(
+            long,
+            help = "genesis validators root of the network, required unless `--state` is provided"
+        )]
+        genesis_validators_root: Option<String>,
+        #[clap(
+            long,
+            default_value = ".",
+            help = "directory to write the `bls_to_execution_change-*.json` file into"
+        )]
+        output_dir: PathBuf,
+    },
+    #[clap(
+        about = "Signs a voluntary exit with a key from an EIP-2335 keystore or a BIP-39 mnemonic."
+    )]
+    Exit {
+        #[clap(long, help = "index of the validator to exit")]
+        validator_index: ValidatorIndex,
+        #[clap(long, help = "earliest epoch the exit is valid in")]
+        epoch: Epoch,
+        #[clap(
+            long,
+            required_unless_present = "phrase",
+            conflicts_with = "phrase",
+            help = "path to the EIP-2335 keystore of the validator"
+        )]
+        keystore: Option<PathBuf>,
+        #[clap(long, conflicts_with = "passphrase_file", help = "passphrase of the keystore")]
+        passphrase: Option<String>,
+        #[clap(long, help = "path to a file containing the passphrase of the keystore")]
+        passphrase_file: Option<PathBuf>,
+        #[clap(long, requires = "key_index", help = "BIP-39 mnemonic to use following EIP-2334")]
+        phrase: Option<String>,
+        #[clap(long, help = "EIP-2334 index of the signing key of the validator")]
+        key_index: Option<u32>,
+        #[clap(
+            long,
+            default_value = "mainnet",
+            help = "network name, `minimal` or path to a custom config directory"
+        )]
+        network: String,
+        #[clap(
+            long,
+            required_unless_present = "beacon_node",
+            help = "genesis validators root of the network, fetched from `--beacon-node` if missing"
+        )]
+        genesis_validators_root: Option<String>,
+        #[clap(
+            long,
+            help = "URL of a beacon node to read genesis details and the head of the chain from and submit to"
+        )]
+        beacon_node: Option<String>,
+        #[clap(long, requires = "beacon_node", help = "submit the signed exit to `--beacon-node`")]
+        submit: bool,
+    },
 }
 
 #[derive(Debug, Args)]
@@ -36,13 +173,192 @@ impl Command {
                 Ok(())
             }
             Commands::GenerateLighthouseKeystores { phrase, start, end } => {
-                let mnemonic = mnemonic::recover_from_phrase(&phrase)?;
-                let seed = mnemonic::to_seed(mnemonic, None);
-                let (signing_keys, _withdrawal_keys) = keys::generate(&seed, start, end);
+                let seed = seed_from_mnemonic(&phrase, None)?;
+                let (signing_keys, _withdrawal_keys) = keys::generate(&seed, start, end)?;
                 let keystores_with_passphrases = keystores::generate(signing_keys);
                 println!("{}", serde_json::to_string_pretty(&keystores_with_passphrases).unwrap());
                 Ok(())
             }
+            Commands::DepositData {
+                phrase,
+                start,
+                end,
+                amount,
+                execution_address,
+                network,
+                output_dir,
+            } => {
+                let context = network::context_for(network)?;
+                if amount < context.min_deposit_amount || amount > context.max_effective_balance {
+                    eyre::bail!(
+                        "deposit amount must be between {} and {} Gwei",
+                        context.min_deposit_amount,
+                        context.max_effective_balance
+                    );
+                }
+                let execution_address = execution_address
+                    .as_deref()
+                    .map(deposit_data::parse_execution_address)
+                    .transpose()?;
+
+                let seed = seed_from_mnemonic(&phrase, None)?;
+                let (signing_keys, withdrawal_keys) = keys::generate(&seed, start, end)?;
+                let deposits = signing_keys
+                    .iter()
+                    .zip(withdrawal_keys.iter())
+                    .map(|(signing_key, withdrawal_key)| {
+                        deposit_data::generate(
+                            signing_key,
+                            withdrawal_key,
+                            execution_address.as_ref(),
+                            amount,
+                            &context,
+                        )
+                    })
+                    .collect::<eyre::Result<Vec<_>>>()?;
+
+                let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+                let path = output_dir.join(format!("deposit_data-{timestamp}.json"));
+                std::fs::write(&path, serde_json::to_string(&deposits)?)?;
+                println!("wrote {} deposit(s) to {}", deposits.len(), path.display());
+                Ok(())
+            }
+            Commands::BlsChange {
+                phrase,
+                start,
+                validator_indices,
+                execution_address,
+                network,
+                state,
+                validators,
+                genesis_validators_root,
+                output_dir,
+            } => {
+                let context = network::context_for(network)?;
+                let execution_address = deposit_data::parse_execution_address(&execution_address)?;
+                let genesis_validators_root =
+                    genesis_validators_root.as_deref().map(bls_change::parse_root).transpose()?;
+                let (validators, genesis_validators_root) = match (state, validators) {
+                    (Some(state), _) => {
+                        let (validators, root) =
+                            bls_change::load_validators_from_state(&state, &context)?;
+                        if genesis_validators_root.is_some_and(|expected| expected != root) {
+                            eyre::bail!(
+                                "genesis validators root does not match the provided state"
+                            );
+                        }
+                        (validators, root)
+                    }
+                    (None, Some(validators)) => {
+                        let Some(root) = genesis_validators_root else {
+                            eyre::bail!(
+                                "`--genesis-validators-root` is required with `--validators`"
+                            )
+                        };
+                        (bls_change::load_validators_from_file(&validators)?, root)
+                    }
+                    (None, None) => eyre::bail!("one of `--state` or `--validators` is required"),
+                };
+
+                let seed = seed_from_mnemonic(&phrase, None)?;
+                let master_sk = derive_master_sk(&seed)?;
+                let changes = validator_indices
+                    .into_iter()
+                    .zip(start..)
+                    .map(|(validator_index, key_index)| {
+                        let keys = derive_validator_keys(&master_sk, key_index);
+                        bls_change::generate(
+                            validator_index,
+                            &keys.withdrawal,
+                            &execution_address,
+                            &validators,
+                            genesis_validators_root,
+                            &context,
+                        )
+                    })
+                    .collect::<eyre::Result<Vec<_>>>()?;
+
+                let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+                let path = output_dir.join(format!("bls_to_execution_change-{timestamp}.json"));
+                std::fs::write(&path, serde_json::to_string_pretty(&changes)?)?;
+                println!("wrote {} change(s) to {}", changes.len(), path.display());
+                Ok(())
+            }
+            Commands::Exit {
+                validator_index,
+                epoch,
+                keystore,
+                passphrase,
+                passphrase_file,
+                phrase,
+                key_index,
+                network,
+                genesis_validators_root,
+                beacon_node,
+                submit,
+            } => {
+                let context = network::context_for(network)?;
+                let secret_key = match (keystore, phrase, key_index) {
+                    (Some(keystore), ..) => {
+                        let passphrase = match (passphrase, passphrase_file) {
+                            (Some(passphrase), _) => passphrase,
+                            (None, Some(path)) => exit::read_passphrase_file(&path)?,
+                            (None, None) => eyre::bail!(
+                                "one of `--passphrase` or `--passphrase-file` is required with `--keystore`"
+                            ),
+                        };
+                        exit::load_secret_key_from_keystore(&keystore, &passphrase)?
+                    }
+                    (None, Some(phrase), Some(key_index)) => {
+                        let seed = seed_from_mnemonic(&phrase, None)?;
+                        let master_sk = derive_master_sk(&seed)?;
+                        derive_key_pair(&master_sk, &Path::signing(key_index)).private_key
+                    }
+                    _ => eyre::bail!("one of `--keystore` or `--phrase` is required"),
+                };
+
+                let client = reqwest::blocking::Client::new();
+                let genesis_validators_root = match (genesis_validators_root, beacon_node.as_ref())
+                {
+                    (Some(root), _) => bls_change::parse_root(&root)?,
+                    (None, Some(beacon_node)) => {
+                        exit::fetch_genesis_validators_root(&client, beacon_node)?
+                    }
+                    (None, None) => eyre::bail!(
+                        "one of `--genesis-validators-root` or `--beacon-node` is required"
+                    ),
+                };
+
+                // the signing domain depends on the fork the exit is processed in, rather than on
+                // `epoch`, so take it from the head of the chain
+                let current_epoch = match beacon_node.as_ref() {
+                    Some(beacon_node) => {
+                        exit::fetch_head_slot(&client, beacon_node)? / context.slots_per_epoch
+                    }
+                    None => context
+                        .clock()
+                        .and_then(|clock| clock.current_epoch())
+                        .ok_or_else(|| {
+                            eyre::eyre!(
+                                "unable to determine the current epoch of the network; use `--beacon-node`"
+                            )
+                        })?,
+                };
+                let signed_exit = exit::sign(
+                    &secret_key,
+                    validator_index,
+                    epoch,
+                    current_epoch,
+                    genesis_validators_root,
+                    &context,
+                )?;
+                println!("{}", serde_json::to_string_pretty(&signed_exit)?);
+                if let Some(beacon_node) = beacon_node.filter(|_| submit) {
+                    exit::submit(&client, &beacon_node, &signed_exit)?;
+                    eprintln!("submitted voluntary exit for validator {validator_index}");
+                }
+                Ok(())
+            }
         }
     }
 }
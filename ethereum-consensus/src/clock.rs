@@ -1,6 +1,7 @@
 //! A consensus clock
 use crate::{
-    configs, phase0 as presets,
+    altair, configs,
+    phase0::{self as presets, networking::MAXIMUM_GOSSIP_CLOCK_DISPARITY},
     primitives::{Epoch, Slot},
 };
 use std::{
@@ -13,11 +14,23 @@ pub const MAINNET_GENESIS_TIME: u64 = 1606824023;
 pub const SEPOLIA_GENESIS_TIME: u64 = 1655733600;
 pub const GOERLI_GENESIS_TIME: u64 = 1616508000;
 pub const HOLESKY_GENESIS_TIME: u64 = 1695902400;
+pub const GNOSIS_GENESIS_TIME: u64 = 1638993340;
+pub const CHIADO_GENESIS_TIME: u64 = 1665396300;
+
+// The number of intervals each slot is divided into, as defined for fork choice.
+// Validators attest at the end of the first interval and aggregate at the end of the second.
+pub const INTERVALS_PER_SLOT: u64 = 3;
 
 fn slot_to_nanos(slot: Slot, seconds_per_slot: u128, genesis_time: u128) -> u128 {
     u128::from(slot) * seconds_per_slot + genesis_time
 }
 
+// NOTE: rounds up so that an instant is in `interval` iff it is at or after the returned time
+fn interval_offset_in_nanos(interval: u64, seconds_per_slot: u128) -> u128 {
+    let intervals_per_slot = u128::from(INTERVALS_PER_SLOT);
+    (u128::from(interval) * seconds_per_slot + intervals_per_slot - 1) / intervals_per_slot
+}
+
 #[inline]
 fn u128_to_u64(t: u128) -> u64 {
     u64::try_from(t).expect("close enough to `UNIX_EPOCH` to fit in type")
@@ -78,9 +91,12 @@ pub struct Inner<T: TimeProvider> {
     genesis_time: u128,
     seconds_per_slot: u128,
     slots_per_epoch: Slot,
+    epochs_per_sync_committee_period: Epoch,
     time_provider: T,
 }
 
+/// Construct a `Clock` using the `mainnet` value for `EPOCHS_PER_SYNC_COMMITTEE_PERIOD`;
+/// see `from_system_time_with_sync_committee_period` for other presets.
 pub fn from_system_time(
     genesis_time: u64,
     seconds_per_slot: u64,
@@ -90,6 +106,22 @@ pub fn from_system_time(
     Clock::new(genesis_time, seconds_per_slot, slots_per_epoch, time_provider)
 }
 
+pub fn from_system_time_with_sync_committee_period(
+    genesis_time: u64,
+    seconds_per_slot: u64,
+    slots_per_epoch: Slot,
+    epochs_per_sync_committee_period: Epoch,
+) -> Clock<SystemTimeProvider> {
+    let time_provider = SystemTimeProvider;
+    Clock::with_sync_committee_period(
+        genesis_time,
+        seconds_per_slot,
+        slots_per_epoch,
+        epochs_per_sync_committee_period,
+        time_provider,
+    )
+}
+
 pub fn for_mainnet() -> Clock<SystemTimeProvider> {
     let genesis_time = MAINNET_GENESIS_TIME;
     let seconds_per_slot = configs::mainnet::SECONDS_PER_SLOT;
@@ -118,16 +150,65 @@ pub fn for_holesky() -> Clock<SystemTimeProvider> {
     from_system_time(genesis_time, seconds_per_slot, slots_per_epoch)
 }
 
+pub fn for_gnosis() -> Clock<SystemTimeProvider> {
+    let genesis_time = GNOSIS_GENESIS_TIME;
+    let seconds_per_slot = configs::gnosis::SECONDS_PER_SLOT;
+    let slots_per_epoch = presets::gnosis::SLOTS_PER_EPOCH;
+    let epochs_per_sync_committee_period = altair::gnosis::EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+    from_system_time_with_sync_committee_period(
+        genesis_time,
+        seconds_per_slot,
+        slots_per_epoch,
+        epochs_per_sync_committee_period,
+    )
+}
+
+pub fn for_chiado() -> Clock<SystemTimeProvider> {
+    let genesis_time = CHIADO_GENESIS_TIME;
+    let seconds_per_slot = configs::chiado::SECONDS_PER_SLOT;
+    let slots_per_epoch = presets::gnosis::SLOTS_PER_EPOCH;
+    let epochs_per_sync_committee_period = altair::gnosis::EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+    from_system_time_with_sync_committee_period(
+        genesis_time,
+        seconds_per_slot,
+        slots_per_epoch,
+        epochs_per_sync_committee_period,
+    )
+}
+
 impl<T: TimeProvider + Send + Sync> Clock<T> {
+    /// Construct a `Clock` using the `mainnet` value for `EPOCHS_PER_SYNC_COMMITTEE_PERIOD`.
     pub fn new(
         genesis_time: u64,
         seconds_per_slot: u64,
         slots_per_epoch: Slot,
         time_provider: T,
+    ) -> Self {
+        Self::with_sync_committee_period(
+            genesis_time,
+            seconds_per_slot,
+            slots_per_epoch,
+            altair::mainnet::EPOCHS_PER_SYNC_COMMITTEE_PERIOD,
+            time_provider,
+        )
+    }
+
+    pub fn with_sync_committee_period(
+        genesis_time: u64,
+        seconds_per_slot: u64,
+        slots_per_epoch: Slot,
+        epochs_per_sync_committee_period: Epoch,
+        time_provider: T,
     ) -> Self {
         let genesis_time = Duration::from_secs(genesis_time).as_nanos();
         let seconds_per_slot = Duration::from_secs(seconds_per_slot).as_nanos();
-        let inner = Inner { genesis_time, seconds_per_slot, slots_per_epoch, time_provider };
+        let inner = Inner {
+            genesis_time,
+            seconds_per_slot,
+            slots_per_epoch,
+            epochs_per_sync_committee_period,
+            time_provider,
+        };
         Self(Arc::new(inner))
     }
 
@@ -191,6 +272,97 @@ impl<T: TimeProvider + Send + Sync> Clock<T> {
             Duration::from_nanos(u128_to_u64(target_slot_in_nanos - current_time))
         }
     }
+
+    // Return the earliest slot the current time could be in given a clock skew of up to
+    // `MAXIMUM_GOSSIP_CLOCK_DISPARITY`, or `None` if before genesis.
+    pub fn earliest_permissible_slot(&self) -> Option<Slot> {
+        let disparity = MAXIMUM_GOSSIP_CLOCK_DISPARITY.as_nanos();
+        let current_time = self.get_current_time().checked_sub(disparity)?;
+        self.slot_at_time(current_time)
+    }
+
+    // Return the latest slot the current time could be in given a clock skew of up to
+    // `MAXIMUM_GOSSIP_CLOCK_DISPARITY`, or `None` if before genesis.
+    pub fn latest_permissible_slot(&self) -> Option<Slot> {
+        let disparity = MAXIMUM_GOSSIP_CLOCK_DISPARITY.as_nanos();
+        self.slot_at_time(self.get_current_time() + disparity)
+    }
+
+    /// Returns `true` if `slot` is the current slot, tolerating a clock skew of up to
+    /// `MAXIMUM_GOSSIP_CLOCK_DISPARITY`.
+    pub fn is_current_slot_with_disparity(&self, slot: Slot) -> bool {
+        self.is_within_slot_range_with_disparity(slot, 0)
+    }
+
+    /// Returns `true` if `slot <= current_slot <= slot + slot_range`, tolerating a clock skew of
+    /// up to `MAXIMUM_GOSSIP_CLOCK_DISPARITY`.
+    ///
+    /// For example, gossip attestations must satisfy this with
+    /// `ATTESTATION_PROPAGATION_SLOT_RANGE`.
+    pub fn is_within_slot_range_with_disparity(&self, slot: Slot, slot_range: Slot) -> bool {
+        let Some(latest_slot) = self.latest_permissible_slot() else { return false };
+        let earliest_slot = self.earliest_permissible_slot().unwrap_or_default();
+        slot <= latest_slot && slot.saturating_add(slot_range) >= earliest_slot
+    }
+
+    // Return the current slot and the index of the interval within it, or `None` if before
+    // genesis.
+    pub fn current_interval(&self) -> Option<(Slot, u64)> {
+        self.interval_at_time(self.get_current_time())
+    }
+
+    pub fn interval_at_time(&self, current_time: u128) -> Option<(Slot, u64)> {
+        let slot = self.slot_at_time(current_time)?;
+        let slot_start = slot_to_nanos(slot, self.seconds_per_slot, self.genesis_time);
+        let time_into_slot = current_time - slot_start;
+        let interval = time_into_slot * u128::from(INTERVALS_PER_SLOT) / self.seconds_per_slot;
+        Some((slot, u128_to_u64(interval)))
+    }
+
+    /// Return a `Duration` until the start of `interval` in `slot` relative to the current time
+    /// as determined by the clock. If the interval has started, return a `Duration` of 0.
+    pub fn duration_until_interval(&self, slot: Slot, interval: u64) -> Duration {
+        let current_time = self.get_current_time();
+        let target_in_nanos = slot_to_nanos(slot, self.seconds_per_slot, self.genesis_time) +
+            interval_offset_in_nanos(interval, self.seconds_per_slot);
+        target_in_nanos
+            .checked_sub(current_time)
+            .map(|t| Duration::from_nanos(u128_to_u64(t)))
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn start_slot_for(&self, epoch: Epoch) -> Slot {
+        epoch * self.slots_per_epoch
+    }
+
+    /// Return a `Duration` until the first slot of `epoch` relative to the current time as
+    /// determined by the clock. If `epoch` has started, return a `Duration` of 0.
+    pub fn duration_until_epoch(&self, epoch: Epoch) -> Duration {
+        self.duration_until_slot(self.start_slot_for(epoch))
+    }
+
+    #[inline]
+    pub fn sync_committee_period_for(&self, epoch: Epoch) -> u64 {
+        epoch / self.epochs_per_sync_committee_period
+    }
+
+    // Return the current sync committee period, or `None` if before genesis.
+    pub fn current_sync_committee_period(&self) -> Option<u64> {
+        let current_epoch = self.current_epoch()?;
+        Some(self.sync_committee_period_for(current_epoch))
+    }
+
+    #[inline]
+    pub fn start_epoch_for_sync_committee_period(&self, period: u64) -> Epoch {
+        period * self.epochs_per_sync_committee_period
+    }
+
+    /// Return a `Duration` until the first slot of the sync committee `period` relative to the
+    /// current time as determined by the clock. If `period` has started, return a `Duration` of 0.
+    pub fn duration_until_sync_committee_period(&self, period: u64) -> Duration {
+        self.duration_until_epoch(self.start_epoch_for_sync_committee_period(period))
+    }
 }
 
 pub type SystemClock = Clock<SystemTimeProvider>;
@@ -210,6 +382,46 @@ impl<T: TimeProvider + Send + Sync> Clock<T> {
             }
         }
     }
+
+    pub fn stream_intervals(&self) -> impl Stream<Item = (Slot, u64)> + '_ {
+        async_stream::stream! {
+            loop {
+                let (slot, interval) = self.current_interval().expect("after genesis");
+                yield (slot, interval);
+                let (next_slot, next_interval) = if interval + 1 < INTERVALS_PER_SLOT {
+                    (slot, interval + 1)
+                } else {
+                    (slot + 1, 0)
+                };
+                let duration_until_next_interval =
+                    self.duration_until_interval(next_slot, next_interval);
+                tokio::time::sleep(duration_until_next_interval).await;
+            }
+        }
+    }
+
+    pub fn stream_epochs(&self) -> impl Stream<Item = Epoch> + '_ {
+        async_stream::stream! {
+            loop {
+                let epoch = self.current_epoch().expect("after genesis");
+                yield epoch;
+                let duration_until_next_epoch = self.duration_until_epoch(epoch + 1);
+                tokio::time::sleep(duration_until_next_epoch).await;
+            }
+        }
+    }
+
+    pub fn stream_sync_committee_periods(&self) -> impl Stream<Item = u64> + '_ {
+        async_stream::stream! {
+            loop {
+                let period = self.current_sync_committee_period().expect("after genesis");
+                yield period;
+                let duration_until_next_period =
+                    self.duration_until_sync_committee_period(period + 1);
+                tokio::time::sleep(duration_until_next_period).await;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -232,6 +444,16 @@ mod tests {
             let mut tick = self.tick.lock().unwrap();
             *tick += self.seconds_per_slot;
         }
+
+        fn tick_nanos(&self, nanos: u128) {
+            let mut tick = self.tick.lock().unwrap();
+            *tick += nanos;
+        }
+
+        fn tick_interval(&self) {
+            let mut tick = self.tick.lock().unwrap();
+            *tick += self.seconds_per_slot / u128::from(INTERVALS_PER_SLOT);
+        }
     }
 
     impl TimeProvider for Arc<Ticker> {
@@ -283,6 +505,81 @@ mod tests {
         assert!(clock.current_slot().is_none());
     }
 
+    #[test]
+    fn test_intervals() {
+        let seconds_per_slot: u64 = 12;
+        let time_provider = new_ticker(seconds_per_slot);
+        let clock = Clock::new(0, seconds_per_slot, 32, time_provider.clone());
+        assert_eq!(clock.current_interval(), Some((0, 0)));
+        assert_eq!(clock.duration_until_interval(0, 1).as_secs(), 4);
+        for _ in 0..4 {
+            time_provider.tick();
+        }
+        assert_eq!(clock.current_interval(), Some((0, 1)));
+        assert_eq!(clock.duration_until_interval(0, 1), Duration::default());
+        assert_eq!(clock.duration_until_interval(0, 2).as_secs(), 4);
+        time_provider.tick_slot();
+        assert_eq!(clock.current_interval(), Some((1, 1)));
+        assert_eq!(clock.duration_until_interval(2, 0).as_secs(), 8);
+
+        // slots that do not divide evenly into intervals
+        let seconds_per_slot: u64 = 5;
+        let time_provider = new_ticker(seconds_per_slot);
+        let clock = Clock::new(0, seconds_per_slot, 16, time_provider.clone());
+        let duration_until_interval = clock.duration_until_interval(0, 1);
+        for _ in 0..2 {
+            time_provider.tick();
+        }
+        assert_eq!(clock.current_interval(), Some((0, 1)));
+        assert_eq!(clock.duration_until_interval(0, 1), Duration::default());
+        assert!(duration_until_interval > Duration::from_secs(1));
+        assert!(duration_until_interval < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_clock_disparity() {
+        let seconds_per_slot: u64 = 12;
+        let time_provider = new_ticker(seconds_per_slot);
+        let clock = Clock::new(1, seconds_per_slot, 32, time_provider.clone());
+        // within the disparity of genesis
+        time_provider.tick_nanos(MAXIMUM_GOSSIP_CLOCK_DISPARITY.as_nanos() + 1);
+        assert!(clock.before_genesis());
+        assert!(clock.is_current_slot_with_disparity(0));
+
+        let time_provider = new_ticker(seconds_per_slot);
+        let clock = Clock::new(0, seconds_per_slot, 32, time_provider.clone());
+        time_provider.tick_slot();
+        assert_eq!(clock.earliest_permissible_slot(), Some(0));
+        assert_eq!(clock.latest_permissible_slot(), Some(1));
+        assert!(clock.is_current_slot_with_disparity(0));
+        assert!(clock.is_current_slot_with_disparity(1));
+        assert!(!clock.is_current_slot_with_disparity(2));
+
+        time_provider.tick();
+        assert_eq!(clock.earliest_permissible_slot(), Some(1));
+        assert!(!clock.is_current_slot_with_disparity(0));
+        assert!(clock.is_within_slot_range_with_disparity(0, 1));
+        assert!(!clock.is_within_slot_range_with_disparity(2, 32));
+    }
+
+    #[test]
+    fn test_sync_committee_periods() {
+        let seconds_per_slot: u64 = 12;
+        let time_provider = new_ticker(seconds_per_slot);
+        let clock =
+            Clock::with_sync_committee_period(0, seconds_per_slot, 4, 2, time_provider.clone());
+        assert_eq!(clock.current_sync_committee_period(), Some(0));
+        assert_eq!(clock.duration_until_epoch(1).as_secs(), 48);
+        assert_eq!(clock.duration_until_sync_committee_period(1).as_secs(), 96);
+        for _ in 0..8 {
+            time_provider.tick_slot();
+        }
+        assert_eq!(clock.current_epoch(), Some(2));
+        assert_eq!(clock.current_sync_committee_period(), Some(1));
+        assert_eq!(clock.start_epoch_for_sync_committee_period(3), 6);
+        assert_eq!(clock.duration_until_sync_committee_period(1), Duration::default());
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     async fn test_slot_stream() {
@@ -309,6 +606,43 @@ mod tests {
         assert_eq!(slots, (current_slot..target_slot).collect::<Vec<_>>());
     }
 
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_interval_and_epoch_streams() {
+        use tokio_stream::StreamExt;
+
+        // note: make this very large so it is clear if the `TimeProvider` mocking is broken
+        let seconds_per_slot: u64 = 1200000000;
+        let time_provider = new_ticker(seconds_per_slot);
+        let clock = Clock::new(0, seconds_per_slot, 2, time_provider.clone());
+
+        let interval_stream = clock.stream_intervals();
+        tokio::pin!(interval_stream);
+        let mut intervals = vec![];
+        while let Some(interval) = interval_stream.next().await {
+            intervals.push(interval);
+            if intervals.len() == 4 {
+                break
+            }
+            time_provider.tick_interval();
+        }
+        assert_eq!(intervals, vec![(0, 0), (0, 1), (0, 2), (1, 0)]);
+
+        let epoch_stream = clock.stream_epochs();
+        tokio::pin!(epoch_stream);
+        let mut epochs = vec![];
+        while let Some(epoch) = epoch_stream.next().await {
+            epochs.push(epoch);
+            if epochs.len() == 3 {
+                break
+            }
+            for _ in 0..2 {
+                time_provider.tick_slot();
+            }
+        }
+        assert_eq!(epochs, vec![0, 1, 2]);
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     #[ignore = "uses wall clock time for mainnet params"]
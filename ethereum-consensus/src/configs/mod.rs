@@ -1,3 +1,5 @@
+pub mod chiado;
+pub mod gnosis;
 pub mod goerli;
 pub mod holesky;
 pub mod mainnet;
@@ -9,13 +11,14 @@ use crate::{
     primitives::{Epoch, ExecutionAddress, Gwei, Hash32, Version, U256},
 };
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct Config {
     pub preset_base: String,
     #[serde(rename = "CONFIG_NAME")]
     pub name: Network,
 
+    #[serde(serialize_with = "serialize_as_decimal")]
     pub terminal_total_difficulty: U256,
     pub terminal_block_hash: Hash32,
     pub terminal_block_hash_activation_epoch: Epoch,
@@ -58,3 +61,11 @@ pub struct Config {
     pub deposit_network_id: usize,
     pub deposit_contract_address: ExecutionAddress,
 }
+
+// NOTE: `U256` is rendered as hex by default but configs conventionally use decimal
+fn serialize_as_decimal<S: serde::Serializer>(
+    value: &U256,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
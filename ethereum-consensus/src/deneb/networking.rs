@@ -1,4 +1,54 @@
+use crate::{
+    deneb::blob_sidecar::BlobIdentifier,
+    primitives::{BlobIndex, Root, Slot, SubnetId},
+    ssz::prelude::*,
+};
+
 pub const MAX_REQUEST_BLOCKS_DENEB: usize = 2usize.pow(7);
 pub const MAX_REQUEST_BLOB_SIDECARS: usize = 768;
 pub const MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS: usize = 2usize.pow(12);
 pub const BLOB_SIDECAR_SUBNET_COUNT: usize = 6;
+
+pub fn compute_subnet_for_blob_sidecar(blob_index: BlobIndex) -> SubnetId {
+    (blob_index % BLOB_SIDECAR_SUBNET_COUNT) as SubnetId
+}
+
+pub type BeaconBlocksByRootRequest = List<Root, MAX_REQUEST_BLOCKS_DENEB>;
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct BlobSidecarsByRangeRequest {
+    #[serde(with = "crate::serde::as_str")]
+    pub start_slot: Slot,
+    #[serde(with = "crate::serde::as_str")]
+    pub count: u64,
+}
+
+pub type BlobSidecarsByRootRequest = List<BlobIdentifier, MAX_REQUEST_BLOB_SIDECARS>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::try_bytes_from_hex_str;
+
+    #[test]
+    fn test_compute_subnet_for_blob_sidecar() {
+        for (blob_index, expected) in [(0, 0), (5, 5), (6, 0), (7, 1), (13, 1)] {
+            assert_eq!(compute_subnet_for_blob_sidecar(blob_index), expected);
+        }
+    }
+
+    #[test]
+    fn test_blob_sidecars_by_range_request_encoding() {
+        let mut request = BlobSidecarsByRangeRequest { start_slot: 8675309, count: 32 };
+        let expected = try_bytes_from_hex_str("0xed5f8400000000002000000000000000").unwrap();
+        assert_eq!(serialize(&request).unwrap(), expected);
+        assert_eq!(BlobSidecarsByRangeRequest::deserialize(&expected).unwrap(), request);
+        let expected = try_bytes_from_hex_str(
+            "0x5bdaacea0b4b8cf78eafab0a31f7135c0b01ff10a0a17808a48031d69a32f998",
+        )
+        .unwrap();
+        assert_eq!(request.hash_tree_root().unwrap().as_ref(), expected);
+    }
+}
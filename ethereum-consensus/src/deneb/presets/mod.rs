@@ -1,6 +1,9 @@
+pub mod gnosis;
 pub mod mainnet;
 pub mod minimal;
 
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub struct Preset {
     pub field_elements_per_blob: usize,
     pub max_blob_commitments_per_block: usize,
@@ -5,6 +5,7 @@ use crate::{
     phase0::{AttestationData, BeaconBlockHeader, Checkpoint},
     primitives::{BlsPublicKey, BlsSignature, Bytes32, Epoch, Hash32, Root, Slot, ValidatorIndex},
     ssz::prelude::*,
+    state_transition::PresetValueMismatch,
     Fork,
 };
 use thiserror::Error;
@@ -23,6 +24,9 @@ pub enum Error {
     #[cfg(feature = "serde")]
     #[error("{0}")]
     Yaml(#[from] serde_yaml::Error),
+    #[cfg(feature = "serde")]
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
     #[error("requested element {requested} but collection only has {bound} elements")]
     OutOfBounds { requested: usize, bound: usize },
     #[error("collection cannot be empty")]
@@ -45,6 +49,8 @@ pub enum Error {
     "the requested epoch {requested} is not in the required current epoch {current} or previous epoch {previous}"
     )]
     InvalidEpoch { requested: Epoch, previous: Epoch, current: Epoch },
+    #[error("duties for epoch {requested} cannot be computed from a state at epoch {current}")]
+    InvalidDutyEpoch { requested: Epoch, current: Epoch },
     #[error(
         "transition requested from a later fork {destination_fork:?} to an earlier fork {source_fork:?}"
     )]
@@ -54,6 +60,11 @@ pub enum Error {
     #[cfg(feature = "serde")]
     #[error("an unknown preset {0} was supplied when constructing context")]
     UnknownPreset(String),
+    #[cfg(feature = "serde")]
+    #[error("the supplied {fork} preset does not match the {preset_base} preset")]
+    PresetMismatch { preset_base: String, fork: Fork },
+    #[error("preset is incompatible with the compile-time preset: {}", format_mismatches(.0))]
+    IncompatiblePreset(Vec<PresetValueMismatch>),
     #[error(transparent)]
     ExecutionEngine(#[from] ExecutionEngineError),
     #[error(transparent)]
@@ -226,6 +237,10 @@ pub(crate) fn invalid_header_error(error: InvalidBeaconBlockHeader) -> Error {
     Error::InvalidBlock(Box::new(InvalidBlock::Header(error)))
 }
 
+fn format_mismatches(mismatches: &[PresetValueMismatch]) -> String {
+    mismatches.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
 pub(crate) fn invalid_operation_error(error: InvalidOperation) -> Error {
     Error::InvalidBlock(Box::new(InvalidBlock::InvalidOperation(error)))
 }
@@ -1,7 +1,10 @@
 use std::fmt;
 
 // Identifies the fork of the protocol the associated object belongs to.
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+// NOTE: variants are declared in activation order so the derived ordering follows the protocol.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum Fork {
     Phase0,
@@ -22,3 +25,28 @@ impl fmt::Display for Fork {
         }
     }
 }
+
+impl Fork {
+    pub const ALL: [Self; 5] =
+        [Self::Phase0, Self::Altair, Self::Bellatrix, Self::Capella, Self::Deneb];
+
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Self::Phase0 => Some(Self::Altair),
+            Self::Altair => Some(Self::Bellatrix),
+            Self::Bellatrix => Some(Self::Capella),
+            Self::Capella => Some(Self::Deneb),
+            Self::Deneb => None,
+        }
+    }
+
+    pub fn previous(&self) -> Option<Self> {
+        match self {
+            Self::Phase0 => None,
+            Self::Altair => Some(Self::Phase0),
+            Self::Bellatrix => Some(Self::Altair),
+            Self::Capella => Some(Self::Bellatrix),
+            Self::Deneb => Some(Self::Capella),
+        }
+    }
+}
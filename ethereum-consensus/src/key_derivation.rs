@@ -0,0 +1,320 @@
+//! Hierarchical derivation of BLS keys following
+//! [EIP-2333](https://eips.ethereum.org/EIPS/eip-2333) with the path conventions of
+//! [EIP-2334](https://eips.ethereum.org/EIPS/eip-2334).
+use crate::crypto::{hash, PublicKey, SecretKey};
+use bip39::Mnemonic;
+use hkdf::Hkdf;
+use ruint::{aliases::U256, uint, Uint};
+use sha2::Sha256;
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+type U384 = Uint<384, 6>;
+
+const SALT: &[u8; 20] = b"BLS-SIG-KEYGEN-SALT-";
+const L: usize = 48;
+const R: U384 =
+    uint!(52435875175126190479447740508185965837690552500527637822603658699938581184513_U384);
+const K: usize = 32;
+const LAMPORT_COUNT: usize = 255;
+const LAMPORT_L: usize = K * LAMPORT_COUNT;
+
+const MIN_SEED_LEN: usize = 32;
+
+pub const PURPOSE: u32 = 12381;
+pub const COIN_TYPE: u32 = 3600;
+
+pub type Seed = [u8; 64];
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid derivation path `{0}`")]
+    InvalidPath(String),
+    #[error("seed must be at least {MIN_SEED_LEN} bytes but found {0} bytes")]
+    SeedTooShort(usize),
+    #[error("{0}")]
+    Mnemonic(#[from] bip39::Error),
+}
+
+/// A derivation path like `m/12381/3600/0/0/0`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path(Vec<u32>);
+
+impl Path {
+    pub fn new(indices: Vec<u32>) -> Self {
+        Self(indices)
+    }
+
+    /// Path to the withdrawal key of the validator at `index`, i.e. `m/12381/3600/index/0`.
+    pub fn withdrawal(index: u32) -> Self {
+        Self(vec![PURPOSE, COIN_TYPE, index, 0])
+    }
+
+    /// Path to the signing key of the validator at `index`, i.e. `m/12381/3600/index/0/0`.
+    pub fn signing(index: u32) -> Self {
+        Self(vec![PURPOSE, COIN_TYPE, index, 0, 0])
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+
+    pub fn child(&self, index: u32) -> Self {
+        let mut indices = self.0.clone();
+        indices.push(index);
+        Self(indices)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{index}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Path {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = s.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(Error::InvalidPath(s.to_string()))
+        }
+        segments
+            .map(|segment| {
+                if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_digit()) {
+                    return None
+                }
+                segment.parse::<u32>().ok()
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+            .ok_or_else(|| Error::InvalidPath(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyPair {
+    pub private_key: SecretKey,
+    pub public_key: PublicKey,
+    pub path: Path,
+}
+
+impl KeyPair {
+    fn new(private_key: SecretKey, path: Path) -> Self {
+        let public_key = private_key.public_key();
+        Self { private_key, public_key, path }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidatorKeys {
+    pub signing: KeyPair,
+    pub withdrawal: KeyPair,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Key(U256);
+
+impl From<&SecretKey> for Key {
+    fn from(secret_key: &SecretKey) -> Self {
+        Self(U256::from_be_bytes(secret_key.clone().to_bytes()))
+    }
+}
+
+impl From<Key> for SecretKey {
+    fn from(key: Key) -> Self {
+        let key: [u8; 32] = key.0.to_be_bytes();
+        // NOTE: `hkdf_mod_r` only returns non-zero keys in the field
+        SecretKey::try_from(key.as_ref()).unwrap()
+    }
+}
+
+fn bytes_split<const M: usize, const N: usize>(input: &[u8]) -> Vec<&[u8]> {
+    debug_assert!(M % N == 0);
+    debug_assert!(input.len() == M);
+
+    input.chunks_exact(N).collect()
+}
+
+fn ikm_to_lamport_secret_key<'a>(ikm: &[u8], salt: &[u8], output: &'a mut [u8]) -> Vec<&'a [u8]> {
+    let hk = Hkdf::<Sha256>::new(Some(salt), ikm);
+    hk.expand(&[], output).expect("length L is valid");
+    bytes_split::<LAMPORT_L, K>(output)
+}
+
+fn flip_bits(input: [u8; 32]) -> Vec<u8> {
+    input.into_iter().map(|i| !i).collect()
+}
+
+fn parent_key_to_lamport_public_key(key: &Key, index: u32) -> [u8; 32] {
+    let salt = index.to_be_bytes();
+    let ikm: [u8; 32] = key.0.to_be_bytes();
+    let mut output_0 = [0u8; LAMPORT_L];
+    let lamport_0 = ikm_to_lamport_secret_key(&ikm, &salt, &mut output_0);
+    let not_ikm = flip_bits(ikm);
+    let mut output_1 = [0u8; LAMPORT_L];
+    let lamport_1 = ikm_to_lamport_secret_key(&not_ikm, &salt, &mut output_1);
+    let mut lamport_public_key = Vec::with_capacity(LAMPORT_L);
+    lamport_0.into_iter().map(hash).for_each(|data| {
+        lamport_public_key.extend_from_slice(data.as_ref());
+    });
+    lamport_1.into_iter().map(hash).for_each(|data| {
+        lamport_public_key.extend_from_slice(data.as_ref());
+    });
+    debug_assert_eq!(lamport_public_key.len(), 16320);
+    let compressed_lamport_public_key = hash(&lamport_public_key);
+    compressed_lamport_public_key.as_ref().try_into().unwrap()
+}
+
+fn hkdf_mod_r(input: &[u8]) -> Key {
+    let mut key = U384::ZERO;
+    let mut salt = hash(SALT);
+    // safety: `L` fits in type by definition
+    let l = L as u8;
+    let key_info = [0, l];
+    let mut ikm = input.to_vec();
+    ikm.push(0);
+
+    while key == U384::ZERO {
+        let hk = Hkdf::<Sha256>::new(Some(salt.as_ref()), &ikm);
+        let mut okm = [0u8; L];
+        hk.expand(&key_info, &mut okm).expect("length L is valid");
+        let inner = U384::from_be_bytes(okm);
+        key = inner % R;
+
+        salt = hash(salt.as_ref());
+    }
+
+    // ensure we are in the field
+    debug_assert_eq!(key % R, key);
+
+    let key_bytes: [u8; L] = key.to_be_bytes();
+    let inner: [u8; 32] = key_bytes[16..].try_into().unwrap();
+    Key(U256::from_be_bytes(inner))
+}
+
+fn derive_child_key(parent_key: &Key, index: u32) -> Key {
+    let compressed_lamport_public_key = parent_key_to_lamport_public_key(parent_key, index);
+    hkdf_mod_r(&compressed_lamport_public_key)
+}
+
+fn derive_path(key: Key, path: &Path) -> Key {
+    path.indices().iter().fold(key, |key, &index| derive_child_key(&key, index))
+}
+
+/// Recovers the seed of a BIP-39 `phrase`, salted with the optional `passphrase`.
+pub fn seed_from_mnemonic(phrase: &str, passphrase: Option<&str>) -> Result<Seed, Error> {
+    let mnemonic = Mnemonic::from_str(phrase)?;
+    Ok(mnemonic.to_seed(passphrase.unwrap_or("")))
+}
+
+pub fn derive_master_sk(seed: &[u8]) -> Result<SecretKey, Error> {
+    if seed.len() < MIN_SEED_LEN {
+        return Err(Error::SeedTooShort(seed.len()))
+    }
+    Ok(hkdf_mod_r(seed).into())
+}
+
+pub fn derive_child_sk(parent_sk: &SecretKey, index: u32) -> SecretKey {
+    derive_child_key(&parent_sk.into(), index).into()
+}
+
+/// Derives the key pair at `path` below the master key `master_sk`.
+pub fn derive_key_pair(master_sk: &SecretKey, path: &Path) -> KeyPair {
+    let key = derive_path(master_sk.into(), path);
+    KeyPair::new(key.into(), path.clone())
+}
+
+/// Derives the signing and withdrawal keys of the validator at `index` below `master_sk`.
+pub fn derive_validator_keys(master_sk: &SecretKey, index: u32) -> ValidatorKeys {
+    let withdrawal_path = Path::withdrawal(index);
+    let withdrawal_key = derive_path(master_sk.into(), &withdrawal_path);
+    // the signing key is the child of the withdrawal key, so reuse the shared prefix
+    let signing_key = derive_child_key(&withdrawal_key, 0);
+    ValidatorKeys {
+        signing: KeyPair::new(signing_key.into(), withdrawal_path.child(0)),
+        withdrawal: KeyPair::new(withdrawal_key.into(), withdrawal_path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_key_derive() {
+        let seed = seed_from_mnemonic("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about", Some("TREZOR")).unwrap();
+        let expected_seed = [
+            197, 82, 87, 195, 96, 192, 124, 114, 2, 154, 235, 193, 181, 60, 5, 237, 3, 98, 173,
+            163, 142, 173, 62, 62, 158, 250, 55, 8, 229, 52, 149, 83, 31, 9, 166, 152, 117, 153,
+            209, 130, 100, 193, 225, 201, 47, 44, 241, 65, 99, 12, 122, 60, 74, 183, 200, 27, 47,
+            0, 22, 152, 231, 70, 59, 4,
+        ];
+        assert_eq!(seed, expected_seed);
+    }
+
+    // Test cases from EIP-2333:
+    #[test]
+    fn test_eip_2333_vectors() {
+        let test_cases = [
+            (
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+                uint!(6083874454709270928345386274498605044986640685124978867557563392430687146096_U256),
+                0,
+                uint!(20397789859736650942317412262472558107875392172444076792671091975210932703118_U256),
+            ),
+            (
+                "3141592653589793238462643383279502884197169399375105820974944592",
+                uint!(29757020647961307431480504535336562678282505419141012933316116377660817309383_U256),
+                3141592653,
+                uint!(25457201688850691947727629385191704516744796114925897962676248250929345014287_U256),
+            ),
+            (
+                "0099FF991111002299DD7744EE3355BBDD8844115566CC55663355668888CC00",
+                uint!(27580842291869792442942448775674722299803720648445448686099262467207037398656_U256),
+                4294967295,
+                uint!(29358610794459428860402234341874281240803786294062035874021252734817515685787_U256),
+            ),
+            (
+                "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+                uint!(19022158461524446591288038168518313374041767046816487870552872741050760015818_U256),
+                42,
+                uint!(31372231650479070279774297061823572166496564838472787488249775572789064611981_U256),
+            ),
+        ];
+        for (seed, expected_master_sk, index, expected_child_sk) in test_cases {
+            let seed = hex::decode(seed).unwrap();
+            let master_sk = derive_master_sk(&seed).unwrap();
+            assert_eq!(Key::from(&master_sk).0, expected_master_sk);
+            let child_sk = derive_child_sk(&master_sk, index);
+            assert_eq!(Key::from(&child_sk).0, expected_child_sk);
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        let path: Path = "m/12381/3600/5/0/0".parse().unwrap();
+        assert_eq!(path, Path::signing(5));
+        assert_eq!(path.to_string(), "m/12381/3600/5/0/0");
+        let path: Path = "m/12381/3600/5/0".parse().unwrap();
+        assert_eq!(path, Path::withdrawal(5));
+        assert_eq!("m".parse::<Path>().unwrap(), Path::default());
+
+        for path in ["", "12381/3600", "m/", "m/12381/", "m/-1", "m/+1", "m/44'/60", "m/4294967296"]
+        {
+            assert!(path.parse::<Path>().is_err(), "{path}");
+        }
+
+        let master_sk = derive_master_sk(&[7u8; 32]).unwrap();
+        let ValidatorKeys { signing, withdrawal } = derive_validator_keys(&master_sk, 5);
+        let expected_signing = derive_key_pair(&master_sk, &signing.path);
+        assert_eq!(signing.public_key, expected_signing.public_key);
+        let expected_withdrawal = derive_key_pair(&master_sk, &withdrawal.path);
+        assert_eq!(withdrawal.public_key, expected_withdrawal.public_key);
+    }
+}
@@ -0,0 +1,487 @@
+//! Support for [EIP-2335](https://eips.ethereum.org/EIPS/eip-2335) keystores.
+use crate::crypto::{self, hash, PublicKey, SecretKey};
+use aes::cipher::{KeyIvInit, StreamCipher};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+use uuid::Uuid;
+
+pub const KEYSTORE_VERSION: u32 = 4;
+
+const DERIVED_KEY_LEN: usize = 32;
+const SALT_LEN: usize = 32;
+const SCRYPT_LOG_N: u8 = 17;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+const PBKDF2_PRF: &str = "hmac-sha256";
+
+const CIPHER_FN: &str = "aes-128-ctr";
+const AES_IV_LEN: usize = 16;
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+const CHECKSUM_FN: &str = "sha256";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("unsupported keystore version {0}")]
+    UnsupportedVersion(u32),
+    #[error("unsupported cipher function `{0}`")]
+    UnsupportedCipher(String),
+    #[error("unsupported checksum function `{0}`")]
+    UnsupportedChecksum(String),
+    #[error("unsupported pseudorandom function `{0}`")]
+    UnsupportedPrf(String),
+    #[error("invalid key derivation parameters: {0}")]
+    InvalidKdfParams(String),
+    #[error("invalid cipher parameters: expected an iv of {AES_IV_LEN} bytes but found {0} bytes")]
+    InvalidIv(usize),
+    #[error("checksum mismatch: the passphrase is incorrect or the keystore is corrupted")]
+    ChecksumMismatch,
+    #[error("decrypted secret key does not correspond to the public key {0:?}")]
+    PublicKeyMismatch(PublicKey),
+    #[error("{0}")]
+    Crypto(#[from] crypto::Error),
+}
+
+mod as_hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(data: T, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(&hex::encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let encoding = String::deserialize(d)?;
+        let encoding = encoding.strip_prefix("0x").unwrap_or(&encoding);
+        hex::decode(encoding).map_err(D::Error::custom)
+    }
+}
+
+// EIP-2335 encodes the public key without a `0x` prefix, unlike the rest of this crate.
+mod as_optional_hex_public_key {
+    use crate::crypto::PublicKey;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &Option<PublicKey>, s: S) -> Result<S::Ok, S::Error> {
+        match data {
+            Some(public_key) => super::as_hex_bytes::serialize(&public_key[..], s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<PublicKey>, D::Error> {
+        let Some(encoding) = Option::<String>::deserialize(d)? else { return Ok(None) };
+        let encoding = encoding.strip_prefix("0x").unwrap_or(&encoding);
+        let bytes = hex::decode(encoding).map_err(D::Error::custom)?;
+        PublicKey::try_from(bytes.as_ref()).map(Some).map_err(D::Error::custom)
+    }
+}
+
+fn normalize_passphrase(passphrase: &str) -> String {
+    let mut passphrase = passphrase.nfkd().collect::<String>();
+    passphrase.retain(|c| !c.is_control());
+    passphrase
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "function", content = "params", rename_all = "lowercase")]
+pub enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u32,
+        p: u32,
+        r: u32,
+        #[serde(with = "as_hex_bytes")]
+        salt: Vec<u8>,
+    },
+    Pbkdf2 {
+        dklen: usize,
+        c: u32,
+        prf: String,
+        #[serde(with = "as_hex_bytes")]
+        salt: Vec<u8>,
+    },
+}
+
+impl KdfParams {
+    pub fn scrypt_with_random_salt() -> Self {
+        Self::Scrypt {
+            dklen: DERIVED_KEY_LEN,
+            n: 1 << SCRYPT_LOG_N,
+            p: SCRYPT_P,
+            r: SCRYPT_R,
+            salt: random_bytes::<SALT_LEN>().to_vec(),
+        }
+    }
+
+    pub fn pbkdf2_with_random_salt(c: u32) -> Self {
+        Self::Pbkdf2 {
+            dklen: DERIVED_KEY_LEN,
+            c,
+            prf: PBKDF2_PRF.to_string(),
+            salt: random_bytes::<SALT_LEN>().to_vec(),
+        }
+    }
+
+    // Keeps the function and its cost parameters but draws a new salt.
+    fn with_random_salt(&self) -> Self {
+        let mut params = self.clone();
+        match &mut params {
+            Self::Scrypt { salt, .. } | Self::Pbkdf2 { salt, .. } => {
+                *salt = random_bytes::<SALT_LEN>().to_vec()
+            }
+        }
+        params
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Vec<u8>, Error> {
+        let passphrase = normalize_passphrase(passphrase);
+        match self {
+            Self::Scrypt { dklen, n, p, r, salt } => {
+                if *dklen < DERIVED_KEY_LEN {
+                    return Err(Error::InvalidKdfParams(format!("dklen {dklen} is too small")))
+                }
+                if *n < 2 || !n.is_power_of_two() {
+                    return Err(Error::InvalidKdfParams(format!("n {n} is not a power of two")))
+                }
+                let log_n = n.trailing_zeros() as u8;
+                let params = scrypt::Params::new(log_n, *r, *p, *dklen)
+                    .map_err(|err| Error::InvalidKdfParams(err.to_string()))?;
+                let mut key = vec![0u8; *dklen];
+                scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+                    .map_err(|err| Error::InvalidKdfParams(err.to_string()))?;
+                Ok(key)
+            }
+            Self::Pbkdf2 { dklen, c, prf, salt } => {
+                if prf != PBKDF2_PRF {
+                    return Err(Error::UnsupportedPrf(prf.clone()))
+                }
+                if *dklen < DERIVED_KEY_LEN {
+                    return Err(Error::InvalidKdfParams(format!("dklen {dklen} is too small")))
+                }
+                if *c == 0 {
+                    return Err(Error::InvalidKdfParams("c must be positive".to_string()))
+                }
+                let mut key = vec![0u8; *dklen];
+                pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, *c, &mut key);
+                Ok(key)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Kdf {
+    #[serde(flatten)]
+    pub params: KdfParams,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmptyParams {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checksum {
+    pub function: String,
+    pub params: EmptyParams,
+    #[serde(with = "as_hex_bytes")]
+    pub message: Vec<u8>,
+}
+
+impl Checksum {
+    fn new(derived_key: &[u8], cipher_text: &[u8]) -> Self {
+        Self {
+            function: CHECKSUM_FN.to_string(),
+            params: EmptyParams {},
+            message: compute_checksum(derived_key, cipher_text).to_vec(),
+        }
+    }
+}
+
+fn compute_checksum(derived_key: &[u8], cipher_text: &[u8]) -> [u8; 32] {
+    let mut pre_image = derived_key[16..DERIVED_KEY_LEN].to_vec();
+    pre_image.extend_from_slice(cipher_text);
+    hash(pre_image).as_ref().try_into().expect("is correct length")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    #[serde(with = "as_hex_bytes")]
+    pub iv: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cipher {
+    pub function: String,
+    pub params: CipherParams,
+    #[serde(with = "as_hex_bytes")]
+    pub message: Vec<u8>,
+}
+
+fn apply_cipher(derived_key: &[u8], iv: &[u8], data: &mut [u8]) -> Result<(), Error> {
+    let mut cipher = Aes128Ctr::new_from_slices(&derived_key[..16], iv)
+        .map_err(|_| Error::InvalidIv(iv.len()))?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Crypto {
+    pub kdf: Kdf,
+    pub checksum: Checksum,
+    pub cipher: Cipher,
+}
+
+impl Crypto {
+    fn encrypt(
+        secret: &[u8],
+        passphrase: &str,
+        kdf: KdfParams,
+        iv: [u8; AES_IV_LEN],
+    ) -> Result<Self, Error> {
+        let derived_key = kdf.derive_key(passphrase)?;
+        let mut cipher_text = secret.to_vec();
+        apply_cipher(&derived_key, &iv, &mut cipher_text)?;
+        let checksum = Checksum::new(&derived_key, &cipher_text);
+        let cipher = Cipher {
+            function: CIPHER_FN.to_string(),
+            params: CipherParams { iv: iv.to_vec() },
+            message: cipher_text,
+        };
+        Ok(Self { kdf: Kdf { params: kdf, message: String::new() }, checksum, cipher })
+    }
+
+    fn decrypt(&self, passphrase: &str) -> Result<Vec<u8>, Error> {
+        if self.checksum.function != CHECKSUM_FN {
+            return Err(Error::UnsupportedChecksum(self.checksum.function.clone()))
+        }
+        if self.cipher.function != CIPHER_FN {
+            return Err(Error::UnsupportedCipher(self.cipher.function.clone()))
+        }
+        let derived_key = self.kdf.params.derive_key(passphrase)?;
+        let cipher_text = &self.cipher.message;
+        if compute_checksum(&derived_key, cipher_text)[..] != self.checksum.message[..] {
+            return Err(Error::ChecksumMismatch)
+        }
+        let mut secret = cipher_text.clone();
+        apply_cipher(&derived_key, &self.cipher.params.iv, &mut secret)?;
+        Ok(secret)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub crypto: Crypto,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "as_optional_hex_public_key")]
+    pub pubkey: Option<PublicKey>,
+    pub path: String,
+    pub uuid: Uuid,
+    pub version: u32,
+}
+
+impl Keystore {
+    /// Encrypts `secret_key` under `passphrase` with scrypt, a random salt and a random iv.
+    pub fn encrypt(secret_key: &SecretKey, passphrase: &str, path: &str) -> Result<Self, Error> {
+        let kdf = KdfParams::scrypt_with_random_salt();
+        Self::encrypt_with_params(secret_key, passphrase, path, kdf, random_bytes())
+    }
+
+    pub fn encrypt_with_params(
+        secret_key: &SecretKey,
+        passphrase: &str,
+        path: &str,
+        kdf: KdfParams,
+        iv: [u8; AES_IV_LEN],
+    ) -> Result<Self, Error> {
+        let secret = secret_key.clone().to_bytes();
+        Ok(Self {
+            crypto: Crypto::encrypt(&secret, passphrase, kdf, iv)?,
+            description: None,
+            pubkey: Some(secret_key.public_key()),
+            path: path.to_string(),
+            uuid: Uuid::new_v4(),
+            version: KEYSTORE_VERSION,
+        })
+    }
+
+    /// Recovers the secret key, verifying the checksum and, if present, the public key.
+    pub fn decrypt(&self, passphrase: &str) -> Result<SecretKey, Error> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(Error::UnsupportedVersion(self.version))
+        }
+        let secret = self.crypto.decrypt(passphrase)?;
+        let secret_key = SecretKey::try_from(secret.as_ref())?;
+        if let Some(public_key) = self.pubkey.as_ref() {
+            if secret_key.public_key() != *public_key {
+                return Err(Error::PublicKeyMismatch(public_key.clone()))
+            }
+        }
+        Ok(secret_key)
+    }
+
+    /// Returns a keystore holding the same secret key under `new_passphrase`.
+    ///
+    /// The key derivation function and its cost parameters are kept, while the salt, iv and
+    /// uuid are regenerated.
+    pub fn reencrypt(&self, passphrase: &str, new_passphrase: &str) -> Result<Self, Error> {
+        let secret_key = self.decrypt(passphrase)?;
+        let kdf = self.crypto.kdf.params.with_random_salt();
+        let mut keystore = Self::encrypt_with_params(
+            &secret_key,
+            new_passphrase,
+            &self.path,
+            kdf,
+            random_bytes(),
+        )?;
+        keystore.description = self.description.clone();
+        Ok(keystore)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑";
+    const SECRET: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+    const PUBLIC_KEY: &str = "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07";
+
+    // Test vectors from EIP-2335:
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "scrypt",
+                "params": {
+                    "dklen": 32,
+                    "n": 262144,
+                    "p": 1,
+                    "r": 8,
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "d2217fe5f3e9a1e34581ef8a78f7c9928e436d36dacc5e846690a5581e8ea484"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "06ae90d55fe0a6e9c5c3bc5b170827b2e5cce3929ed3f116c2811e6366dfe20f"
+            }
+        },
+        "description": "This is a test keystore that uses scrypt to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/3141592653/589793238",
+        "uuid": "1d85ae20-35c5-4611-98e8-aa14a633906f",
+        "version": 4
+    }"#;
+
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "dklen": 32,
+                    "c": 262144,
+                    "prf": "hmac-sha256",
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+            }
+        },
+        "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/0/0",
+        "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+        "version": 4
+    }"#;
+
+    fn secret_key() -> SecretKey {
+        SecretKey::try_from(hex::decode(SECRET).unwrap().as_ref()).unwrap()
+    }
+
+    #[test]
+    fn test_keystore_pubkey() {
+        let public_key = hex::decode(PUBLIC_KEY).unwrap();
+        let expected_public_key = PublicKey::try_from(public_key.as_ref()).unwrap();
+        assert_eq!(expected_public_key, secret_key().public_key());
+    }
+
+    #[test]
+    fn test_decrypt_pbkdf2() {
+        let keystore: Keystore = serde_json::from_str(PBKDF2_KEYSTORE).unwrap();
+        let recovered = keystore.decrypt(PASSPHRASE).unwrap();
+        assert_eq!(recovered.to_bytes(), secret_key().to_bytes());
+
+        assert!(matches!(keystore.decrypt("wrong passphrase"), Err(Error::ChecksumMismatch)));
+
+        let encoding = serde_json::to_value(&keystore).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(PBKDF2_KEYSTORE).unwrap();
+        assert_eq!(encoding, expected);
+    }
+
+    #[test]
+    #[ignore = "strong key parameters take a long time to run"]
+    fn test_decrypt_scrypt() {
+        let keystore: Keystore = serde_json::from_str(SCRYPT_KEYSTORE).unwrap();
+        let recovered = keystore.decrypt(PASSPHRASE).unwrap();
+        assert_eq!(recovered.to_bytes(), secret_key().to_bytes());
+
+        let KdfParams::Scrypt { salt, .. } = keystore.crypto.kdf.params.clone() else {
+            unreachable!("test vector uses scrypt")
+        };
+        let kdf = KdfParams::Scrypt { dklen: 32, n: 262144, p: 1, r: 8, salt };
+        let iv = keystore.crypto.cipher.params.iv.clone().try_into().unwrap();
+        let encrypted =
+            Keystore::encrypt_with_params(&secret_key(), PASSPHRASE, &keystore.path, kdf, iv)
+                .unwrap();
+        assert_eq!(encrypted.crypto, keystore.crypto);
+    }
+
+    #[test]
+    fn test_reencrypt() {
+        let kdf = KdfParams::pbkdf2_with_random_salt(2);
+        let keystore = Keystore::encrypt_with_params(
+            &secret_key(),
+            "first",
+            "m/12381/3600/0/0/0",
+            kdf,
+            [7; 16],
+        )
+        .unwrap();
+        let reencrypted = keystore.reencrypt("first", "second\u{7f}").unwrap();
+        assert_ne!(reencrypted.crypto, keystore.crypto);
+        assert_eq!(reencrypted.path, keystore.path);
+
+        // control characters are stripped from passphrases before key derivation
+        let recovered = reencrypted.decrypt("second").unwrap();
+        assert_eq!(recovered.to_bytes(), secret_key().to_bytes());
+        assert!(matches!(reencrypted.decrypt("first"), Err(Error::ChecksumMismatch)));
+    }
+}
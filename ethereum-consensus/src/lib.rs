@@ -9,16 +9,24 @@ pub mod deneb;
 pub mod domains;
 pub mod error;
 mod fork;
+#[cfg(feature = "key-derivation")]
+pub mod key_derivation;
+#[cfg(feature = "keystore")]
+pub mod keystore;
 pub mod networking;
 pub mod networks;
 pub mod phase0;
 pub mod primitives;
 #[cfg(feature = "serde")]
 pub mod serde;
+pub mod signer;
 pub mod signing;
+#[cfg(feature = "serde")]
+pub mod slashing_protection;
 pub mod ssz;
 pub mod state_transition;
 pub mod types;
+pub mod weak_subjectivity;
 
 pub use error::Error;
 pub use fork::Fork;
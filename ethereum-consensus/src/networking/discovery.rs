@@ -0,0 +1,269 @@
+//! Helpers for the consensus-specific content of node records used in discovery.
+use crate::{
+    altair::constants::SYNC_COMMITTEE_SUBNET_COUNT,
+    networking::{Enr, Multiaddr},
+    phase0::networking::{EnrForkId, ATTESTATION_SUBNET_COUNT},
+    primitives::{ForkDigest, NodeId},
+    ssz::prelude::*,
+};
+use enr::k256::ecdsa::SigningKey;
+use multiaddr::Protocol;
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+};
+use thiserror::Error;
+
+pub const ETH2_ENR_KEY: &str = "eth2";
+pub const ATTESTATION_SUBNET_ENR_KEY: &str = "attnets";
+pub const SYNC_COMMITTEE_SUBNET_ENR_KEY: &str = "syncnets";
+pub const QUIC_ENR_KEY: &str = "quic";
+pub const QUIC6_ENR_KEY: &str = "quic6";
+
+pub type AttestationSubnets = Bitvector<ATTESTATION_SUBNET_COUNT>;
+pub type SyncCommitteeSubnets = Bitvector<SYNC_COMMITTEE_SUBNET_COUNT>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("ENR is missing the `{0}` key")]
+    MissingKey(&'static str),
+    #[error("could not decode the `{key}` key of the ENR: {source}")]
+    InvalidValue { key: &'static str, source: DeserializeError },
+    #[error("could not decode the `{0}` port of the ENR")]
+    InvalidPort(&'static str),
+    #[error("{0}")]
+    Serialize(#[from] SerializeError),
+    #[error("could not build ENR: {0}")]
+    Enr(#[from] enr::EnrError),
+    #[error("could not parse ENR `{enr}`: {reason}")]
+    Parse { enr: String, reason: String },
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+fn decode_value<T: Deserialize>(enr: &Enr, key: &'static str) -> Result<T, Error> {
+    let value = enr.get(key).ok_or(Error::MissingKey(key))?;
+    T::deserialize(value).map_err(|source| Error::InvalidValue { key, source })
+}
+
+/// Decodes the `ENRForkID` advertised under the `eth2` key.
+pub fn eth2_field(enr: &Enr) -> Result<EnrForkId, Error> {
+    decode_value(enr, ETH2_ENR_KEY)
+}
+
+/// Returns the fork digest of the `eth2` key, which identifies the fork the node is on.
+pub fn fork_digest(enr: &Enr) -> Result<ForkDigest, Error> {
+    eth2_field(enr).map(|enr_fork_id| enr_fork_id.fork_digest)
+}
+
+/// Decodes the persistent attestation subnets advertised under the `attnets` key.
+pub fn attestation_subnets(enr: &Enr) -> Result<AttestationSubnets, Error> {
+    decode_value(enr, ATTESTATION_SUBNET_ENR_KEY)
+}
+
+/// Decodes the sync committee subnets advertised under the `syncnets` key.
+///
+/// NOTE: this key is only present from Altair onwards.
+pub fn sync_committee_subnets(enr: &Enr) -> Result<SyncCommitteeSubnets, Error> {
+    decode_value(enr, SYNC_COMMITTEE_SUBNET_ENR_KEY)
+}
+
+/// Returns the subnet-independent `NodeId` of the record.
+pub fn node_id(enr: &Enr) -> NodeId {
+    NodeId::from_be_bytes(enr.node_id().raw())
+}
+
+fn decode_port(enr: &Enr, key: &'static str) -> Result<Option<u16>, Error> {
+    let Some(value) = enr.get(key) else { return Ok(None) };
+    // ports are RLP-encoded as big-endian integers without leading zeros
+    if value.len() > 2 {
+        return Err(Error::InvalidPort(key))
+    }
+    let port = value.iter().fold(0u16, |port, byte| (port << 8) | *byte as u16);
+    Ok(Some(port))
+}
+
+pub fn quic4(enr: &Enr) -> Result<Option<u16>, Error> {
+    decode_port(enr, QUIC_ENR_KEY)
+}
+
+pub fn quic6(enr: &Enr) -> Result<Option<u16>, Error> {
+    decode_port(enr, QUIC6_ENR_KEY)
+}
+
+fn multiaddr_for(ip: IpAddr, transport: Protocol<'_>) -> Multiaddr {
+    let mut multiaddr = Multiaddr::empty();
+    match ip {
+        IpAddr::V4(ip) => multiaddr.push(Protocol::Ip4(ip)),
+        IpAddr::V6(ip) => multiaddr.push(Protocol::Ip6(ip)),
+    }
+    multiaddr.push(transport);
+    multiaddr
+}
+
+/// Returns the multiaddrs for the libp2p TCP transport advertised by the record.
+pub fn tcp_multiaddrs(enr: &Enr) -> Vec<Multiaddr> {
+    let mut multiaddrs = vec![];
+    if let (Some(ip), Some(port)) = (enr.ip4(), enr.tcp4()) {
+        multiaddrs.push(multiaddr_for(ip.into(), Protocol::Tcp(port)));
+    }
+    if let (Some(ip), Some(port)) = (enr.ip6(), enr.tcp6()) {
+        multiaddrs.push(multiaddr_for(ip.into(), Protocol::Tcp(port)));
+    }
+    multiaddrs
+}
+
+/// Returns the multiaddrs for the discovery UDP transport advertised by the record.
+pub fn udp_multiaddrs(enr: &Enr) -> Vec<Multiaddr> {
+    let mut multiaddrs = vec![];
+    if let (Some(ip), Some(port)) = (enr.ip4(), enr.udp4()) {
+        multiaddrs.push(multiaddr_for(ip.into(), Protocol::Udp(port)));
+    }
+    if let (Some(ip), Some(port)) = (enr.ip6(), enr.udp6()) {
+        multiaddrs.push(multiaddr_for(ip.into(), Protocol::Udp(port)));
+    }
+    multiaddrs
+}
+
+/// Returns the multiaddrs for the libp2p QUIC transport advertised by the record.
+pub fn quic_multiaddrs(enr: &Enr) -> Result<Vec<Multiaddr>, Error> {
+    let mut multiaddrs = vec![];
+    if let (Some(ip), Some(port)) = (enr.ip4(), quic4(enr)?) {
+        let mut multiaddr = multiaddr_for(ip.into(), Protocol::Udp(port));
+        multiaddr.push(Protocol::Quic);
+        multiaddrs.push(multiaddr);
+    }
+    if let (Some(ip), Some(port)) = (enr.ip6(), quic6(enr)?) {
+        let mut multiaddr = multiaddr_for(ip.into(), Protocol::Udp(port));
+        multiaddr.push(Protocol::Quic);
+        multiaddrs.push(multiaddr);
+    }
+    Ok(multiaddrs)
+}
+
+/// The network-level content of a local node record.
+#[derive(Debug, Default, Clone)]
+pub struct NodeRecordConfig {
+    pub ip4: Option<Ipv4Addr>,
+    pub tcp4: Option<u16>,
+    pub udp4: Option<u16>,
+    pub quic4: Option<u16>,
+    pub ip6: Option<Ipv6Addr>,
+    pub tcp6: Option<u16>,
+    pub udp6: Option<u16>,
+    pub quic6: Option<u16>,
+}
+
+/// Builds a record for the local node with the consensus-specific keys, signed with `key`.
+///
+/// `sync_committee_subnets` should be provided from Altair onwards.
+pub fn build_enr(
+    key: &SigningKey,
+    config: &NodeRecordConfig,
+    enr_fork_id: &EnrForkId,
+    attestation_subnets: &AttestationSubnets,
+    sync_committee_subnets: Option<&SyncCommitteeSubnets>,
+) -> Result<Enr, Error> {
+    let mut builder = enr::EnrBuilder::new("v4");
+    if let Some(ip) = config.ip4 {
+        builder.ip4(ip);
+    }
+    if let Some(port) = config.tcp4 {
+        builder.tcp4(port);
+    }
+    if let Some(port) = config.udp4 {
+        builder.udp4(port);
+    }
+    if let Some(port) = config.quic4 {
+        builder.add_value(QUIC_ENR_KEY, &port);
+    }
+    if let Some(ip) = config.ip6 {
+        builder.ip6(ip);
+    }
+    if let Some(port) = config.tcp6 {
+        builder.tcp6(port);
+    }
+    if let Some(port) = config.udp6 {
+        builder.udp6(port);
+    }
+    if let Some(port) = config.quic6 {
+        builder.add_value(QUIC6_ENR_KEY, &port);
+    }
+
+    builder.add_value(ETH2_ENR_KEY, &serialize(enr_fork_id)?);
+    builder.add_value(ATTESTATION_SUBNET_ENR_KEY, &serialize(attestation_subnets)?);
+    if let Some(sync_committee_subnets) = sync_committee_subnets {
+        builder.add_value(SYNC_COMMITTEE_SUBNET_ENR_KEY, &serialize(sync_committee_subnets)?);
+    }
+
+    Ok(builder.build(key)?)
+}
+
+/// Parses the contents of a `bootstrap_nodes.txt` file as published for each network.
+///
+/// Each non-empty line holds one ENR, optionally as an item of a YAML list; comments starting
+/// with `#` are ignored.
+pub fn parse_bootstrap_nodes(contents: &str) -> Result<Vec<Enr>, Error> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .map(|line| {
+            line.strip_prefix('-').unwrap_or(line).trim().trim_matches(|c| c == '"' || c == '\'')
+        })
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse::<Enr>().map_err(|reason| Error::Parse { enr: line.to_string(), reason })
+        })
+        .collect()
+}
+
+pub fn load_bootstrap_nodes(path: impl AsRef<Path>) -> Result<Vec<Enr>, Error> {
+    let contents = std::fs::read_to_string(path)?;
+    parse_bootstrap_nodes(&contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enr_roundtrip() {
+        let key = SigningKey::random(&mut rand::thread_rng());
+        let config = NodeRecordConfig {
+            ip4: Some(Ipv4Addr::new(10, 0, 0, 1)),
+            tcp4: Some(9000),
+            udp4: Some(9000),
+            quic4: Some(9001),
+            ..Default::default()
+        };
+        let enr_fork_id = EnrForkId {
+            fork_digest: [0x6a, 0x95, 0xa1, 0xa9],
+            next_fork_version: [4, 0, 0, 0],
+            next_fork_epoch: 269568,
+        };
+        let mut attnets = AttestationSubnets::default();
+        attnets.set(3, true);
+        attnets.set(41, true);
+        let mut syncnets = SyncCommitteeSubnets::default();
+        syncnets.set(2, true);
+
+        let enr = build_enr(&key, &config, &enr_fork_id, &attnets, Some(&syncnets)).unwrap();
+        let enr = parse_bootstrap_nodes(&format!("# comment\n- \"{}\"\n", enr.to_base64()))
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        assert_eq!(eth2_field(&enr).unwrap(), enr_fork_id);
+        assert_eq!(attestation_subnets(&enr).unwrap(), attnets);
+        assert_eq!(sync_committee_subnets(&enr).unwrap(), syncnets);
+        assert_eq!(quic4(&enr).unwrap(), Some(9001));
+        assert_eq!(
+            tcp_multiaddrs(&enr),
+            vec!["/ip4/10.0.0.1/tcp/9000".parse::<Multiaddr>().unwrap()]
+        );
+        assert_eq!(
+            quic_multiaddrs(&enr).unwrap(),
+            vec!["/ip4/10.0.0.1/udp/9001/quic".parse::<Multiaddr>().unwrap()]
+        );
+    }
+}
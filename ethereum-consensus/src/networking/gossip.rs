@@ -0,0 +1,253 @@
+//! Helpers for the gossipsub domain of the consensus p2p interface.
+use crate::{
+    crypto::hash,
+    networking::MessageDomain,
+    primitives::{ForkDigest, SubnetId},
+};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+pub const TOPIC_PREFIX: &str = "eth2";
+pub const ENCODING_POSTFIX: &str = "ssz_snappy";
+pub const MESSAGE_ID_LENGTH: usize = 20;
+
+pub type MessageId = [u8; MESSAGE_ID_LENGTH];
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("topic `{0}` is not of the form `/{TOPIC_PREFIX}/{{fork_digest}}/{{name}}/{ENCODING_POSTFIX}`")]
+    InvalidTopic(String),
+    #[error("invalid fork digest `{0}`")]
+    InvalidForkDigest(String),
+    #[error("unknown topic name `{0}`")]
+    UnknownTopicName(String),
+}
+
+/// The names of the gossip topics, across all forks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopicName {
+    BeaconBlock,
+    BeaconAggregateAndProof,
+    BeaconAttestation(SubnetId),
+    VoluntaryExit,
+    ProposerSlashing,
+    AttesterSlashing,
+    SyncCommitteeContributionAndProof,
+    SyncCommittee(SubnetId),
+    LightClientFinalityUpdate,
+    LightClientOptimisticUpdate,
+    BlsToExecutionChange,
+    BlobSidecar(SubnetId),
+}
+
+impl fmt::Display for TopicName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BeaconBlock => write!(f, "beacon_block"),
+            Self::BeaconAggregateAndProof => write!(f, "beacon_aggregate_and_proof"),
+            Self::BeaconAttestation(subnet_id) => write!(f, "beacon_attestation_{subnet_id}"),
+            Self::VoluntaryExit => write!(f, "voluntary_exit"),
+            Self::ProposerSlashing => write!(f, "proposer_slashing"),
+            Self::AttesterSlashing => write!(f, "attester_slashing"),
+            Self::SyncCommitteeContributionAndProof => {
+                write!(f, "sync_committee_contribution_and_proof")
+            }
+            Self::SyncCommittee(subnet_id) => write!(f, "sync_committee_{subnet_id}"),
+            Self::LightClientFinalityUpdate => write!(f, "light_client_finality_update"),
+            Self::LightClientOptimisticUpdate => write!(f, "light_client_optimistic_update"),
+            Self::BlsToExecutionChange => write!(f, "bls_to_execution_change"),
+            Self::BlobSidecar(subnet_id) => write!(f, "blob_sidecar_{subnet_id}"),
+        }
+    }
+}
+
+impl FromStr for TopicName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = match s {
+            "beacon_block" => Self::BeaconBlock,
+            "beacon_aggregate_and_proof" => Self::BeaconAggregateAndProof,
+            "voluntary_exit" => Self::VoluntaryExit,
+            "proposer_slashing" => Self::ProposerSlashing,
+            "attester_slashing" => Self::AttesterSlashing,
+            "sync_committee_contribution_and_proof" => Self::SyncCommitteeContributionAndProof,
+            "light_client_finality_update" => Self::LightClientFinalityUpdate,
+            "light_client_optimistic_update" => Self::LightClientOptimisticUpdate,
+            "bls_to_execution_change" => Self::BlsToExecutionChange,
+            s => {
+                let parse_subnet = |prefix: &str| {
+                    s.strip_prefix(prefix).and_then(|subnet_id| subnet_id.parse::<SubnetId>().ok())
+                };
+                if let Some(subnet_id) = parse_subnet("beacon_attestation_") {
+                    Self::BeaconAttestation(subnet_id)
+                } else if let Some(subnet_id) = parse_subnet("sync_committee_") {
+                    Self::SyncCommittee(subnet_id)
+                } else if let Some(subnet_id) = parse_subnet("blob_sidecar_") {
+                    Self::BlobSidecar(subnet_id)
+                } else {
+                    return Err(Error::UnknownTopicName(s.to_string()))
+                }
+            }
+        };
+        Ok(name)
+    }
+}
+
+/// A fully-qualified gossip topic of the form `/eth2/{fork_digest}/{name}/ssz_snappy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Topic {
+    pub fork_digest: ForkDigest,
+    pub name: TopicName,
+}
+
+impl Topic {
+    pub fn new(fork_digest: ForkDigest, name: TopicName) -> Self {
+        Self { fork_digest, name }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{TOPIC_PREFIX}/")?;
+        for byte in self.fork_digest {
+            write!(f, "{byte:02x}")?;
+        }
+        write!(f, "/{}/{ENCODING_POSTFIX}", self.name)
+    }
+}
+
+impl FromStr for Topic {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_topic = || Error::InvalidTopic(s.to_string());
+        let mut parts = s.split('/');
+        if parts.next() != Some("") || parts.next() != Some(TOPIC_PREFIX) {
+            return Err(invalid_topic())
+        }
+        let (fork_digest, name) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(fork_digest), Some(name), Some(ENCODING_POSTFIX), None) => (fork_digest, name),
+            _ => return Err(invalid_topic()),
+        };
+        let fork_digest = parse_fork_digest(fork_digest)
+            .ok_or_else(|| Error::InvalidForkDigest(fork_digest.to_string()))?;
+        Ok(Self { fork_digest, name: name.parse()? })
+    }
+}
+
+fn parse_fork_digest(s: &str) -> Option<ForkDigest> {
+    if s.len() != 2 * ForkDigest::default().len() || !s.is_ascii() {
+        return None
+    }
+    let mut fork_digest = ForkDigest::default();
+    for (i, byte) in fork_digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(fork_digest)
+}
+
+/// Returns the gossip topic string for `name` under `fork_digest`.
+pub fn gossip_topic(fork_digest: ForkDigest, name: TopicName) -> String {
+    Topic::new(fork_digest, name).to_string()
+}
+
+// Attempt to decompress the raw snappy `data` of a gossip message, refusing to allocate for
+// payloads that claim to be larger than `gossip_max_size`.
+fn decompress_message(data: &[u8], gossip_max_size: usize) -> Option<Vec<u8>> {
+    let length = snap::raw::decompress_len(data).ok()?;
+    if length > gossip_max_size {
+        return None
+    }
+    snap::raw::Decoder::new().decompress_vec(data).ok()
+}
+
+fn compute_message_id_with_prefix(
+    topic: Option<&str>,
+    data: &[u8],
+    gossip_max_size: usize,
+) -> MessageId {
+    let mut preimage = vec![];
+    let payload = match decompress_message(data, gossip_max_size) {
+        Some(payload) => {
+            preimage.extend_from_slice(&MessageDomain::ValidSnappy.as_bytes());
+            payload
+        }
+        None => {
+            preimage.extend_from_slice(&MessageDomain::InvalidSnappy.as_bytes());
+            data.to_vec()
+        }
+    };
+    if let Some(topic) = topic {
+        preimage.extend_from_slice(&(topic.len() as u64).to_le_bytes());
+        preimage.extend_from_slice(topic.as_bytes());
+    }
+    preimage.extend_from_slice(&payload);
+    let digest = hash(preimage);
+    digest[..MESSAGE_ID_LENGTH].try_into().expect("correct size")
+}
+
+/// Computes the `message-id` of a gossip message as defined for Phase 0.
+///
+/// `gossip_max_size` should be the `GOSSIP_MAX_SIZE` in effect for the message's fork.
+pub fn compute_message_id_phase0(data: &[u8], gossip_max_size: usize) -> MessageId {
+    compute_message_id_with_prefix(None, data, gossip_max_size)
+}
+
+/// Computes the `message-id` of a gossip message as defined from Altair onwards, which
+/// additionally commits to the message's `topic`.
+///
+/// `gossip_max_size` should be the `GOSSIP_MAX_SIZE` in effect for the message's fork.
+pub fn compute_message_id_altair(topic: &str, data: &[u8], gossip_max_size: usize) -> MessageId {
+    compute_message_id_with_prefix(Some(topic), data, gossip_max_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phase0::networking::GOSSIP_MAX_SIZE;
+
+    #[test]
+    fn test_topic_roundtrip() {
+        let fork_digest = [0xb5, 0x30, 0x3f, 0x2a];
+        let topic = gossip_topic(fork_digest, TopicName::BeaconAttestation(17));
+        assert_eq!(topic, "/eth2/b5303f2a/beacon_attestation_17/ssz_snappy");
+        let recovered: Topic = topic.parse().unwrap();
+        assert_eq!(recovered, Topic::new(fork_digest, TopicName::BeaconAttestation(17)));
+
+        let topic = gossip_topic(fork_digest, TopicName::BeaconBlock);
+        assert_eq!(topic, "/eth2/b5303f2a/beacon_block/ssz_snappy");
+        assert_eq!(topic.parse::<Topic>().unwrap().name, TopicName::BeaconBlock);
+
+        assert!("/eth2/b5303f2a/beacon_block/ssz".parse::<Topic>().is_err());
+        assert!("/eth2/b5303f/beacon_block/ssz_snappy".parse::<Topic>().is_err());
+        assert!("/eth2/b5303f2a/unknown/ssz_snappy".parse::<Topic>().is_err());
+    }
+
+    #[test]
+    fn test_message_id_domains() {
+        // raw snappy encoding of `some gossip payload` as a single literal
+        let mut data = vec![0x13, 0x48];
+        data.extend_from_slice(b"some gossip payload");
+        let expected = [
+            0x84, 0x87, 0x74, 0x56, 0xc0, 0x39, 0x15, 0x1f, 0x2a, 0xdb, 0xd3, 0x8f, 0xc8, 0xef,
+            0xd2, 0x0a, 0xb4, 0xb9, 0x97, 0x15,
+        ];
+        assert_eq!(compute_message_id_phase0(&data, GOSSIP_MAX_SIZE), expected);
+
+        let topic = "/eth2/b5303f2a/beacon_block/ssz_snappy";
+        let expected = [
+            0xae, 0x8a, 0xa6, 0x0d, 0xba, 0x69, 0x37, 0x88, 0x35, 0xc4, 0x99, 0x7a, 0x9a, 0xa1,
+            0x6b, 0xa9, 0xfe, 0xf3, 0xb1, 0x2c,
+        ];
+        assert_eq!(compute_message_id_altair(topic, &data, GOSSIP_MAX_SIZE), expected);
+
+        // not a valid snappy encoding so the raw data is hashed
+        let invalid = [0xff; 8];
+        let expected = [
+            0xa7, 0x26, 0x94, 0x5a, 0xef, 0xa9, 0x7e, 0xb2, 0x20, 0xa4, 0xcb, 0x0e, 0xa8, 0xdc,
+            0xf9, 0xd5, 0xbb, 0xe1, 0x81, 0xb5,
+        ];
+        assert_eq!(compute_message_id_phase0(&invalid, GOSSIP_MAX_SIZE), expected);
+    }
+}
@@ -0,0 +1,322 @@
+pub mod discovery;
+pub mod gossip;
+pub mod ssz_snappy;
+
+use enr::{
+    self,
+    k256::{ecdsa::signature::Verifier, elliptic_curve::sec1::ToEncodedPoint},
+};
+pub use multiaddr::Multiaddr;
+use multihash::{Code, Error, Multihash, MultihashDigest};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt, str::FromStr};
+use thiserror::Error;
+
+pub const MAX_INLINE_KEY_LENGTH: usize = 42;
+
+// protobuf field tags and `KeyType` values of the libp2p `PublicKey` message
+const PROTOBUF_KEY_TYPE_TAG: u8 = 0x08;
+const PROTOBUF_DATA_TAG: u8 = 0x12;
+const KEY_TYPE_ED25519: u8 = 1;
+const KEY_TYPE_SECP256K1: u8 = 2;
+
+pub type Secp256k1PublicKey = enr::k256::ecdsa::VerifyingKey;
+pub type Ed25519PublicKey = ed25519_dalek::VerifyingKey;
+
+#[derive(Debug, Error)]
+pub enum PublicKeyError {
+    #[error("invalid protobuf encoding of public key")]
+    InvalidEncoding,
+    #[error("unsupported key type {0}")]
+    UnsupportedKeyType(u8),
+    #[error("invalid secp256k1 public key")]
+    InvalidSecp256k1Key,
+    #[error("invalid ed25519 public key")]
+    InvalidEd25519Key,
+}
+
+/// A libp2p public key of one of the types used on the consensus p2p network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PublicKey {
+    Secp256k1(Secp256k1PublicKey),
+    Ed25519(Ed25519PublicKey),
+}
+
+impl PublicKey {
+    /// Encodes the key as the libp2p `PublicKey` protobuf message.
+    pub fn to_protobuf_encoding(&self) -> Vec<u8> {
+        let (key_type, data) = match self {
+            Self::Secp256k1(key) => {
+                (KEY_TYPE_SECP256K1, key.to_encoded_point(true).as_bytes().to_vec())
+            }
+            Self::Ed25519(key) => (KEY_TYPE_ED25519, key.as_bytes().to_vec()),
+        };
+        // NOTE: keys of the supported types are short enough for their length to fit in a single
+        // varint byte
+        let mut encoding =
+            vec![PROTOBUF_KEY_TYPE_TAG, key_type, PROTOBUF_DATA_TAG, data.len() as u8];
+        encoding.extend_from_slice(&data);
+        encoding
+    }
+
+    /// Decodes a key from the libp2p `PublicKey` protobuf message.
+    pub fn try_from_protobuf_encoding(encoding: &[u8]) -> Result<Self, PublicKeyError> {
+        let (key_type, data) = match encoding {
+            [PROTOBUF_KEY_TYPE_TAG, key_type, PROTOBUF_DATA_TAG, length, data @ ..]
+                if *length as usize == data.len() && *length < 0x80 =>
+            {
+                (*key_type, data)
+            }
+            _ => return Err(PublicKeyError::InvalidEncoding),
+        };
+        match key_type {
+            KEY_TYPE_SECP256K1 => Secp256k1PublicKey::from_sec1_bytes(data)
+                .map(Self::Secp256k1)
+                .map_err(|_| PublicKeyError::InvalidSecp256k1Key),
+            KEY_TYPE_ED25519 => {
+                let data = data.try_into().map_err(|_| PublicKeyError::InvalidEd25519Key)?;
+                Ed25519PublicKey::from_bytes(data)
+                    .map(Self::Ed25519)
+                    .map_err(|_| PublicKeyError::InvalidEd25519Key)
+            }
+            other => Err(PublicKeyError::UnsupportedKeyType(other)),
+        }
+    }
+
+    /// Verifies `signature` over `message` following the libp2p conventions for each key type:
+    /// DER-encoded ECDSA over the SHA-256 digest for secp256k1 and plain ed25519 otherwise.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Secp256k1(key) => enr::k256::ecdsa::Signature::from_der(signature)
+                .map(|signature| key.verify(message, &signature).is_ok())
+                .unwrap_or(false),
+            Self::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+                .map(|signature| key.verify_strict(message, &signature).is_ok())
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn to_peer_id(&self) -> PeerId {
+        PeerId::from_public_key(self)
+    }
+}
+
+// PeerId reimplemented from rust-libp2p
+// revisit this implementation later
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PeerId {
+    multihash: Multihash,
+}
+
+impl fmt::Display for PeerId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_base58())
+    }
+}
+
+impl fmt::Debug for PeerId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PeerId").field(&self.to_base58()).finish()
+    }
+}
+
+impl PeerId {
+    pub fn to_base58(&self) -> String {
+        bs58::encode(self.multihash.to_bytes()).into_string()
+    }
+    /// Returns a raw bytes representation of this `PeerId`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.multihash.to_bytes()
+    }
+    /// Parses a `PeerId` from bytes.
+    pub fn from_bytes(data: &[u8]) -> Result<PeerId, Error> {
+        PeerId::from_multihash(Multihash::from_bytes(data)?)
+            .map_err(|mh| Error::UnsupportedCode(mh.code()))
+    }
+    /// Derives the `PeerId` of `key`, inlining the encoded key when it is short enough.
+    pub fn from_public_key(key: &PublicKey) -> PeerId {
+        let encoding = key.to_protobuf_encoding();
+        let code =
+            if encoding.len() <= MAX_INLINE_KEY_LENGTH { Code::Identity } else { Code::Sha2_256 };
+        PeerId { multihash: code.digest(&encoding) }
+    }
+    /// Derives the `PeerId` of the node identified by the secp256k1 key of `enr`.
+    pub fn from_enr(enr: &Enr) -> PeerId {
+        PeerId::from_public_key(&PublicKey::Secp256k1(enr.public_key()))
+    }
+    /// Recovers the public key of this `PeerId` if it was inlined.
+    pub fn public_key(&self) -> Option<PublicKey> {
+        if self.multihash.code() != u64::from(Code::Identity) {
+            return None
+        }
+        PublicKey::try_from_protobuf_encoding(self.multihash.digest()).ok()
+    }
+    /// Returns `true` if this `PeerId` was derived from `key`.
+    pub fn is_public_key(&self, key: &PublicKey) -> bool {
+        PeerId::from_public_key(key) == *self
+    }
+    /// Verifies `signature` over `message` by `key`, ensuring `key` belongs to this `PeerId`.
+    /// If `key` is not provided, it is recovered from the `PeerId` itself when inlined.
+    pub fn verify(&self, message: &[u8], signature: &[u8], key: Option<&PublicKey>) -> bool {
+        let key = match key {
+            Some(key) if self.is_public_key(key) => key.clone(),
+            Some(_) => return false,
+            None => match self.public_key() {
+                Some(key) => key,
+                None => return false,
+            },
+        };
+        key.verify(message, signature)
+    }
+    /// Tries to turn a `Multihash` into a `PeerId`.
+    pub fn from_multihash(multihash: Multihash) -> Result<PeerId, Multihash> {
+        match Code::try_from(multihash.code()) {
+            Ok(Code::Sha2_256) => Ok(PeerId { multihash }),
+            Ok(Code::Identity) if multihash.digest().len() <= MAX_INLINE_KEY_LENGTH => {
+                Ok(PeerId { multihash })
+            }
+            _ => Err(multihash),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PeerId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_base58())
+        } else {
+            serializer.serialize_bytes(&self.to_bytes()[..])
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PeerId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::*;
+
+        struct PeerIdVisitor;
+
+        impl<'de> Visitor<'de> for PeerIdVisitor {
+            type Value = PeerId;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "valid peer id")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                PeerId::from_bytes(v).map_err(|_| Error::invalid_value(Unexpected::Bytes(v), &self))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                PeerId::from_str(v).map_err(|_| Error::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(PeerIdVisitor)
+        } else {
+            deserializer.deserialize_bytes(PeerIdVisitor)
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("base-58 decode error: {0}")]
+    B58(#[from] bs58::decode::Error),
+    #[error("decoding multihash failed")]
+    MultiHash,
+}
+
+impl FromStr for PeerId {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = bs58::decode(s).into_vec()?;
+        PeerId::from_bytes(&bytes).map_err(|_| ParseError::MultiHash)
+    }
+}
+
+pub type Enr = enr::Enr<enr::k256::ecdsa::SigningKey>;
+
+#[derive(Clone, Copy)]
+pub enum MessageDomain {
+    InvalidSnappy, // 0
+    ValidSnappy,   // 1
+}
+
+impl MessageDomain {
+    pub fn as_bytes(&self) -> [u8; 4] {
+        let data = *self as u32;
+        data.to_le_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_id_serde() {
+        let id_repr = "\"16Uiu2HAmVDji3ShrqL9DLnQo3teJcEWiKqy9qKefFFFxrz2EYwde\"";
+        let id: PeerId = serde_json::from_str(id_repr).unwrap();
+        let roundtrip_id_repr = serde_json::to_string(&id).unwrap();
+        assert_eq!(id_repr, roundtrip_id_repr);
+    }
+
+    #[test]
+    fn test_peer_id_from_public_key() {
+        // secp256k1 keys are inlined
+        let id: PeerId = "16Uiu2HAmVDji3ShrqL9DLnQo3teJcEWiKqy9qKefFFFxrz2EYwde".parse().unwrap();
+        let key = id.public_key().unwrap();
+        assert!(matches!(key, PublicKey::Secp256k1(..)));
+        assert_eq!(PeerId::from_public_key(&key), id);
+
+        // as are ed25519 keys
+        let id: PeerId = "12D3KooWD3eckifWpRn9wQpMG9R9hX3sD158z7EqHWmweQAJU5SA".parse().unwrap();
+        let key = id.public_key().unwrap();
+        assert!(matches!(key, PublicKey::Ed25519(..)));
+        assert_eq!(key.to_peer_id(), id);
+
+        let id: PeerId = "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N".parse().unwrap();
+        assert!(id.public_key().is_none());
+    }
+
+    #[test]
+    fn test_peer_id_verify() {
+        use enr::k256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let key = PublicKey::Secp256k1((&signing_key).into());
+        let id = key.to_peer_id();
+        let message = b"some message";
+        let signature: Signature = signing_key.sign(message);
+        let signature = signature.to_der();
+        assert!(id.verify(message, signature.as_bytes(), None));
+        assert!(id.verify(message, signature.as_bytes(), Some(&key)));
+        assert!(!id.verify(b"another message", signature.as_bytes(), None));
+    }
+
+    #[test]
+    fn test_id_str_format() {
+        let id_repr = "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N";
+        let id: PeerId = PeerId::from_str(id_repr).unwrap();
+        assert_eq!(format!("{id}"), "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N")
+    }
+}
@@ -0,0 +1,672 @@
+//! An implementation of the `ssz_snappy` encoding used by the Req/Resp domain of the
+//! consensus p2p interface.
+//!
+//! A request is a single `<encoding-dependent-header> | <encoded-payload>` while a response is
+//! a sequence of chunks of the form `<result> | <context-bytes> | <encoding-dependent-header> |
+//! <encoded-payload>`. The header is the unsigned varint length of the SSZ payload and the
+//! payload itself is compressed with the snappy framing format.
+//!
+//! Everything here operates on byte buffers and is independent of any transport.
+use crate::{
+    bellatrix::networking::MAX_CHUNK_SIZE_BELLATRIX,
+    deneb::blob_sidecar::BlobSidecar,
+    phase0::{helpers::compute_fork_digest, networking::MAX_CHUNK_SIZE},
+    primitives::{ForkDigest, Root},
+    ssz::prelude::*,
+    state_transition::Context,
+    types::SignedBeaconBlock,
+    Fork,
+};
+use thiserror::Error;
+
+// NOTE: protobuf varints for 64-bit values never need more than 10 bytes
+const MAX_VARINT_LENGTH: usize = 10;
+
+pub const MAX_ERROR_MESSAGE_LENGTH: usize = 256;
+
+pub type ErrorMessage = ByteList<MAX_ERROR_MESSAGE_LENGTH>;
+
+const STREAM_IDENTIFIER: &[u8] = b"sNaPpY";
+const CHUNK_TYPE_COMPRESSED: u8 = 0x00;
+const CHUNK_TYPE_UNCOMPRESSED: u8 = 0x01;
+const CHUNK_TYPE_PADDING: u8 = 0xfe;
+const CHUNK_TYPE_STREAM_IDENTIFIER: u8 = 0xff;
+const CHUNK_HEADER_LENGTH: usize = 4;
+const CHECKSUM_LENGTH: usize = 4;
+const MAX_UNCOMPRESSED_CHUNK_LENGTH: usize = 65536;
+const CHECKSUM_MASK_DELTA: u32 = 0xa282ead8;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("unexpected end of input")]
+    UnexpectedEndOfInput,
+    #[error("varint is longer than the maximum of {MAX_VARINT_LENGTH} bytes")]
+    VarintTooLong,
+    #[error("varint does not fit in the target type")]
+    VarintOverflow,
+    #[error("payload of length {length} exceeds the maximum chunk size of {max_chunk_size}")]
+    PayloadTooLarge { length: usize, max_chunk_size: usize },
+    #[error("compressed payload consumed {consumed} bytes but at most {bound} are allowed for a payload of length {length}")]
+    CompressedPayloadTooLarge { consumed: usize, bound: usize, length: usize },
+    #[error("decompressed payload has length {provided} but the header announced {expected}")]
+    PayloadLengthMismatch { expected: usize, provided: usize },
+    #[error("trailing data of {0} bytes after the request payload")]
+    TrailingData(usize),
+    #[error("missing snappy stream identifier")]
+    MissingStreamIdentifier,
+    #[error("invalid snappy chunk of type {0:#04x}")]
+    InvalidChunk(u8),
+    #[error("invalid checksum for snappy chunk")]
+    InvalidChecksum,
+    #[error("{0}")]
+    Snappy(#[from] snap::Error),
+    #[error("{0}")]
+    Serialize(#[from] SerializeError),
+    #[error("{0}")]
+    Deserialize(#[from] DeserializeError),
+    #[error("context bytes {0:?} do not correspond to any known fork")]
+    UnknownContextBytes(ForkDigest),
+    #[error("response chunk is missing context bytes")]
+    MissingContextBytes,
+    #[error("response chunk has an unsuccessful result `{0:?}`")]
+    UnsuccessfulResponse(ResponseCode),
+    #[error("object is not supported in fork {0}")]
+    UnsupportedFork(Fork),
+    #[error("{0}")]
+    Consensus(#[from] crate::Error),
+}
+
+/// The `<result>` byte leading each response chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseCode {
+    Success,
+    InvalidRequest,
+    ServerError,
+    ResourceUnavailable,
+    // Any other code is reserved and should be treated as a generic error by readers.
+    Reserved(ReservedCode),
+}
+
+/// A `<result>` byte outside of the codes defined by the spec.
+///
+/// Only obtainable by converting a `u8` into a `ResponseCode` so that it never aliases one of
+/// the defined codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReservedCode(u8);
+
+impl ReservedCode {
+    pub fn code(&self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for ResponseCode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Success,
+            1 => Self::InvalidRequest,
+            2 => Self::ServerError,
+            3 => Self::ResourceUnavailable,
+            other => Self::Reserved(ReservedCode(other)),
+        }
+    }
+}
+
+impl From<ResponseCode> for u8 {
+    fn from(value: ResponseCode) -> Self {
+        match value {
+            ResponseCode::Success => 0,
+            ResponseCode::InvalidRequest => 1,
+            ResponseCode::ServerError => 2,
+            ResponseCode::ResourceUnavailable => 3,
+            ResponseCode::Reserved(other) => other.code(),
+        }
+    }
+}
+
+/// A single decoded response chunk.
+///
+/// For a `Success` chunk `payload` is the SSZ encoding of the response object, otherwise it is
+/// the SSZ encoding of an `ErrorMessage`. Context bytes are only ever present on successful
+/// chunks of protocols that define them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseChunk {
+    pub code: ResponseCode,
+    pub context_bytes: Option<ForkDigest>,
+    pub payload: Vec<u8>,
+}
+
+impl ResponseChunk {
+    pub fn is_success(&self) -> bool {
+        self.code == ResponseCode::Success
+    }
+
+    /// Returns the (lossily decoded) error message for an unsuccessful chunk.
+    pub fn error_message(&self) -> Option<String> {
+        if self.is_success() {
+            None
+        } else {
+            Some(String::from_utf8_lossy(&self.payload).into_owned())
+        }
+    }
+
+    pub fn decode<T: Deserialize>(&self) -> Result<T, Error> {
+        if !self.is_success() {
+            return Err(Error::UnsuccessfulResponse(self.code))
+        }
+        Ok(T::deserialize(&self.payload)?)
+    }
+}
+
+/// Returns the maximum allowed size of an uncompressed payload for the given `fork`.
+pub fn max_chunk_size(fork: Fork) -> usize {
+    match fork {
+        Fork::Phase0 | Fork::Altair => MAX_CHUNK_SIZE,
+        _ => MAX_CHUNK_SIZE_BELLATRIX,
+    }
+}
+
+/// The worst-case size of the snappy framed encoding of `length` bytes.
+pub fn max_compressed_length(length: usize) -> usize {
+    32 + length + length / 6
+}
+
+pub fn encode_varint(mut value: u64, buffer: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Decodes an unsigned protobuf varint from the front of `input`, returning the value and the
+/// number of bytes consumed.
+pub fn decode_varint(input: &[u8]) -> Result<(u64, usize), Error> {
+    let mut value = 0u64;
+    for (i, byte) in input.iter().take(MAX_VARINT_LENGTH).enumerate() {
+        let bits = (byte & 0x7f) as u64;
+        let shift = 7 * i as u32;
+        if shift == 63 && bits > 1 {
+            return Err(Error::VarintOverflow)
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1))
+        }
+    }
+    if input.len() >= MAX_VARINT_LENGTH {
+        Err(Error::VarintTooLong)
+    } else {
+        Err(Error::UnexpectedEndOfInput)
+    }
+}
+
+// CRC-32C (Castagnoli), as required by the snappy framing format.
+fn crc32c(data: &[u8]) -> u32 {
+    const POLYNOMIAL: u32 = 0x82f63b78;
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLYNOMIAL & mask);
+        }
+    }
+    !crc
+}
+
+fn masked_checksum(data: &[u8]) -> u32 {
+    let checksum = crc32c(data);
+    checksum.rotate_right(15).wrapping_add(CHECKSUM_MASK_DELTA)
+}
+
+fn write_chunk_header(chunk_type: u8, length: usize, buffer: &mut Vec<u8>) {
+    buffer.push(chunk_type);
+    buffer.extend_from_slice(&(length as u32).to_le_bytes()[..3]);
+}
+
+/// Compresses `data` with the snappy framing format, appending the result to `buffer`.
+pub fn compress(data: &[u8], buffer: &mut Vec<u8>) -> Result<(), Error> {
+    let mut encoder = snap::raw::Encoder::new();
+    write_chunk_header(CHUNK_TYPE_STREAM_IDENTIFIER, STREAM_IDENTIFIER.len(), buffer);
+    buffer.extend_from_slice(STREAM_IDENTIFIER);
+    for block in data.chunks(MAX_UNCOMPRESSED_CHUNK_LENGTH) {
+        let checksum = masked_checksum(block).to_le_bytes();
+        let compressed = encoder.compress_vec(block)?;
+        // NOTE: the format allows for storing data uncompressed when it does not compress well
+        let (chunk_type, body) = if compressed.len() < block.len() {
+            (CHUNK_TYPE_COMPRESSED, compressed.as_slice())
+        } else {
+            (CHUNK_TYPE_UNCOMPRESSED, block)
+        };
+        write_chunk_header(chunk_type, CHECKSUM_LENGTH + body.len(), buffer);
+        buffer.extend_from_slice(&checksum);
+        buffer.extend_from_slice(body);
+    }
+    Ok(())
+}
+
+/// Decompresses snappy frames from the front of `input` until exactly `length` bytes have been
+/// recovered, returning the data and the number of bytes consumed from `input`.
+///
+/// Reading stops at the frame boundary where `length` is reached so that any following response
+/// chunks in `input` are left intact.
+pub fn decompress(input: &[u8], length: usize) -> Result<(Vec<u8>, usize), Error> {
+    let mut decoder = snap::raw::Decoder::new();
+    let mut output = Vec::with_capacity(length);
+    let mut cursor = 0;
+    let mut seen_stream_identifier = false;
+    let bound = max_compressed_length(length);
+    while output.len() < length || !seen_stream_identifier {
+        let header =
+            input.get(cursor..cursor + CHUNK_HEADER_LENGTH).ok_or(Error::UnexpectedEndOfInput)?;
+        let chunk_type = header[0];
+        let chunk_length = u32::from_le_bytes([header[1], header[2], header[3], 0]) as usize;
+        cursor += CHUNK_HEADER_LENGTH;
+        let body = input.get(cursor..cursor + chunk_length).ok_or(Error::UnexpectedEndOfInput)?;
+        cursor += chunk_length;
+        if cursor > bound {
+            return Err(Error::CompressedPayloadTooLarge { consumed: cursor, bound, length })
+        }
+
+        if chunk_type == CHUNK_TYPE_STREAM_IDENTIFIER {
+            if body != STREAM_IDENTIFIER {
+                return Err(Error::MissingStreamIdentifier)
+            }
+            seen_stream_identifier = true;
+            continue
+        }
+        if !seen_stream_identifier {
+            return Err(Error::MissingStreamIdentifier)
+        }
+        match chunk_type {
+            CHUNK_TYPE_COMPRESSED | CHUNK_TYPE_UNCOMPRESSED => {
+                if body.len() < CHECKSUM_LENGTH {
+                    return Err(Error::InvalidChunk(chunk_type))
+                }
+                let (checksum, data) = body.split_at(CHECKSUM_LENGTH);
+                // NOTE: check the announced length before decompressing to bound the allocation
+                let data_length = if chunk_type == CHUNK_TYPE_COMPRESSED {
+                    snap::raw::decompress_len(data)?
+                } else {
+                    data.len()
+                };
+                if data_length > MAX_UNCOMPRESSED_CHUNK_LENGTH {
+                    return Err(Error::InvalidChunk(chunk_type))
+                }
+                if output.len() + data_length > length {
+                    return Err(Error::PayloadLengthMismatch {
+                        expected: length,
+                        provided: output.len() + data_length,
+                    })
+                }
+                let data = if chunk_type == CHUNK_TYPE_COMPRESSED {
+                    decoder.decompress_vec(data)?
+                } else {
+                    data.to_vec()
+                };
+                if masked_checksum(&data).to_le_bytes() != checksum {
+                    return Err(Error::InvalidChecksum)
+                }
+                output.extend_from_slice(&data);
+            }
+            // reserved skippable chunks and padding
+            0x80..=CHUNK_TYPE_PADDING => {}
+            // reserved unskippable chunks
+            other => return Err(Error::InvalidChunk(other)),
+        }
+    }
+    Ok((output, cursor))
+}
+
+/// Encodes an SSZ payload as `<encoding-dependent-header> | <encoded-payload>`, appending the
+/// result to `buffer`.
+pub fn encode_payload(
+    ssz_bytes: &[u8],
+    max_chunk_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let length = ssz_bytes.len();
+    if length > max_chunk_size {
+        return Err(Error::PayloadTooLarge { length, max_chunk_size })
+    }
+    encode_varint(length as u64, buffer);
+    compress(ssz_bytes, buffer)
+}
+
+/// Decodes `<encoding-dependent-header> | <encoded-payload>` from the front of `input`, returning
+/// the SSZ payload and the number of bytes consumed.
+pub fn decode_payload(input: &[u8], max_chunk_size: usize) -> Result<(Vec<u8>, usize), Error> {
+    let (length, header_length) = decode_varint(input)?;
+    let length = usize::try_from(length).map_err(|_| Error::VarintOverflow)?;
+    if length > max_chunk_size {
+        return Err(Error::PayloadTooLarge { length, max_chunk_size })
+    }
+    let (payload, payload_length) = decompress(&input[header_length..], length)?;
+    Ok((payload, header_length + payload_length))
+}
+
+pub fn encode_request<T: Serialize>(request: &T, max_chunk_size: usize) -> Result<Vec<u8>, Error> {
+    let ssz_bytes = serialize(request)?;
+    let mut buffer = vec![];
+    encode_payload(&ssz_bytes, max_chunk_size, &mut buffer)?;
+    Ok(buffer)
+}
+
+pub fn decode_request<T: Deserialize>(input: &[u8], max_chunk_size: usize) -> Result<T, Error> {
+    let (payload, consumed) = decode_payload(input, max_chunk_size)?;
+    if consumed != input.len() {
+        return Err(Error::TrailingData(input.len() - consumed))
+    }
+    Ok(T::deserialize(&payload)?)
+}
+
+/// Encodes a single response chunk, appending the result to `buffer`.
+pub fn encode_response_chunk(
+    chunk: &ResponseChunk,
+    max_chunk_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), Error> {
+    buffer.push(chunk.code.into());
+    if chunk.is_success() {
+        if let Some(context_bytes) = chunk.context_bytes {
+            buffer.extend_from_slice(&context_bytes);
+        }
+    }
+    encode_payload(&chunk.payload, max_chunk_size, buffer)
+}
+
+/// Encodes a successful response chunk carrying `response`.
+pub fn encode_response<T: Serialize>(
+    response: &T,
+    context_bytes: Option<ForkDigest>,
+    max_chunk_size: usize,
+) -> Result<Vec<u8>, Error> {
+    let chunk =
+        ResponseChunk { code: ResponseCode::Success, context_bytes, payload: serialize(response)? };
+    let mut buffer = vec![];
+    encode_response_chunk(&chunk, max_chunk_size, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Encodes an error response chunk, truncating `message` to `MAX_ERROR_MESSAGE_LENGTH` bytes.
+pub fn encode_error_response(code: ResponseCode, message: &str) -> Result<Vec<u8>, Error> {
+    let message = &message.as_bytes()[..message.len().min(MAX_ERROR_MESSAGE_LENGTH)];
+    let chunk = ResponseChunk { code, context_bytes: None, payload: message.to_vec() };
+    let mut buffer = vec![];
+    encode_response_chunk(&chunk, MAX_CHUNK_SIZE, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Decodes a single response chunk from the front of `input`, returning the chunk and the number
+/// of bytes consumed. `has_context_bytes` should be set according to the protocol being read.
+pub fn decode_response_chunk(
+    input: &[u8],
+    has_context_bytes: bool,
+    max_chunk_size: usize,
+) -> Result<(ResponseChunk, usize), Error> {
+    let code = ResponseCode::from(*input.first().ok_or(Error::UnexpectedEndOfInput)?);
+    let mut cursor = 1;
+    let mut context_bytes = None;
+    if code == ResponseCode::Success && has_context_bytes {
+        let bytes = input.get(cursor..cursor + 4).ok_or(Error::UnexpectedEndOfInput)?;
+        context_bytes = Some(bytes.try_into().expect("correct size"));
+        cursor += 4;
+    }
+    let max_chunk_size =
+        if code == ResponseCode::Success { max_chunk_size } else { MAX_ERROR_MESSAGE_LENGTH };
+    let (payload, consumed) = decode_payload(&input[cursor..], max_chunk_size)?;
+    cursor += consumed;
+    Ok((ResponseChunk { code, context_bytes, payload }, cursor))
+}
+
+/// Decodes all of the response chunks in `input`.
+pub fn decode_response(
+    input: &[u8],
+    has_context_bytes: bool,
+    max_chunk_size: usize,
+) -> Result<Vec<ResponseChunk>, Error> {
+    let mut chunks = vec![];
+    let mut cursor = 0;
+    while cursor < input.len() {
+        let (chunk, consumed) =
+            decode_response_chunk(&input[cursor..], has_context_bytes, max_chunk_size)?;
+        chunks.push(chunk);
+        cursor += consumed;
+    }
+    Ok(chunks)
+}
+
+/// Returns the context bytes used for objects from `fork` on the network identified by
+/// `genesis_validators_root`.
+pub fn context_bytes_for(
+    fork: Fork,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<ForkDigest, Error> {
+    let fork_version = context.fork_version_for(fork);
+    Ok(compute_fork_digest(fork_version, genesis_validators_root)?)
+}
+
+/// Returns the `Fork` identified by `context_bytes` on the network identified by
+/// `genesis_validators_root`.
+pub fn fork_for_context_bytes(
+    context_bytes: ForkDigest,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<Fork, Error> {
+    context
+        .fork_schedule()
+        .fork_for_digest(&context_bytes, genesis_validators_root)?
+        .map(|scheduled_fork| scheduled_fork.fork)
+        .ok_or(Error::UnknownContextBytes(context_bytes))
+}
+
+fn fork_for_chunk(
+    chunk: &ResponseChunk,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<Fork, Error> {
+    if !chunk.is_success() {
+        return Err(Error::UnsuccessfulResponse(chunk.code))
+    }
+    let context_bytes = chunk.context_bytes.ok_or(Error::MissingContextBytes)?;
+    fork_for_context_bytes(context_bytes, genesis_validators_root, context)
+}
+
+/// Decodes the `SignedBeaconBlock` in `chunk`, using its context bytes to select the fork.
+pub fn decode_signed_beacon_block<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    chunk: &ResponseChunk,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<
+    SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    Error,
+> {
+    let fork = fork_for_chunk(chunk, genesis_validators_root, context)?;
+    let payload = &chunk.payload;
+    let block = match fork {
+        Fork::Phase0 => SignedBeaconBlock::Phase0(Deserialize::deserialize(payload)?),
+        Fork::Altair => SignedBeaconBlock::Altair(Deserialize::deserialize(payload)?),
+        Fork::Bellatrix => SignedBeaconBlock::Bellatrix(Deserialize::deserialize(payload)?),
+        Fork::Capella => SignedBeaconBlock::Capella(Deserialize::deserialize(payload)?),
+        Fork::Deneb => SignedBeaconBlock::Deneb(Deserialize::deserialize(payload)?),
+    };
+    Ok(block)
+}
+
+/// Decodes the `BlobSidecar` in `chunk`, verifying its context bytes refer to a fork with blobs.
+pub fn decode_blob_sidecar<
+    const BYTES_PER_BLOB: usize,
+    const KZG_COMMITMENT_INCLUSION_PROOF_DEPTH: usize,
+>(
+    chunk: &ResponseChunk,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<BlobSidecar<BYTES_PER_BLOB, KZG_COMMITMENT_INCLUSION_PROOF_DEPTH>, Error> {
+    let fork = fork_for_chunk(chunk, genesis_validators_root, context)?;
+    match fork {
+        Fork::Deneb => Ok(BlobSidecar::deserialize(&chunk.payload)?),
+        fork => Err(Error::UnsupportedFork(fork)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_roundtrip() {
+        for value in [0, 1, 127, 128, 300, 16384, MAX_CHUNK_SIZE as u64, u64::MAX] {
+            let mut buffer = vec![];
+            encode_varint(value, &mut buffer);
+            let (recovered, consumed) = decode_varint(&buffer).unwrap();
+            assert_eq!(recovered, value);
+            assert_eq!(consumed, buffer.len());
+        }
+        assert_eq!(decode_varint(&[0xac, 0x02]).unwrap(), (300, 2));
+        assert!(matches!(decode_varint(&[0x80; 11]), Err(Error::VarintTooLong)));
+        assert!(matches!(decode_varint(&[0x80, 0x80]), Err(Error::UnexpectedEndOfInput)));
+    }
+
+    #[test]
+    fn test_snappy_frames_interoperate() {
+        use std::io::{Read, Write};
+
+        let data = (0..200_000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+        let mut encoded = vec![];
+        compress(&data, &mut encoded).unwrap();
+        let mut recovered = vec![];
+        snap::read::FrameDecoder::new(encoded.as_slice()).read_to_end(&mut recovered).unwrap();
+        assert_eq!(recovered, data);
+
+        let mut encoder = snap::write::FrameEncoder::new(vec![]);
+        encoder.write_all(&data).unwrap();
+        let encoded = encoder.into_inner().unwrap();
+        let (recovered, consumed) = decompress(&encoded, data.len()).unwrap();
+        assert_eq!(recovered, data);
+        assert_eq!(consumed, encoded.len());
+    }
+
+    #[test]
+    fn test_payload_length_is_enforced() {
+        let data = vec![1u8; 64];
+        let mut buffer = vec![];
+        assert!(matches!(
+            encode_payload(&data, 32, &mut buffer),
+            Err(Error::PayloadTooLarge { length: 64, max_chunk_size: 32 })
+        ));
+
+        encode_payload(&data, MAX_CHUNK_SIZE, &mut buffer).unwrap();
+        assert!(matches!(
+            decode_payload(&buffer, 32),
+            Err(Error::PayloadTooLarge { length: 64, max_chunk_size: 32 })
+        ));
+
+        // announce fewer bytes than the frames actually contain
+        let mut tampered = vec![];
+        encode_varint(16, &mut tampered);
+        tampered.extend_from_slice(&buffer[1..]);
+        assert!(matches!(
+            decode_payload(&tampered, MAX_CHUNK_SIZE),
+            Err(Error::PayloadLengthMismatch { expected: 16, provided: 64 })
+        ));
+    }
+
+    #[test]
+    fn test_response_chunks_roundtrip() {
+        let context_bytes = [1, 2, 3, 4];
+        let success = ResponseChunk {
+            code: ResponseCode::Success,
+            context_bytes: Some(context_bytes),
+            payload: vec![7u8; 100],
+        };
+        let mut buffer = vec![];
+        encode_response_chunk(&success, MAX_CHUNK_SIZE, &mut buffer).unwrap();
+        encode_response_chunk(&success, MAX_CHUNK_SIZE, &mut buffer).unwrap();
+        buffer
+            .extend(encode_error_response(ResponseCode::ResourceUnavailable, "no blocks").unwrap());
+
+        let chunks = decode_response(&buffer, true, MAX_CHUNK_SIZE).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0], success);
+        assert_eq!(chunks[1], success);
+        assert_eq!(chunks[2].code, ResponseCode::ResourceUnavailable);
+        assert_eq!(chunks[2].context_bytes, None);
+        assert_eq!(chunks[2].error_message().unwrap(), "no blocks");
+    }
+
+    #[test]
+    fn test_request_roundtrip() {
+        let request = 12345u64;
+        let encoding = encode_request(&request, MAX_CHUNK_SIZE).unwrap();
+        let recovered: u64 = decode_request(&encoding, MAX_CHUNK_SIZE).unwrap();
+        assert_eq!(recovered, request);
+    }
+
+    #[test]
+    fn test_decompress_bounds_announced_length() {
+        // a compressed chunk whose preamble claims close to 4 GiB of data
+        let mut data = vec![];
+        encode_varint(u32::MAX as u64, &mut data);
+        data.extend_from_slice(&[0x00, 0x00]);
+        let mut encoded = vec![];
+        write_chunk_header(CHUNK_TYPE_STREAM_IDENTIFIER, STREAM_IDENTIFIER.len(), &mut encoded);
+        encoded.extend_from_slice(STREAM_IDENTIFIER);
+        write_chunk_header(CHUNK_TYPE_COMPRESSED, CHECKSUM_LENGTH + data.len(), &mut encoded);
+        encoded.extend_from_slice(&[0u8; CHECKSUM_LENGTH]);
+        encoded.extend_from_slice(&data);
+        assert!(matches!(
+            decompress(&encoded, MAX_CHUNK_SIZE),
+            Err(Error::InvalidChunk(CHUNK_TYPE_COMPRESSED))
+        ));
+
+        // a chunk announcing more than the bytes still expected
+        let mut encoded = vec![];
+        compress(&[1u8; 64], &mut encoded).unwrap();
+        assert!(matches!(
+            decompress(&encoded, 16),
+            Err(Error::PayloadLengthMismatch { expected: 16, provided: 64 })
+        ));
+    }
+
+    #[test]
+    fn test_response_codes() {
+        for value in 0..=u8::MAX {
+            let code = ResponseCode::from(value);
+            assert_eq!(u8::from(code), value);
+            assert_eq!(matches!(code, ResponseCode::Reserved(_)), value > 3);
+        }
+    }
+}
@@ -15,6 +15,8 @@ pub enum Network {
     Sepolia,
     Goerli,
     Holesky,
+    Gnosis,
+    Chiado,
     Custom(String),
 }
 
@@ -25,6 +27,8 @@ impl std::fmt::Display for Network {
             Self::Sepolia => write!(f, "sepolia"),
             Self::Goerli => write!(f, "goerli"),
             Self::Holesky => write!(f, "holesky"),
+            Self::Gnosis => write!(f, "gnosis"),
+            Self::Chiado => write!(f, "chiado"),
             Self::Custom(config_dir) => write!(f, "{config_dir}"),
         }
     }
@@ -43,6 +47,8 @@ impl From<String> for Network {
             "sepolia" => Self::Sepolia,
             "goerli" => Self::Goerli,
             "holesky" => Self::Holesky,
+            "gnosis" => Self::Gnosis,
+            "chiado" => Self::Chiado,
             _ => Self::Custom(value),
         }
     }
@@ -57,10 +63,9 @@ impl TryFrom<Network> for Context {
             Network::Sepolia => Ok(Context::for_sepolia()),
             Network::Goerli => Ok(Context::for_goerli()),
             Network::Holesky => Ok(Context::for_holesky()),
-            Network::Custom(config) => {
-                let config_file = PathBuf::from(config).join("config.yaml");
-                Context::try_from_file(config_file)
-            }
+            Network::Gnosis => Ok(Context::for_gnosis()),
+            Network::Chiado => Ok(Context::for_chiado()),
+            Network::Custom(config) => Context::try_from_dir(PathBuf::from(config)),
         }
     }
 }
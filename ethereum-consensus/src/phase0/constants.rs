@@ -2,6 +2,7 @@ pub const BASE_REWARDS_PER_EPOCH: u64 = 4;
 pub const DEPOSIT_CONTRACT_TREE_DEPTH: usize = 2usize.pow(5);
 pub const JUSTIFICATION_BITS_LENGTH: usize = 4;
 pub const DEPOSIT_DATA_LIST_BOUND: usize = 2usize.pow(DEPOSIT_CONTRACT_TREE_DEPTH as u32);
+pub const TARGET_AGGREGATORS_PER_COMMITTEE: usize = 2usize.pow(4);
 
 pub use crate::phase0::networking::{
     ATTESTATION_PROPAGATION_SLOT_RANGE, ATTESTATION_SUBNET_COUNT, GOSSIP_MAX_SIZE,
@@ -1,4 +1,10 @@
-use crate::{primitives::Epoch, ssz::prelude::Bitvector};
+use crate::{
+    crypto::hash,
+    phase0::helpers::compute_shuffled_index,
+    primitives::{CommitteeIndex, Epoch, ForkDigest, NodeId, Root, Slot, SubnetId, Version},
+    ssz::prelude::*,
+    state_transition::{Context, Result},
+};
 use std::time::Duration;
 
 pub const ATTESTATION_SUBNET_COUNT: usize = 64;
@@ -10,10 +16,220 @@ pub const TTFB_TIMEOUT: Duration = Duration::from_secs(5);
 pub const RESP_TIMEOUT: Duration = Duration::from_secs(10);
 pub const ATTESTATION_PROPAGATION_SLOT_RANGE: usize = 32;
 pub const MAXIMUM_GOSSIP_CLOCK_DISPARITY: Duration = Duration::from_millis(500);
+pub const SUBNETS_PER_NODE: usize = 2;
+pub const EPOCHS_PER_SUBNET_SUBSCRIPTION: Epoch = 2u64.pow(8);
+pub const ATTESTATION_SUBNET_EXTRA_BITS: u32 = 0;
+pub const ATTESTATION_SUBNET_PREFIX_BITS: u32 =
+    ATTESTATION_SUBNET_COUNT.ilog2() + ATTESTATION_SUBNET_EXTRA_BITS;
+pub const NODE_ID_BITS: u32 = 256;
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
 pub struct MetaData {
     #[serde(with = "crate::serde::as_str")]
     pub seq_number: u64,
     pub attnets: Bitvector<ATTESTATION_SUBNET_COUNT>,
 }
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct EnrForkId {
+    #[serde(with = "crate::serde::as_hex")]
+    pub fork_digest: ForkDigest,
+    #[serde(with = "crate::serde::as_hex")]
+    pub next_fork_version: Version,
+    #[serde(with = "crate::serde::as_str")]
+    pub next_fork_epoch: Epoch,
+}
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct Status {
+    #[serde(with = "crate::serde::as_hex")]
+    pub fork_digest: ForkDigest,
+    pub finalized_root: Root,
+    #[serde(with = "crate::serde::as_str")]
+    pub finalized_epoch: Epoch,
+    pub head_root: Root,
+    #[serde(with = "crate::serde::as_str")]
+    pub head_slot: Slot,
+}
+
+pub type Goodbye = u64;
+
+pub type Ping = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoodbyeReason {
+    ClientShutdown,
+    IrrelevantNetwork,
+    FaultOrError,
+    // Values from 128 are reserved for client-specific reasons.
+    Other(Goodbye),
+}
+
+impl From<Goodbye> for GoodbyeReason {
+    fn from(value: Goodbye) -> Self {
+        match value {
+            1 => Self::ClientShutdown,
+            2 => Self::IrrelevantNetwork,
+            3 => Self::FaultOrError,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<GoodbyeReason> for Goodbye {
+    fn from(value: GoodbyeReason) -> Self {
+        match value {
+            GoodbyeReason::ClientShutdown => 1,
+            GoodbyeReason::IrrelevantNetwork => 2,
+            GoodbyeReason::FaultOrError => 3,
+            GoodbyeReason::Other(other) => other,
+        }
+    }
+}
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct BeaconBlocksByRangeRequest {
+    #[serde(with = "crate::serde::as_str")]
+    pub start_slot: Slot,
+    #[serde(with = "crate::serde::as_str")]
+    pub count: u64,
+    // NOTE: deprecated, responders should treat any value as `1`
+    #[serde(with = "crate::serde::as_str")]
+    pub step: u64,
+}
+
+pub type BeaconBlocksByRootRequest = List<Root, MAX_REQUEST_BLOCKS>;
+
+// Compute the correct subnet for an attestation for Phase 0.
+// NOTE: this subnet assignment depends on the number of committees per slot, which is
+// usually derived from the state via `get_committee_count_per_slot`.
+pub fn compute_subnet_for_attestation(
+    committees_per_slot: u64,
+    slot: Slot,
+    committee_index: CommitteeIndex,
+    context: &Context,
+) -> SubnetId {
+    let slots_since_epoch_start = slot % context.slots_per_epoch;
+    let committees_since_epoch_start = committees_per_slot * slots_since_epoch_start;
+    (committees_since_epoch_start + committee_index as u64) % ATTESTATION_SUBNET_COUNT as u64
+}
+
+pub fn compute_subscribed_subnet(
+    node_id: &NodeId,
+    epoch: Epoch,
+    index: usize,
+    context: &Context,
+) -> Result<SubnetId> {
+    let node_id = node_id.to_be_bytes::<32>();
+    // NOTE: `NODE_ID_BITS` is a multiple of 64 so the most and least significant limbs can be
+    // used directly for the prefix and the offset, respectively.
+    let most_significant_limb = u64::from_be_bytes(node_id[..8].try_into().expect("correct size"));
+    let least_significant_limb =
+        u64::from_be_bytes(node_id[24..].try_into().expect("correct size"));
+    let node_id_prefix = most_significant_limb >> (u64::BITS - ATTESTATION_SUBNET_PREFIX_BITS);
+    let node_offset = least_significant_limb % EPOCHS_PER_SUBNET_SUBSCRIPTION;
+
+    let permutation_seed =
+        hash(((epoch + node_offset) / EPOCHS_PER_SUBNET_SUBSCRIPTION).to_le_bytes());
+    let permutated_prefix = compute_shuffled_index(
+        node_id_prefix as usize,
+        1 << ATTESTATION_SUBNET_PREFIX_BITS,
+        &permutation_seed,
+        context,
+    )?;
+    Ok(((permutated_prefix + index) % ATTESTATION_SUBNET_COUNT) as SubnetId)
+}
+
+// Return the long-lived attestation subnets a node with `node_id` should be subscribed to in
+// `epoch`. The assignment is deterministic so peers can verify it from the node's ENR.
+pub fn compute_subscribed_subnets(
+    node_id: &NodeId,
+    epoch: Epoch,
+    context: &Context,
+) -> Result<Vec<SubnetId>> {
+    (0..SUBNETS_PER_NODE)
+        .map(|index| compute_subscribed_subnet(node_id, epoch, index, context))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::try_bytes_from_hex_str;
+
+    #[test]
+    fn test_compute_subnet_for_attestation() {
+        let context = Context::for_mainnet();
+        assert_eq!(compute_subnet_for_attestation(4, 33, 2, &context), 6);
+        assert_eq!(compute_subnet_for_attestation(64, 31, 63, &context), 63);
+        assert_eq!(compute_subnet_for_attestation(3, 30, 1, &context), 27);
+    }
+
+    #[test]
+    fn test_compute_subscribed_subnets() {
+        let context = Context::for_mainnet();
+        let mut last_byte_set = [0u8; 32];
+        last_byte_set[31] = 1;
+        let mut high_and_low_bytes_set = [0u8; 32];
+        high_and_low_bytes_set[0] = 0xff;
+        high_and_low_bytes_set[31] = 0x80;
+        // expected values follow the reference implementation in the consensus specs
+        for (node_id, epoch, expected) in [
+            ([0xab; 32], 0, [3, 4]),
+            ([0xab; 32], 255, [0, 1]),
+            ([0xab; 32], 100000, [62, 63]),
+            (last_byte_set, 0, [49, 50]),
+            (last_byte_set, 255, [16, 17]),
+            (last_byte_set, 100000, [35, 36]),
+            (high_and_low_bytes_set, 0, [57, 58]),
+            (high_and_low_bytes_set, 255, [55, 56]),
+            (high_and_low_bytes_set, 100000, [25, 26]),
+        ] {
+            let node_id = NodeId::from_be_bytes(node_id);
+            let subnets = compute_subscribed_subnets(&node_id, epoch, &context).unwrap();
+            assert_eq!(subnets, expected);
+        }
+    }
+
+    #[test]
+    fn test_req_resp_encodings() {
+        let mut status = Status {
+            fork_digest: [0xb5, 0x30, 0x3f, 0x2a],
+            finalized_root: Root::try_from([0x11; 32].as_ref()).unwrap(),
+            finalized_epoch: 7,
+            head_root: Root::try_from([0x22; 32].as_ref()).unwrap(),
+            head_slot: 250,
+        };
+        let expected = try_bytes_from_hex_str(
+            "0xb5303f2a1111111111111111111111111111111111111111111111111111111111111111070000000000\
+             00002222222222222222222222222222222222222222222222222222222222222222fa00000000000000",
+        )
+        .unwrap();
+        assert_eq!(serialize(&status).unwrap(), expected);
+        assert_eq!(Status::deserialize(&expected).unwrap(), status);
+        let expected = try_bytes_from_hex_str(
+            "0xab901b1978619ab08e25fd4f43cf05f7c4866cc8875b22113dfaf4746bd9076a",
+        )
+        .unwrap();
+        assert_eq!(status.hash_tree_root().unwrap().as_ref(), expected);
+
+        let mut request = BeaconBlocksByRangeRequest { start_slot: 1024, count: 64, step: 1 };
+        let expected =
+            try_bytes_from_hex_str("0x000400000000000040000000000000000100000000000000").unwrap();
+        assert_eq!(serialize(&request).unwrap(), expected);
+        assert_eq!(BeaconBlocksByRangeRequest::deserialize(&expected).unwrap(), request);
+        let expected = try_bytes_from_hex_str(
+            "0x23172b66ff1e2542e65a34fc9a5515cc2c3fe4db371c6413b2eba932428a3d67",
+        )
+        .unwrap();
+        assert_eq!(request.hash_tree_root().unwrap().as_ref(), expected);
+    }
+}
@@ -1,8 +1,11 @@
+pub mod gnosis;
 pub mod mainnet;
 pub mod minimal;
 
 use crate::primitives::{Epoch, Gwei, Slot};
 
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub struct Preset {
     pub max_committees_per_slot: u64,
     pub target_committee_size: u64,
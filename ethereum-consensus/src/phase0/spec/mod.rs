@@ -15,7 +15,7 @@ pub use crate::{
         },
         constants::{
             BASE_REWARDS_PER_EPOCH, DEPOSIT_CONTRACT_TREE_DEPTH, DEPOSIT_DATA_LIST_BOUND,
-            JUSTIFICATION_BITS_LENGTH,
+            JUSTIFICATION_BITS_LENGTH, TARGET_AGGREGATORS_PER_COMMITTEE,
         },
         epoch_processing::{
             get_attestation_component_deltas, get_attestation_deltas, get_attesting_balance,
@@ -52,7 +52,11 @@ pub use crate::{
         },
         slot_processing::{process_slot, process_slots},
         state_transition::{state_transition, state_transition_block_in_slot},
-        validator::{AggregateAndProof, Eth1Block, SignedAggregateAndProof, Validator},
+        validator::{
+            get_attestation_duties, get_committee_assignment, get_proposer_duties,
+            get_slot_signature, is_aggregator, AggregateAndProof, AttestationDuty, Eth1Block,
+            ProposerDuty, SignedAggregateAndProof, Validator,
+        },
     },
     primitives::*,
     signing::*,
@@ -1,8 +1,26 @@
 use crate::{
-    phase0::operations::Attestation,
-    primitives::{BlsPublicKey, BlsSignature, Bytes32, Epoch, Gwei, Root, ValidatorIndex},
+    crypto::hash,
+    error::Error,
+    phase0::{
+        beacon_state::BeaconState,
+        constants::TARGET_AGGREGATORS_PER_COMMITTEE,
+        helpers::{
+            compute_epoch_at_slot, compute_proposer_index, compute_start_slot_at_epoch,
+            get_active_validator_indices, get_beacon_committee, get_committee_count_per_slot,
+            get_current_epoch, get_domain, get_previous_epoch, get_seed,
+        },
+        operations::Attestation,
+        slot_processing::process_slots,
+    },
+    primitives::{
+        BlsPublicKey, BlsSecretKey, BlsSignature, Bytes32, CommitteeIndex, DomainType, Epoch, Gwei,
+        Root, Slot, ValidatorIndex,
+    },
+    signing::sign_with_domain,
     ssz::prelude::*,
+    state_transition::{Context, Result},
 };
+use std::{cmp, collections::HashSet};
 
 #[derive(
     Default, Debug, SimpleSerialize, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize,
@@ -45,3 +63,398 @@ pub struct SignedAggregateAndProof<const MAX_VALIDATORS_PER_COMMITTEE: usize> {
     pub message: AggregateAndProof<MAX_VALIDATORS_PER_COMMITTEE>,
     pub signature: BlsSignature,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AttestationDuty {
+    #[serde(rename = "pubkey")]
+    pub public_key: BlsPublicKey,
+    #[serde(with = "crate::serde::as_str")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "crate::serde::as_str")]
+    pub committee_index: CommitteeIndex,
+    #[serde(with = "crate::serde::as_str")]
+    pub committee_length: usize,
+    #[serde(with = "crate::serde::as_str")]
+    pub committees_at_slot: usize,
+    #[serde(with = "crate::serde::as_str")]
+    pub validator_committee_index: usize,
+    #[serde(with = "crate::serde::as_str")]
+    pub slot: Slot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProposerDuty {
+    #[serde(rename = "pubkey")]
+    pub public_key: BlsPublicKey,
+    #[serde(with = "crate::serde::as_str")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "crate::serde::as_str")]
+    pub slot: Slot,
+}
+
+pub fn get_slot_signature<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    slot: Slot,
+    private_key: &BlsSecretKey,
+    context: &Context,
+) -> Result<BlsSignature> {
+    let epoch = compute_epoch_at_slot(slot, context);
+    let domain = get_domain(state, DomainType::SelectionProof, Some(epoch), context)?;
+    let mut slot = slot;
+    sign_with_domain(&mut slot, private_key, domain)
+}
+
+pub fn is_aggregator<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    slot: Slot,
+    committee_index: CommitteeIndex,
+    slot_signature: &BlsSignature,
+    context: &Context,
+) -> Result<bool> {
+    let committee = get_beacon_committee(state, slot, committee_index, context)?;
+    let modulo = cmp::max(1, committee.len() / TARGET_AGGREGATORS_PER_COMMITTEE) as u64;
+    let digest = hash(slot_signature.as_ref());
+    let value = u64::from_le_bytes(digest[..8].try_into().expect("correct size"));
+    Ok(value % modulo == 0)
+}
+
+// Return the committee assignment in the `epoch` for `validator_index`.
+// `assignment` returned is a tuple of the following form:
+//     * `assignment[0]` is the list of validators in the committee
+//     * `assignment[1]` is the index to which the committee is assigned
+//     * `assignment[2]` is the slot at which the committee is assigned
+// Return `None` if no assignment.
+pub fn get_committee_assignment<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    epoch: Epoch,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<Option<(Vec<ValidatorIndex>, CommitteeIndex, Slot)>> {
+    let previous_epoch = get_previous_epoch(state, context);
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < previous_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch })
+    }
+
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let committee_count_per_slot = get_committee_count_per_slot(state, epoch, context);
+    for slot in start_slot..start_slot + context.slots_per_epoch {
+        for index in 0..committee_count_per_slot {
+            let committee = get_beacon_committee(state, slot, index, context)?;
+            if committee.contains(&validator_index) {
+                return Ok(Some((committee, index, slot)))
+            }
+        }
+    }
+    Ok(None)
+}
+
+// Return the attestation duties in `epoch` for each of the validators in `validator_indices`,
+// in the same shape as the beacon node API. `epoch` must be between the previous and the next
+// epoch of `state`, inclusive.
+pub fn get_attestation_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    epoch: Epoch,
+    validator_indices: &[ValidatorIndex],
+    context: &Context,
+) -> Result<Vec<AttestationDuty>> {
+    let previous_epoch = get_previous_epoch(state, context);
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < previous_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch })
+    }
+    let mut targets = HashSet::with_capacity(validator_indices.len());
+    for &index in validator_indices {
+        if index >= state.validators.len() {
+            return Err(Error::OutOfBounds { requested: index, bound: state.validators.len() })
+        }
+        targets.insert(index);
+    }
+
+    let mut duties = vec![];
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let committees_at_slot = get_committee_count_per_slot(state, epoch, context);
+    for slot in start_slot..start_slot + context.slots_per_epoch {
+        for committee_index in 0..committees_at_slot {
+            let committee = get_beacon_committee(state, slot, committee_index, context)?;
+            for (validator_committee_index, validator_index) in committee.iter().enumerate() {
+                if targets.contains(validator_index) {
+                    duties.push(AttestationDuty {
+                        public_key: state.validators[*validator_index].public_key.clone(),
+                        validator_index: *validator_index,
+                        committee_index,
+                        committee_length: committee.len(),
+                        committees_at_slot,
+                        validator_committee_index,
+                        slot,
+                    });
+                }
+            }
+        }
+    }
+    Ok(duties)
+}
+
+// Return the block proposer for each slot in `epoch`.
+// Proposer selection depends on the effective balances at the start of `epoch`, so if `epoch` is
+// the next epoch of `state`, a copy of `state` is advanced to the start of `epoch`.
+// Like the beacon node API, only duties for the current and the next epoch of `state` can be
+// computed.
+pub fn get_proposer_duties<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    epoch: Epoch,
+    context: &Context,
+) -> Result<Vec<ProposerDuty>> {
+    let current_epoch = get_current_epoch(state, context);
+    if epoch < current_epoch || epoch > current_epoch + 1 {
+        return Err(Error::InvalidDutyEpoch { requested: epoch, current: current_epoch })
+    }
+
+    let start_slot = compute_start_slot_at_epoch(epoch, context);
+    let advanced_state;
+    let state = if epoch > current_epoch {
+        let mut state = state.clone();
+        process_slots(&mut state, start_slot, context)?;
+        advanced_state = state;
+        &advanced_state
+    } else {
+        state
+    };
+
+    let seed = get_seed(state, epoch, DomainType::BeaconProposer, context);
+    let indices = get_active_validator_indices(state, epoch);
+    let mut input = [0u8; 40];
+    input[..32].copy_from_slice(seed.as_ref());
+    (start_slot..start_slot + context.slots_per_epoch)
+        .map(|slot| {
+            input[32..40].copy_from_slice(&slot.to_le_bytes());
+            let seed = hash(input);
+            let validator_index = compute_proposer_index(state, &indices, &seed, context)?;
+            Ok(ProposerDuty {
+                public_key: state.validators[validator_index].public_key.clone(),
+                validator_index,
+                slot,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        phase0::{helpers::get_beacon_proposer_index, minimal},
+        primitives::FAR_FUTURE_EPOCH,
+    };
+
+    const VALIDATOR_COUNT: usize = 64;
+
+    // Returns a state in the middle of epoch 2 with `VALIDATOR_COUNT` active validators.
+    fn test_state(context: &Context) -> minimal::BeaconState {
+        let validators = (0..VALIDATOR_COUNT)
+            .map(|i| Validator {
+                public_key: BlsSecretKey::key_gen(&[i as u8 + 1; 32]).unwrap().public_key(),
+                effective_balance: context.max_effective_balance,
+                exit_epoch: FAR_FUTURE_EPOCH,
+                withdrawable_epoch: FAR_FUTURE_EPOCH,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let mut state =
+            minimal::BeaconState { slot: 2 * context.slots_per_epoch + 3, ..Default::default() };
+        state.validators = validators.try_into().unwrap();
+        state.balances = vec![context.max_effective_balance; VALIDATOR_COUNT].try_into().unwrap();
+        state
+    }
+
+    #[test]
+    fn test_committee_assignment() {
+        let context = Context::for_minimal();
+        let state = test_state(&context);
+        let current_epoch = get_current_epoch(&state, &context);
+        let validator_indices = (0..VALIDATOR_COUNT).collect::<Vec<_>>();
+        for epoch in [current_epoch - 1, current_epoch, current_epoch + 1] {
+            let duties =
+                get_attestation_duties(&state, epoch, &validator_indices, &context).unwrap();
+            assert_eq!(duties.len(), VALIDATOR_COUNT);
+            for duty in duties {
+                let (committee, committee_index, slot) =
+                    get_committee_assignment(&state, epoch, duty.validator_index, &context)
+                        .unwrap()
+                        .unwrap();
+                assert_eq!(
+                    committee,
+                    get_beacon_committee(&state, slot, committee_index, &context).unwrap()
+                );
+                assert_eq!(committee[duty.validator_committee_index], duty.validator_index);
+                assert_eq!(duty.committee_index, committee_index);
+                assert_eq!(duty.committee_length, committee.len());
+                assert_eq!(duty.slot, slot);
+            }
+            let assignment =
+                get_committee_assignment(&state, epoch, VALIDATOR_COUNT, &context).unwrap();
+            assert!(assignment.is_none());
+        }
+    }
+
+    #[test]
+    fn test_proposer_duties() {
+        let context = Context::for_minimal();
+        let state = test_state(&context);
+        let current_epoch = get_current_epoch(&state, &context);
+        let mut next_state = state.clone();
+        let next_epoch_start_slot = compute_start_slot_at_epoch(current_epoch + 1, &context);
+        process_slots(&mut next_state, next_epoch_start_slot, &context).unwrap();
+
+        for (epoch, state_at_epoch) in [(current_epoch, &state), (current_epoch + 1, &next_state)] {
+            let duties = get_proposer_duties(&state, epoch, &context).unwrap();
+            let start_slot = compute_start_slot_at_epoch(epoch, &context);
+            let slots = (start_slot..start_slot + context.slots_per_epoch).collect::<Vec<_>>();
+            assert_eq!(duties.iter().map(|duty| duty.slot).collect::<Vec<_>>(), slots);
+            for duty in duties {
+                let mut state = state_at_epoch.clone();
+                state.slot = duty.slot;
+                let proposer_index = get_beacon_proposer_index(&state, &context).unwrap();
+                assert_eq!(duty.validator_index, proposer_index);
+                assert_eq!(duty.public_key, state.validators[proposer_index].public_key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_duties_reject_epochs() {
+        let context = Context::for_minimal();
+        let state = test_state(&context);
+        let current_epoch = get_current_epoch(&state, &context);
+        for epoch in [current_epoch - 2, current_epoch + 2] {
+            assert!(get_committee_assignment(&state, epoch, 0, &context).is_err());
+            assert!(get_attestation_duties(&state, epoch, &[0], &context).is_err());
+            assert!(get_proposer_duties(&state, epoch, &context).is_err());
+        }
+        assert!(get_proposer_duties(&state, current_epoch - 1, &context).is_err());
+    }
+
+    #[test]
+    fn test_slot_signature_and_aggregator() {
+        let context = Context::for_minimal();
+        let mut state = test_state(&context);
+        state.fork.current_version = context.genesis_fork_version;
+        state.genesis_validators_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+        // committees of 32 validators select aggregators with a modulo of 2
+        let validator = state.validators[0].clone();
+        state.validators = vec![validator; 1024].try_into().unwrap();
+        let private_key = BlsSecretKey::key_gen(&[7u8; 32]).unwrap();
+
+        for (slot, expected_signature, expected_is_aggregator) in [
+            (
+                17,
+                "0x85146861f89f5cac0d7137aa3b6dcf12d6164ef8f09a635d02fa84e8f994062e7333115e25476ff48eb9927a112354d90c58dde1f3f80712cf8ba53315882d53cf5697055a400907fb3ddb8fc31d3ff477d8436a1366ec54222ee1e8f542e133",
+                true,
+            ),
+            (
+                18,
+                "0x806d72f6cb1ae03dc6c6ead88464ab1df8a8661586cd30095edcf1d3953541b606a2aa817563b7c1c8328d84d681358718d1fce6dbd909f789d1f54f86d7191b6417fdfd573545bd68355a1e6f531b116feff664a6d4edacf568941f4eec9c3d",
+                false,
+            ),
+        ] {
+            let signature = get_slot_signature(&state, slot, &private_key, &context).unwrap();
+            let expected_signature =
+                crate::serde::try_bytes_from_hex_str(expected_signature).unwrap();
+            assert_eq!(signature, BlsSignature::try_from(expected_signature.as_ref()).unwrap());
+            assert_eq!(
+                is_aggregator(&state, slot, 0, &signature, &context).unwrap(),
+                expected_is_aggregator
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::ssz::prelude::*;
 pub use crate::{
-    crypto::{PublicKey as BlsPublicKey, Signature as BlsSignature},
+    crypto::{PublicKey as BlsPublicKey, SecretKey as BlsSecretKey, Signature as BlsSignature},
     domains::DomainType,
     ssz::prelude::U256,
 };
@@ -30,6 +30,9 @@ pub type Bytes32 = ByteVector<32>;
 
 pub type ParticipationFlags = u8;
 
+pub type SubnetId = u64;
+pub type NodeId = U256;
+
 // Coordinate refers to a unique location in the block tree
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Coordinate {
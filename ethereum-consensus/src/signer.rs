@@ -0,0 +1,387 @@
+//! A typed interface for signing consensus messages.
+//!
+//! Each method of [`Signer`] derives the domain for its message from the `Context` and the
+//! `genesis_validators_root` of the chain, so callers never pick a `DomainType` or fork version
+//! by hand. Implementations only need to provide [`Signer::public_key`] and
+//! [`Signer::sign_root`].
+use crate::{
+    altair::{ContributionAndProof, SyncAggregatorSelectionData},
+    builder::{compute_builder_domain, ValidatorRegistration},
+    capella::BlsToExecutionChange,
+    crypto::SecretKey,
+    phase0::{
+        compute_domain, compute_epoch_at_slot, AggregateAndProof, AttestationData,
+        BeaconBlockHeader, DepositMessage, VoluntaryExit,
+    },
+    primitives::{BlsPublicKey, BlsSignature, Domain, DomainType, Epoch, Root, Slot},
+    signing::compute_signing_root,
+    ssz::prelude::*,
+    state_transition::Context,
+    types::BeaconBlock,
+    Error, Fork,
+};
+
+/// Returns the domain of `domain_type` for a message at `epoch`, using the fork version active
+/// at `epoch` according to the fork schedule in `context`.
+pub fn compute_domain_at_epoch(
+    domain_type: DomainType,
+    epoch: Epoch,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<Domain, Error> {
+    let fork_version = context.fork_schedule().fork_at_epoch(epoch).version;
+    compute_domain(domain_type, Some(fork_version), Some(genesis_validators_root), context)
+}
+
+/// Returns the domain for a `VoluntaryExit` at `voluntary_exit_epoch` that is processed by a
+/// chain at `current_epoch`.
+///
+/// The domain follows the fork the exit is processed in rather than the epoch of the exit itself:
+/// from Deneb onwards, exits are always verified against the Capella fork version (EIP-7044) so
+/// that they remain valid across future forks.
+pub fn compute_voluntary_exit_domain(
+    voluntary_exit_epoch: Epoch,
+    current_epoch: Epoch,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<Domain, Error> {
+    let fork_schedule = context.fork_schedule();
+    let fork_version = if fork_schedule.fork_at_epoch(current_epoch).fork >= Fork::Deneb {
+        context.capella_fork_version
+    } else {
+        let fork = fork_schedule.fork_data(current_epoch);
+        if voluntary_exit_epoch < fork.epoch {
+            fork.previous_version
+        } else {
+            fork.current_version
+        }
+    };
+    compute_domain(
+        DomainType::VoluntaryExit,
+        Some(fork_version),
+        Some(genesis_validators_root),
+        context,
+    )
+}
+
+pub trait Signer {
+    fn public_key(&self) -> BlsPublicKey;
+
+    fn sign_root(&self, signing_root: Root) -> Result<BlsSignature, Error>;
+
+    fn sign_with_domain<T: Merkleized>(
+        &self,
+        data: &mut T,
+        domain: Domain,
+    ) -> Result<BlsSignature, Error> {
+        let signing_root = compute_signing_root(data, domain)?;
+        self.sign_root(signing_root)
+    }
+
+    fn sign_block<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+    >(
+        &self,
+        block: &mut BeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(block.slot(), context);
+        let domain = compute_domain_at_epoch(
+            DomainType::BeaconProposer,
+            epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        self.sign_with_domain(block, domain)
+    }
+
+    // The root of a block header matches the root of the block it summarizes, so this produces
+    // the same signature as `sign_block` (including for blinded blocks).
+    fn sign_block_header(
+        &self,
+        header: &mut BeaconBlockHeader,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(header.slot, context);
+        let domain = compute_domain_at_epoch(
+            DomainType::BeaconProposer,
+            epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        self.sign_with_domain(header, domain)
+    }
+
+    fn sign_randao_reveal(
+        &self,
+        epoch: Epoch,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let domain =
+            compute_domain_at_epoch(DomainType::Randao, epoch, genesis_validators_root, context)?;
+        let mut epoch = epoch;
+        self.sign_with_domain(&mut epoch, domain)
+    }
+
+    fn sign_attestation_data(
+        &self,
+        attestation_data: &mut AttestationData,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let domain = compute_domain_at_epoch(
+            DomainType::BeaconAttester,
+            attestation_data.target.epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        self.sign_with_domain(attestation_data, domain)
+    }
+
+    fn sign_aggregate_selection_proof(
+        &self,
+        slot: Slot,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(slot, context);
+        let domain = compute_domain_at_epoch(
+            DomainType::SelectionProof,
+            epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        let mut slot = slot;
+        self.sign_with_domain(&mut slot, domain)
+    }
+
+    fn sign_aggregate_and_proof<const MAX_VALIDATORS_PER_COMMITTEE: usize>(
+        &self,
+        aggregate_and_proof: &mut AggregateAndProof<MAX_VALIDATORS_PER_COMMITTEE>,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(aggregate_and_proof.aggregate.data.slot, context);
+        let domain = compute_domain_at_epoch(
+            DomainType::AggregateAndProof,
+            epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        self.sign_with_domain(aggregate_and_proof, domain)
+    }
+
+    fn sign_sync_committee_message(
+        &self,
+        slot: Slot,
+        beacon_block_root: Root,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(slot, context);
+        let domain = compute_domain_at_epoch(
+            DomainType::SyncCommittee,
+            epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        let mut beacon_block_root = beacon_block_root;
+        self.sign_with_domain(&mut beacon_block_root, domain)
+    }
+
+    fn sign_sync_committee_selection_proof(
+        &self,
+        selection_data: &mut SyncAggregatorSelectionData,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(selection_data.slot, context);
+        let domain = compute_domain_at_epoch(
+            DomainType::SyncCommitteeSelectionProof,
+            epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        self.sign_with_domain(selection_data, domain)
+    }
+
+    fn sign_contribution_and_proof<const SYNC_SUBCOMMITTEE_SIZE: usize>(
+        &self,
+        contribution_and_proof: &mut ContributionAndProof<SYNC_SUBCOMMITTEE_SIZE>,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let epoch = compute_epoch_at_slot(contribution_and_proof.contribution.slot, context);
+        let domain = compute_domain_at_epoch(
+            DomainType::ContributionAndProof,
+            epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        self.sign_with_domain(contribution_and_proof, domain)
+    }
+
+    fn sign_voluntary_exit(
+        &self,
+        voluntary_exit: &mut VoluntaryExit,
+        current_epoch: Epoch,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let domain = compute_voluntary_exit_domain(
+            voluntary_exit.epoch,
+            current_epoch,
+            genesis_validators_root,
+            context,
+        )?;
+        self.sign_with_domain(voluntary_exit, domain)
+    }
+
+    // `BlsToExecutionChange` messages are valid in any fork so they use the genesis fork version.
+    fn sign_bls_to_execution_change(
+        &self,
+        bls_to_execution_change: &mut BlsToExecutionChange,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let domain = compute_domain(
+            DomainType::BlsToExecutionChange,
+            None,
+            Some(genesis_validators_root),
+            context,
+        )?;
+        self.sign_with_domain(bls_to_execution_change, domain)
+    }
+
+    // Deposits are valid on any fork of any chain with the same genesis fork version.
+    fn sign_deposit_message(
+        &self,
+        deposit_message: &mut DepositMessage,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let domain = compute_domain(DomainType::Deposit, None, None, context)?;
+        self.sign_with_domain(deposit_message, domain)
+    }
+
+    fn sign_validator_registration(
+        &self,
+        registration: &mut ValidatorRegistration,
+        context: &Context,
+    ) -> Result<BlsSignature, Error> {
+        let domain = compute_builder_domain(context)?;
+        self.sign_with_domain(registration, domain)
+    }
+}
+
+impl Signer for SecretKey {
+    fn public_key(&self) -> BlsPublicKey {
+        SecretKey::public_key(self)
+    }
+
+    fn sign_root(&self, signing_root: Root) -> Result<BlsSignature, Error> {
+        Ok(self.sign(signing_root.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::verify_signed_data;
+
+    #[test]
+    fn test_voluntary_exit_domain() {
+        let mut context = Context::for_mainnet();
+        context.deneb_fork_epoch = context.capella_fork_epoch + 1024;
+        let genesis_validators_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+        let capella_domain = compute_domain_at_epoch(
+            DomainType::VoluntaryExit,
+            context.capella_fork_epoch,
+            genesis_validators_root,
+            &context,
+        )
+        .unwrap();
+
+        // an exit from before Capella is still verified against Capella once Deneb is active
+        for voluntary_exit_epoch in [context.bellatrix_fork_epoch, context.deneb_fork_epoch] {
+            let domain = compute_voluntary_exit_domain(
+                voluntary_exit_epoch,
+                context.deneb_fork_epoch,
+                genesis_validators_root,
+                &context,
+            )
+            .unwrap();
+            assert_eq!(domain, capella_domain);
+        }
+
+        let bellatrix_domain = compute_domain_at_epoch(
+            DomainType::VoluntaryExit,
+            context.bellatrix_fork_epoch,
+            genesis_validators_root,
+            &context,
+        )
+        .unwrap();
+        let domain = compute_voluntary_exit_domain(
+            context.bellatrix_fork_epoch,
+            context.capella_fork_epoch,
+            genesis_validators_root,
+            &context,
+        )
+        .unwrap();
+        assert_eq!(domain, bellatrix_domain);
+    }
+
+    #[test]
+    fn test_sign_attestation_data() {
+        let mut context = Context::for_mainnet();
+        context.deneb_fork_epoch = context.capella_fork_epoch + 1024;
+        let genesis_validators_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+        let secret_key = SecretKey::key_gen(&[7u8; 32]).unwrap();
+
+        let mut attestation_data = AttestationData::default();
+        attestation_data.target.epoch = context.deneb_fork_epoch;
+        let signature = secret_key
+            .sign_attestation_data(&mut attestation_data, genesis_validators_root, &context)
+            .unwrap();
+
+        let domain = compute_domain(
+            DomainType::BeaconAttester,
+            Some(context.deneb_fork_version),
+            Some(genesis_validators_root),
+            &context,
+        )
+        .unwrap();
+        let public_key = Signer::public_key(&secret_key);
+        verify_signed_data(&mut attestation_data, &signature, &public_key, domain).unwrap();
+    }
+}
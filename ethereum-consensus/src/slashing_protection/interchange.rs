@@ -0,0 +1,66 @@
+use crate::{
+    crypto::PublicKey,
+    primitives::{Epoch, Root, Slot},
+    slashing_protection::ValidatorHistory,
+};
+
+pub const INTERCHANGE_FORMAT_VERSION: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Interchange {
+    pub metadata: InterchangeMetadata,
+    pub data: Vec<InterchangeData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InterchangeMetadata {
+    #[serde(with = "crate::serde::as_str")]
+    pub interchange_format_version: u64,
+    pub genesis_validators_root: Root,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InterchangeData {
+    #[serde(rename = "pubkey")]
+    pub public_key: PublicKey,
+    pub signed_blocks: Vec<SignedBlock>,
+    pub signed_attestations: Vec<SignedAttestation>,
+}
+
+impl InterchangeData {
+    pub fn from_history(public_key: PublicKey, history: &ValidatorHistory) -> Self {
+        let signed_blocks = history
+            .blocks
+            .iter()
+            .map(|(slot, signing_root)| SignedBlock { slot: *slot, signing_root: *signing_root })
+            .collect();
+        let signed_attestations = history
+            .attestations
+            .iter()
+            .map(|(target_epoch, record)| SignedAttestation {
+                source_epoch: record.source_epoch,
+                target_epoch: *target_epoch,
+                signing_root: record.signing_root,
+            })
+            .collect();
+        Self { public_key, signed_blocks, signed_attestations }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SignedBlock {
+    #[serde(with = "crate::serde::as_str")]
+    pub slot: Slot,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<Root>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SignedAttestation {
+    #[serde(with = "crate::serde::as_str")]
+    pub source_epoch: Epoch,
+    #[serde(with = "crate::serde::as_str")]
+    pub target_epoch: Epoch,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_root: Option<Root>,
+}
@@ -0,0 +1,514 @@
+//! Slashing protection for validator clients, following
+//! [EIP-3076](https://eips.ethereum.org/EIPS/eip-3076).
+//!
+//! Every signed block and attestation is recorded per validator public key before the signature
+//! is released. Surround votes are detected with min-max span tracking over the source epochs
+//! of the attestations signed so far.
+mod interchange;
+mod store;
+
+pub use interchange::{
+    Interchange, InterchangeData, InterchangeMetadata, SignedAttestation, SignedBlock,
+    INTERCHANGE_FORMAT_VERSION,
+};
+pub use store::{FileStore, MemoryStore, Store};
+
+use crate::{
+    crypto::PublicKey,
+    phase0::{AttestationData, BeaconBlockHeader},
+    primitives::{Epoch, Root, Slot},
+};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("refusing to sign a second block at slot {0}")]
+    DoubleBlockProposal(Slot),
+    #[error("refusing to sign a block at slot {slot} below the lowest signed slot {watermark}")]
+    BlockSlotBelowWatermark { slot: Slot, watermark: Slot },
+    #[error("refusing to sign a second attestation with target epoch {0}")]
+    DoubleVote(Epoch),
+    #[error("attestation source epoch {source_epoch} is after its target epoch {target_epoch}")]
+    SourceAfterTarget { source_epoch: Epoch, target_epoch: Epoch },
+    #[error("refusing to sign an attestation with source epoch {source_epoch} below the lowest signed source epoch {watermark}")]
+    SourceBelowWatermark { source_epoch: Epoch, watermark: Epoch },
+    #[error("refusing to sign an attestation with target epoch {target_epoch} below the lowest signed target epoch {watermark}")]
+    TargetBelowWatermark { target_epoch: Epoch, watermark: Epoch },
+    #[error("refusing to sign an attestation from epoch {source_epoch} to epoch {target_epoch} that surrounds a previously signed attestation")]
+    SurroundingVote { source_epoch: Epoch, target_epoch: Epoch },
+    #[error("refusing to sign an attestation from epoch {source_epoch} to epoch {target_epoch} that is surrounded by a previously signed attestation")]
+    SurroundedVote { source_epoch: Epoch, target_epoch: Epoch },
+    #[error("genesis validators root {provided:?} does not match the expected root {expected:?}")]
+    GenesisValidatorsRootMismatch { expected: Root, provided: Root },
+    #[error("unsupported interchange format version {0}")]
+    UnsupportedInterchangeVersion(u64),
+    #[error("{0}")]
+    Crypto(#[from] crate::crypto::Error),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SignedAttestationRecord {
+    pub source_epoch: Epoch,
+    // records imported from an interchange file may not carry a signing root
+    pub signing_root: Option<Root>,
+}
+
+/// The signing history of a single validator.
+///
+/// Only the signed blocks and attestations are persisted; the spans used to detect surround
+/// votes are rebuilt from the attestations when a history is deserialized.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "SignedRecords")]
+pub struct ValidatorHistory {
+    /// Signing roots of signed blocks, keyed by slot.
+    pub blocks: BTreeMap<Slot, Option<Root>>,
+    /// Signed attestations, keyed by target epoch.
+    pub attestations: BTreeMap<Epoch, SignedAttestationRecord>,
+    // `min_spans[e]` is the minimum of `target - e` over signed attestations with `source > e`
+    #[serde(skip)]
+    min_spans: BTreeMap<Epoch, u64>,
+    // `max_spans[e]` is the maximum of `target - e` over signed attestations with `source < e`
+    #[serde(skip)]
+    max_spans: BTreeMap<Epoch, u64>,
+}
+
+// The persisted part of a `ValidatorHistory`.
+#[derive(serde::Deserialize)]
+struct SignedRecords {
+    blocks: BTreeMap<Slot, Option<Root>>,
+    attestations: BTreeMap<Epoch, SignedAttestationRecord>,
+}
+
+impl From<SignedRecords> for ValidatorHistory {
+    fn from(records: SignedRecords) -> Self {
+        let mut history = Self {
+            blocks: records.blocks,
+            attestations: records.attestations,
+            ..Default::default()
+        };
+        history.rebuild_spans();
+        history
+    }
+}
+
+impl ValidatorHistory {
+    pub fn lowest_signed_slot(&self) -> Option<Slot> {
+        self.blocks.keys().next().copied()
+    }
+
+    pub fn lowest_signed_source_epoch(&self) -> Option<Epoch> {
+        self.attestations.values().map(|record| record.source_epoch).min()
+    }
+
+    pub fn lowest_signed_target_epoch(&self) -> Option<Epoch> {
+        self.attestations.keys().next().copied()
+    }
+
+    /// Record a block proposal at `slot` if it is safe to sign.
+    /// Returns `false` if this is a repeat of a previously signed block.
+    pub fn insert_block_proposal(&mut self, slot: Slot, signing_root: Root) -> Result<bool, Error> {
+        if let Some(existing) = self.blocks.get(&slot) {
+            if existing.as_ref() == Some(&signing_root) {
+                return Ok(false)
+            }
+            return Err(Error::DoubleBlockProposal(slot))
+        }
+        if let Some(watermark) = self.lowest_signed_slot() {
+            if slot < watermark {
+                return Err(Error::BlockSlotBelowWatermark { slot, watermark })
+            }
+        }
+        self.blocks.insert(slot, Some(signing_root));
+        Ok(true)
+    }
+
+    /// Record an attestation from `source_epoch` to `target_epoch` if it is safe to sign.
+    /// Returns `false` if this is a repeat of a previously signed attestation.
+    pub fn insert_attestation(
+        &mut self,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: Root,
+    ) -> Result<bool, Error> {
+        if source_epoch > target_epoch {
+            return Err(Error::SourceAfterTarget { source_epoch, target_epoch })
+        }
+        if let Some(existing) = self.attestations.get(&target_epoch) {
+            if existing.source_epoch == source_epoch &&
+                existing.signing_root.as_ref() == Some(&signing_root)
+            {
+                return Ok(false)
+            }
+            return Err(Error::DoubleVote(target_epoch))
+        }
+        if let Some(watermark) = self.lowest_signed_target_epoch() {
+            if target_epoch < watermark {
+                return Err(Error::TargetBelowWatermark { target_epoch, watermark })
+            }
+        }
+        if let Some(watermark) = self.lowest_signed_source_epoch() {
+            if source_epoch < watermark {
+                return Err(Error::SourceBelowWatermark { source_epoch, watermark })
+            }
+        }
+
+        let distance = target_epoch - source_epoch;
+        if matches!(self.min_spans.get(&source_epoch), Some(&span) if span < distance) {
+            return Err(Error::SurroundingVote { source_epoch, target_epoch })
+        }
+        if matches!(self.max_spans.get(&source_epoch), Some(&span) if span > distance) {
+            return Err(Error::SurroundedVote { source_epoch, target_epoch })
+        }
+
+        self.attestations.insert(
+            target_epoch,
+            SignedAttestationRecord { source_epoch, signing_root: Some(signing_root) },
+        );
+        let lower_bound = self.lowest_signed_source_epoch().unwrap_or(source_epoch);
+        self.update_spans(source_epoch, target_epoch, lower_bound);
+        Ok(true)
+    }
+
+    // Merge records from an interchange file, keeping any existing record at the same slot or
+    // target epoch.
+    fn merge(&mut self, data: &InterchangeData) -> Result<(), Error> {
+        for block in &data.signed_blocks {
+            self.blocks.entry(block.slot).or_insert(block.signing_root);
+        }
+        for attestation in &data.signed_attestations {
+            let source_epoch = attestation.source_epoch;
+            let target_epoch = attestation.target_epoch;
+            if source_epoch > target_epoch {
+                return Err(Error::SourceAfterTarget { source_epoch, target_epoch })
+            }
+            self.attestations.entry(target_epoch).or_insert(SignedAttestationRecord {
+                source_epoch,
+                signing_root: attestation.signing_root,
+            });
+        }
+        // spans are only tracked down to the lowest source epoch, which may have moved
+        self.rebuild_spans();
+        Ok(())
+    }
+
+    fn rebuild_spans(&mut self) {
+        self.min_spans.clear();
+        self.max_spans.clear();
+        let Some(lower_bound) = self.lowest_signed_source_epoch() else { return };
+        let attestations = self
+            .attestations
+            .iter()
+            .map(|(target_epoch, record)| (record.source_epoch, *target_epoch))
+            .collect::<Vec<_>>();
+        for (source_epoch, target_epoch) in attestations {
+            self.update_spans(source_epoch, target_epoch, lower_bound);
+        }
+    }
+
+    // Spans are monotonic moving away from `source_epoch`, so each walk can stop at the first
+    // epoch an earlier attestation already covers.
+    fn update_spans(&mut self, source_epoch: Epoch, target_epoch: Epoch, lower_bound: Epoch) {
+        for epoch in (lower_bound..source_epoch).rev() {
+            let span = target_epoch - epoch;
+            match self.min_spans.get(&epoch) {
+                Some(&existing) if existing <= span => break,
+                _ => {
+                    self.min_spans.insert(epoch, span);
+                }
+            }
+        }
+        for epoch in source_epoch + 1..target_epoch {
+            let span = target_epoch - epoch;
+            match self.max_spans.get(&epoch) {
+                Some(&existing) if existing >= span => break,
+                _ => {
+                    self.max_spans.insert(epoch, span);
+                }
+            }
+        }
+    }
+}
+
+/// A slashing protection database for the validators of the chain identified by
+/// `genesis_validators_root`.
+#[derive(Debug)]
+pub struct SlashingProtection<S> {
+    genesis_validators_root: Root,
+    store: S,
+}
+
+impl<S: Store> SlashingProtection<S> {
+    pub fn new(mut store: S, genesis_validators_root: Root) -> Result<Self, Error> {
+        match store.genesis_validators_root()? {
+            Some(expected) if expected != genesis_validators_root => {
+                return Err(Error::GenesisValidatorsRootMismatch {
+                    expected,
+                    provided: genesis_validators_root,
+                })
+            }
+            Some(_) => {}
+            None => store.set_genesis_validators_root(genesis_validators_root)?,
+        }
+        Ok(Self { genesis_validators_root, store })
+    }
+
+    pub fn genesis_validators_root(&self) -> Root {
+        self.genesis_validators_root
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    pub fn history(&self, public_key: &PublicKey) -> Result<Option<ValidatorHistory>, Error> {
+        self.store.load(public_key)
+    }
+
+    /// Check that `public_key` can safely sign `header` with the given `signing_root` and record
+    /// it if so. Repeat requests for an already signed block succeed.
+    pub fn check_and_insert_block_proposal(
+        &mut self,
+        public_key: &PublicKey,
+        header: &BeaconBlockHeader,
+        signing_root: Root,
+    ) -> Result<(), Error> {
+        let mut history = self.store.load(public_key)?.unwrap_or_default();
+        if history.insert_block_proposal(header.slot, signing_root)? {
+            self.store.save(public_key, &history)?;
+        }
+        Ok(())
+    }
+
+    /// Check that `public_key` can safely sign `attestation_data` with the given `signing_root`
+    /// and record it if so. Repeat requests for an already signed attestation succeed.
+    pub fn check_and_insert_attestation(
+        &mut self,
+        public_key: &PublicKey,
+        attestation_data: &AttestationData,
+        signing_root: Root,
+    ) -> Result<(), Error> {
+        let mut history = self.store.load(public_key)?.unwrap_or_default();
+        let source_epoch = attestation_data.source.epoch;
+        let target_epoch = attestation_data.target.epoch;
+        if history.insert_attestation(source_epoch, target_epoch, signing_root)? {
+            self.store.save(public_key, &history)?;
+        }
+        Ok(())
+    }
+
+    pub fn import_interchange(&mut self, interchange: &Interchange) -> Result<(), Error> {
+        let metadata = &interchange.metadata;
+        if metadata.interchange_format_version != INTERCHANGE_FORMAT_VERSION {
+            return Err(Error::UnsupportedInterchangeVersion(metadata.interchange_format_version))
+        }
+        if metadata.genesis_validators_root != self.genesis_validators_root {
+            return Err(Error::GenesisValidatorsRootMismatch {
+                expected: self.genesis_validators_root,
+                provided: metadata.genesis_validators_root,
+            })
+        }
+        for data in &interchange.data {
+            let mut history = self.store.load(&data.public_key)?.unwrap_or_default();
+            history.merge(data)?;
+            self.store.save(&data.public_key, &history)?;
+        }
+        Ok(())
+    }
+
+    pub fn export_interchange(&self) -> Result<Interchange, Error> {
+        let mut data = vec![];
+        for public_key in self.store.public_keys()? {
+            let Some(history) = self.store.load(&public_key)? else { continue };
+            data.push(InterchangeData::from_history(public_key, &history));
+        }
+        Ok(Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root: self.genesis_validators_root,
+            },
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phase0::Checkpoint;
+
+    fn root(byte: u8) -> Root {
+        Root::try_from([byte; 32].as_ref()).unwrap()
+    }
+
+    fn attestation(source: Epoch, target: Epoch) -> AttestationData {
+        AttestationData {
+            source: Checkpoint { epoch: source, ..Default::default() },
+            target: Checkpoint { epoch: target, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_block_proposals() {
+        let mut db = SlashingProtection::new(MemoryStore::default(), root(0)).unwrap();
+        let public_key = PublicKey::default();
+        let header = BeaconBlockHeader { slot: 10, ..Default::default() };
+
+        db.check_and_insert_block_proposal(&public_key, &header, root(1)).unwrap();
+        db.check_and_insert_block_proposal(&public_key, &header, root(1)).unwrap();
+        assert!(matches!(
+            db.check_and_insert_block_proposal(&public_key, &header, root(2)),
+            Err(Error::DoubleBlockProposal(10))
+        ));
+        let header = BeaconBlockHeader { slot: 9, ..Default::default() };
+        assert!(matches!(
+            db.check_and_insert_block_proposal(&public_key, &header, root(3)),
+            Err(Error::BlockSlotBelowWatermark { slot: 9, watermark: 10 })
+        ));
+        let header = BeaconBlockHeader { slot: 11, ..Default::default() };
+        db.check_and_insert_block_proposal(&public_key, &header, root(4)).unwrap();
+    }
+
+    #[test]
+    fn test_attestations() {
+        let mut db = SlashingProtection::new(MemoryStore::default(), root(0)).unwrap();
+        let public_key = PublicKey::default();
+
+        db.check_and_insert_attestation(&public_key, &attestation(2, 3), root(1)).unwrap();
+        db.check_and_insert_attestation(&public_key, &attestation(2, 3), root(1)).unwrap();
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(2, 3), root(2)),
+            Err(Error::DoubleVote(3))
+        ));
+        db.check_and_insert_attestation(&public_key, &attestation(5, 10), root(3)).unwrap();
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(6, 9), root(4)),
+            Err(Error::SurroundedVote { source_epoch: 6, target_epoch: 9 })
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(4, 11), root(5)),
+            Err(Error::SurroundingVote { source_epoch: 4, target_epoch: 11 })
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(1, 4), root(6)),
+            Err(Error::SourceBelowWatermark { source_epoch: 1, watermark: 2 })
+        ));
+        db.check_and_insert_attestation(&public_key, &attestation(3, 4), root(7)).unwrap();
+        db.check_and_insert_attestation(&public_key, &attestation(10, 11), root(8)).unwrap();
+    }
+
+    #[test]
+    fn test_interchange_roundtrip() {
+        let input = r#"{
+            "metadata": {
+                "interchange_format_version": "5",
+                "genesis_validators_root": "0x04700007fabc8282644aed6d1c7c9e21d38a03a0c4ba193f3afe428824b3a673"
+            },
+            "data": [
+                {
+                    "pubkey": "0xb845089a1457f811bfc000588fbb4e713669be8ce060ea6be3c6ece09afc3794106c91ca73acda5e5457122d58723bed",
+                    "signed_blocks": [
+                        {
+                            "slot": "81952",
+                            "signing_root": "0x4ff6f743a43f3b4f95350831aeaf0a122a1a392922c45d804280284a69eb850b"
+                        },
+                        {
+                            "slot": "81951"
+                        }
+                    ],
+                    "signed_attestations": [
+                        {
+                            "source_epoch": "2290",
+                            "target_epoch": "3007",
+                            "signing_root": "0x587d6a4f59a58fe24f406e0502413e77fe1babddee641fda30034ed37ecc884d"
+                        },
+                        {
+                            "source_epoch": "2290",
+                            "target_epoch": "3008"
+                        }
+                    ]
+                }
+            ]
+        }"#;
+        let interchange: Interchange = serde_json::from_str(input).unwrap();
+        let genesis_validators_root = interchange.metadata.genesis_validators_root;
+        let public_key = interchange.data[0].public_key.clone();
+
+        let mut db = SlashingProtection::new(MemoryStore::default(), root(0)).unwrap();
+        assert!(matches!(
+            db.import_interchange(&interchange),
+            Err(Error::GenesisValidatorsRootMismatch { .. })
+        ));
+
+        let mut db =
+            SlashingProtection::new(MemoryStore::default(), genesis_validators_root).unwrap();
+        db.import_interchange(&interchange).unwrap();
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(2289, 3009), root(1)),
+            Err(Error::SourceBelowWatermark { .. })
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(2291, 3007), root(1)),
+            Err(Error::DoubleVote(3007))
+        ));
+        let header = BeaconBlockHeader { slot: 81951, ..Default::default() };
+        assert!(matches!(
+            db.check_and_insert_block_proposal(&public_key, &header, root(1)),
+            Err(Error::DoubleBlockProposal(81951))
+        ));
+        db.check_and_insert_attestation(&public_key, &attestation(2290, 3009), root(1)).unwrap();
+
+        let exported = db.export_interchange().unwrap();
+        let mut expected_blocks = interchange.data[0].signed_blocks.clone();
+        expected_blocks.sort_by_key(|block| block.slot);
+        assert_eq!(exported.data[0].signed_blocks, expected_blocks);
+        assert_eq!(exported.data[0].signed_attestations.len(), 3);
+        let recovered: Interchange =
+            serde_json::from_str(&serde_json::to_string(&exported).unwrap()).unwrap();
+        assert_eq!(recovered, exported);
+    }
+
+    #[test]
+    fn test_file_store() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("slashing-protection");
+        let public_key = PublicKey::default();
+
+        let store = FileStore::new(&path).unwrap();
+        let mut db = SlashingProtection::new(store, root(0)).unwrap();
+        db.check_and_insert_attestation(&public_key, &attestation(2, 3), root(1)).unwrap();
+        db.check_and_insert_attestation(&public_key, &attestation(5, 10), root(2)).unwrap();
+
+        let store = FileStore::new(&path).unwrap();
+        assert!(matches!(
+            SlashingProtection::new(store, root(1)),
+            Err(Error::GenesisValidatorsRootMismatch { .. })
+        ));
+        // only the signed records are persisted
+        let contents =
+            std::fs::read_to_string(path.join(format!("0x{}.json", hex::encode(&public_key[..]))))
+                .unwrap();
+        assert!(!contents.contains("spans"));
+
+        let store = FileStore::new(&path).unwrap();
+        let mut db = SlashingProtection::new(store, root(0)).unwrap();
+        assert_eq!(db.store().public_keys().unwrap(), vec![public_key.clone()]);
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(6, 9), root(3)),
+            Err(Error::SurroundedVote { .. })
+        ));
+        assert!(matches!(
+            db.check_and_insert_attestation(&public_key, &attestation(4, 11), root(4)),
+            Err(Error::SurroundingVote { .. })
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    crypto::{self, PublicKey},
+    primitives::Root,
+    slashing_protection::{Error, ValidatorHistory},
+};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+const GENESIS_VALIDATORS_ROOT_FILE: &str = "genesis_validators_root.json";
+
+/// Persistence for slashing protection records.
+pub trait Store {
+    fn genesis_validators_root(&self) -> Result<Option<Root>, Error>;
+
+    fn set_genesis_validators_root(&mut self, genesis_validators_root: Root) -> Result<(), Error>;
+
+    fn public_keys(&self) -> Result<Vec<PublicKey>, Error>;
+
+    fn load(&self, public_key: &PublicKey) -> Result<Option<ValidatorHistory>, Error>;
+
+    fn save(&mut self, public_key: &PublicKey, history: &ValidatorHistory) -> Result<(), Error>;
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStore {
+    genesis_validators_root: Option<Root>,
+    histories: HashMap<PublicKey, ValidatorHistory>,
+}
+
+impl Store for MemoryStore {
+    fn genesis_validators_root(&self) -> Result<Option<Root>, Error> {
+        Ok(self.genesis_validators_root)
+    }
+
+    fn set_genesis_validators_root(&mut self, genesis_validators_root: Root) -> Result<(), Error> {
+        self.genesis_validators_root = Some(genesis_validators_root);
+        Ok(())
+    }
+
+    fn public_keys(&self) -> Result<Vec<PublicKey>, Error> {
+        Ok(self.histories.keys().cloned().collect())
+    }
+
+    fn load(&self, public_key: &PublicKey) -> Result<Option<ValidatorHistory>, Error> {
+        Ok(self.histories.get(public_key).cloned())
+    }
+
+    fn save(&mut self, public_key: &PublicKey, history: &ValidatorHistory) -> Result<(), Error> {
+        self.histories.insert(public_key.clone(), history.clone());
+        Ok(())
+    }
+}
+
+/// Keeps the history of each validator as a JSON file named by its public key in a directory.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    fn history_path(&self, public_key: &PublicKey) -> PathBuf {
+        self.path.join(format!("0x{}.json", hex::encode(&public_key[..])))
+    }
+
+    fn read<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, Error> {
+        match fs::read(path) {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // Write to a temporary file first so that a crash never leaves a partially written record,
+    // then sync the directory so that the rename is durable before any signature is released.
+    fn write<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), Error> {
+        let temporary_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&temporary_path)?;
+        serde_json::to_writer(&mut file, value)?;
+        file.flush()?;
+        file.sync_all()?;
+        fs::rename(temporary_path, path)?;
+        // NOTE: directories can only be opened for syncing on unix platforms
+        #[cfg(unix)]
+        if let Some(parent) = path.parent() {
+            fs::File::open(parent)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+impl Store for FileStore {
+    fn genesis_validators_root(&self) -> Result<Option<Root>, Error> {
+        Self::read(&self.path.join(GENESIS_VALIDATORS_ROOT_FILE))
+    }
+
+    fn set_genesis_validators_root(&mut self, genesis_validators_root: Root) -> Result<(), Error> {
+        Self::write(&self.path.join(GENESIS_VALIDATORS_ROOT_FILE), &genesis_validators_root)
+    }
+
+    fn public_keys(&self) -> Result<Vec<PublicKey>, Error> {
+        let mut public_keys = vec![];
+        for entry in fs::read_dir(&self.path)? {
+            let file_name = entry?.file_name();
+            let Some(encoding) = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix("0x"))
+                .and_then(|name| name.strip_suffix(".json"))
+            else {
+                continue
+            };
+            let bytes = hex::decode(encoding).map_err(crypto::Error::from)?;
+            public_keys.push(PublicKey::try_from(bytes.as_ref())?);
+        }
+        Ok(public_keys)
+    }
+
+    fn load(&self, public_key: &PublicKey) -> Result<Option<ValidatorHistory>, Error> {
+        Self::read(&self.history_path(public_key))
+    }
+
+    fn save(&mut self, public_key: &PublicKey, history: &ValidatorHistory) -> Result<(), Error> {
+        Self::write(&self.history_path(public_key), history)
+    }
+}
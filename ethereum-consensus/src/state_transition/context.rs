@@ -5,9 +5,33 @@ use crate::{
     deneb,
     networks::Network,
     phase0,
-    primitives::{Epoch, ExecutionAddress, Gwei, Hash32, Slot, Version, U256},
+    primitives::{Epoch, ExecutionAddress, Gwei, Hash32, Slot, Version, GENESIS_EPOCH, U256},
+    ssz::prelude::{Deserialize, SimpleSerializeError},
+    state_transition::ForkSchedule,
     Error, Fork,
 };
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// A preset value that differs from the value a type was instantiated with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetValueMismatch {
+    pub name: &'static str,
+    pub expected: u64,
+    pub provided: u64,
+}
+
+impl fmt::Display for PresetValueMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is {} but expected {}", self.name, self.provided, self.expected)
+    }
+}
 
 #[derive(Debug, Default, Clone, serde::Deserialize)]
 pub struct Context {
@@ -74,6 +98,7 @@ pub struct Context {
     pub max_blobs_per_block: usize,
 
     // config
+    pub preset_base: String,
     pub name: Network,
 
     pub terminal_total_difficulty: U256,
@@ -112,13 +137,75 @@ pub struct Context {
     pub deposit_chain_id: usize,
     pub deposit_network_id: usize,
     pub deposit_contract_address: ExecutionAddress,
+
+    // NOTE: used for networks without a well-known genesis time, e.g. devnets;
+    // see `with_genesis_time`
+    pub custom_genesis_time: Option<u64>,
 }
 
 impl Context {
     #[cfg(feature = "serde")]
-    pub fn try_from_file<P: AsRef<std::path::Path>>(config_file: P) -> Result<Self, Error> {
-        let mut file = std::fs::File::open(config_file)?;
+    pub fn try_from_file<P: AsRef<Path>>(config_file: P) -> Result<Self, Error> {
+        let mut file = File::open(config_file)?;
+        let config: Config = serde_yaml::from_reader(&mut file)?;
+        Self::try_from_config(&config)
+    }
+
+    /// Loads a `Context` from a network config directory containing a `config.yaml`.
+    ///
+    /// Presets are read from `{fork}.yaml` files under either `presets/{preset_base}/` or
+    /// `presets/` in `config_dir`. Any values they provide override those of the compile-time
+    /// preset named by `preset_base`; if there is no such preset then each file must be complete.
+    ///
+    /// NOTE: callers should verify the result with `check_type_level_presets` before using it
+    /// with the types of a given preset.
+    #[cfg(feature = "serde")]
+    pub fn try_from_dir<P: AsRef<Path>>(config_dir: P) -> Result<Self, Error> {
+        let config_dir = config_dir.as_ref();
+        let mut file = File::open(config_dir.join("config.yaml"))?;
         let config: Config = serde_yaml::from_reader(&mut file)?;
+        let base = Self::try_from_config(&config).ok();
+
+        let preset_dirs =
+            [config_dir.join("presets").join(&config.preset_base), config_dir.join("presets")];
+        let load = |fork| find_preset_file(&preset_dirs, fork);
+        let preset_base = &config.preset_base;
+        let phase0_preset =
+            load_preset(load(Fork::Phase0), base.as_ref().map(Self::phase0_preset), preset_base)?;
+        let altair_preset =
+            load_preset(load(Fork::Altair), base.as_ref().map(Self::altair_preset), preset_base)?;
+        let bellatrix_preset = load_preset(
+            load(Fork::Bellatrix),
+            base.as_ref().map(Self::bellatrix_preset),
+            preset_base,
+        )?;
+        let capella_preset =
+            load_preset(load(Fork::Capella), base.as_ref().map(Self::capella_preset), preset_base)?;
+        let deneb_preset =
+            load_preset(load(Fork::Deneb), base.as_ref().map(Self::deneb_preset), preset_base)?;
+
+        let context = Self::from(
+            &phase0_preset,
+            &altair_preset,
+            &bellatrix_preset,
+            &capella_preset,
+            &deneb_preset,
+            &config,
+        );
+
+        let genesis_state_path = config_dir.join("genesis.ssz");
+        if !genesis_state_path.exists() {
+            return Ok(context)
+        }
+        // NOTE: only the leading `genesis_time` is needed from the (potentially large) state
+        let mut encoding = [0u8; 8];
+        File::open(genesis_state_path)?.read_exact(&mut encoding)?;
+        context.with_genesis_state_ssz(&encoding)
+    }
+
+    /// Builds a `Context` from `config`, using the compile-time preset named by its `preset_base`.
+    #[cfg(feature = "serde")]
+    pub fn try_from_config(config: &Config) -> Result<Self, Error> {
         let context = match config.preset_base.as_ref() {
             "mainnet" => {
                 let phase0_preset = &phase0::mainnet::PRESET;
@@ -132,7 +219,7 @@ impl Context {
                     bellatrix_preset,
                     capella_preset,
                     deneb_preset,
-                    &config,
+                    config,
                 )
             }
             "minimal" => {
@@ -147,7 +234,22 @@ impl Context {
                     bellatrix_preset,
                     capella_preset,
                     deneb_preset,
-                    &config,
+                    config,
+                )
+            }
+            "gnosis" => {
+                let phase0_preset = &phase0::gnosis::PRESET;
+                let altair_preset = &altair::gnosis::PRESET;
+                let bellatrix_preset = &bellatrix::gnosis::PRESET;
+                let capella_preset = &capella::gnosis::PRESET;
+                let deneb_preset = &deneb::gnosis::PRESET;
+                Self::from(
+                    phase0_preset,
+                    altair_preset,
+                    bellatrix_preset,
+                    capella_preset,
+                    deneb_preset,
+                    config,
                 )
             }
             other => return Err(Error::UnknownPreset(other.to_string())),
@@ -155,6 +257,45 @@ impl Context {
         Ok(context)
     }
 
+    /// Builds a `Context` from the flat map of values served by a beacon node at
+    /// `/eth/v1/config/spec`.
+    ///
+    /// Any preset values in `spec` are checked against the compile-time preset named by
+    /// `PRESET_BASE`; presets for forks the remote node does not serve are skipped.
+    #[cfg(feature = "serde")]
+    pub fn try_from_spec(spec: &HashMap<String, String>) -> Result<Self, Error> {
+        // NOTE: all values are served as strings so recover integers where possible
+        // to match the types in `Config` and the presets
+        let values = spec
+            .iter()
+            .map(|(key, value)| {
+                let value = match value.parse::<u64>() {
+                    Ok(value) => serde_json::Value::from(value),
+                    Err(_) => serde_json::Value::from(value.as_str()),
+                };
+                (key.clone(), value)
+            })
+            .collect::<serde_json::Map<_, _>>();
+        let values = serde_json::Value::Object(values);
+
+        let config: Config = serde_json::from_value(values.clone())?;
+        let context = Self::try_from_config(&config)?;
+
+        for fork in Fork::ALL {
+            let matches = match fork {
+                Fork::Phase0 => remote_preset_matches(&values, &context.phase0_preset()),
+                Fork::Altair => remote_preset_matches(&values, &context.altair_preset()),
+                Fork::Bellatrix => remote_preset_matches(&values, &context.bellatrix_preset()),
+                Fork::Capella => remote_preset_matches(&values, &context.capella_preset()),
+                Fork::Deneb => remote_preset_matches(&values, &context.deneb_preset()),
+            };
+            if !matches {
+                return Err(Error::PresetMismatch { preset_base: config.preset_base, fork })
+            }
+        }
+        Ok(context)
+    }
+
     pub fn from(
         phase0_preset: &phase0::Preset,
         altair_preset: &altair::Preset,
@@ -228,6 +369,7 @@ impl Context {
             max_blob_commitments_per_block: deneb_preset.max_blob_commitments_per_block,
             max_blobs_per_block: deneb_preset.max_blobs_per_block,
             // config
+            preset_base: config.preset_base.clone(),
             name: config.name.clone(),
             terminal_total_difficulty: config.terminal_total_difficulty,
             terminal_block_hash: config.terminal_block_hash.clone(),
@@ -259,6 +401,8 @@ impl Context {
             deposit_chain_id: config.deposit_chain_id,
             deposit_network_id: config.deposit_network_id,
             deposit_contract_address: config.deposit_contract_address.clone(),
+
+            custom_genesis_time: None,
         }
     }
 
@@ -347,6 +491,310 @@ impl Context {
         )
     }
 
+    pub fn for_gnosis() -> Self {
+        let config = &configs::gnosis::config();
+        let phase0_preset = &phase0::gnosis::PRESET;
+        let altair_preset = &altair::gnosis::PRESET;
+        let bellatrix_preset = &bellatrix::gnosis::PRESET;
+        let capella_preset = &capella::gnosis::PRESET;
+        let deneb_preset = &deneb::gnosis::PRESET;
+        Self::from(
+            phase0_preset,
+            altair_preset,
+            bellatrix_preset,
+            capella_preset,
+            deneb_preset,
+            config,
+        )
+    }
+
+    pub fn for_chiado() -> Self {
+        let config = &configs::chiado::config();
+        let phase0_preset = &phase0::gnosis::PRESET;
+        let altair_preset = &altair::gnosis::PRESET;
+        let bellatrix_preset = &bellatrix::gnosis::PRESET;
+        let capella_preset = &capella::gnosis::PRESET;
+        let deneb_preset = &deneb::gnosis::PRESET;
+        Self::from(
+            phase0_preset,
+            altair_preset,
+            bellatrix_preset,
+            capella_preset,
+            deneb_preset,
+            config,
+        )
+    }
+
+    pub fn phase0_preset(&self) -> phase0::Preset {
+        phase0::Preset {
+            max_committees_per_slot: self.max_committees_per_slot,
+            target_committee_size: self.target_committee_size,
+            max_validators_per_committee: self.max_validators_per_committee,
+            shuffle_round_count: self.shuffle_round_count,
+            hysteresis_quotient: self.hysteresis_quotient,
+            hysteresis_downward_multiplier: self.hysteresis_downward_multiplier,
+            hysteresis_upward_multiplier: self.hysteresis_upward_multiplier,
+            min_deposit_amount: self.min_deposit_amount,
+            max_effective_balance: self.max_effective_balance,
+            effective_balance_increment: self.effective_balance_increment,
+            min_attestation_inclusion_delay: self.min_attestation_inclusion_delay,
+            slots_per_epoch: self.slots_per_epoch,
+            min_seed_lookahead: self.min_seed_lookahead,
+            max_seed_lookahead: self.max_seed_lookahead,
+            min_epochs_to_inactivity_penalty: self.min_epochs_to_inactivity_penalty,
+            epochs_per_eth1_voting_period: self.epochs_per_eth1_voting_period,
+            slots_per_historical_root: self.slots_per_historical_root,
+            epochs_per_historical_vector: self.epochs_per_historical_vector,
+            epochs_per_slashings_vector: self.epochs_per_slashings_vector,
+            historical_roots_limit: self.historical_roots_limit,
+            validator_registry_limit: self.validator_registry_limit,
+            base_reward_factor: self.base_reward_factor,
+            whistleblower_reward_quotient: self.whistleblower_reward_quotient,
+            proposer_reward_quotient: self.proposer_reward_quotient,
+            inactivity_penalty_quotient: self.inactivity_penalty_quotient,
+            min_slashing_penalty_quotient: self.min_slashing_penalty_quotient,
+            proportional_slashing_multiplier: self.proportional_slashing_multiplier,
+            max_proposer_slashings: self.max_proposer_slashings,
+            max_attester_slashings: self.max_attester_slashings,
+            max_attestations: self.max_attestations,
+            max_deposits: self.max_deposits,
+            max_voluntary_exits: self.max_voluntary_exits,
+        }
+    }
+
+    pub fn altair_preset(&self) -> altair::Preset {
+        altair::Preset {
+            inactivity_penalty_quotient_altair: self.inactivity_penalty_quotient_altair,
+            min_slashing_penalty_quotient_altair: self.min_slashing_penalty_quotient_altair,
+            proportional_slashing_multiplier_altair: self.proportional_slashing_multiplier_altair,
+            sync_committee_size: self.sync_committee_size,
+            epochs_per_sync_committee_period: self.epochs_per_sync_committee_period,
+            min_sync_committee_participants: self.min_sync_committee_participants,
+            update_timeout: self.update_timeout,
+        }
+    }
+
+    pub fn bellatrix_preset(&self) -> bellatrix::Preset {
+        bellatrix::Preset {
+            inactivity_penalty_quotient_bellatrix: self.inactivity_penalty_quotient_bellatrix,
+            min_slashing_penalty_quotient_bellatrix: self.min_slashing_penalty_quotient_bellatrix,
+            proportional_slashing_multiplier_bellatrix: self
+                .proportional_slashing_multiplier_bellatrix,
+            max_bytes_per_transaction: self.max_bytes_per_transaction,
+            max_transactions_per_payload: self.max_transactions_per_payload,
+            bytes_per_logs_bloom: self.bytes_per_logs_bloom,
+            max_extra_data_bytes: self.max_extra_data_bytes,
+        }
+    }
+
+    pub fn capella_preset(&self) -> capella::Preset {
+        capella::Preset {
+            max_bls_to_execution_changes: self.max_bls_to_execution_changes,
+            max_withdrawals_per_payload: self.max_withdrawals_per_payload,
+            max_validators_per_withdrawals_sweep: self.max_validators_per_withdrawals_sweep,
+        }
+    }
+
+    pub fn deneb_preset(&self) -> deneb::Preset {
+        deneb::Preset {
+            field_elements_per_blob: self.field_elements_per_blob,
+            max_blob_commitments_per_block: self.max_blob_commitments_per_block,
+            max_blobs_per_block: self.max_blobs_per_block,
+        }
+    }
+
+    /// Verifies the preset values of this `Context` that are fixed at compile time (through the
+    /// const generics of the SSZ types) match the given presets, reporting every mismatch.
+    ///
+    /// Values that are only read at runtime (e.g. `SHUFFLE_ROUND_COUNT`) may differ freely.
+    pub fn check_type_level_presets(
+        &self,
+        phase0_preset: &phase0::Preset,
+        altair_preset: &altair::Preset,
+        bellatrix_preset: &bellatrix::Preset,
+        capella_preset: &capella::Preset,
+        deneb_preset: &deneb::Preset,
+    ) -> Result<(), Error> {
+        let mut mismatches = vec![];
+        let mut check = |name, expected: u64, provided: u64| {
+            if expected != provided {
+                mismatches.push(PresetValueMismatch { name, expected, provided });
+            }
+        };
+        // phase0
+        // NOTE: `SLOTS_PER_EPOCH` and `EPOCHS_PER_ETH1_VOTING_PERIOD` determine the bounds of
+        // pending attestations and eth1 data votes in the `BeaconState`
+        check("SLOTS_PER_EPOCH", phase0_preset.slots_per_epoch, self.slots_per_epoch);
+        check(
+            "EPOCHS_PER_ETH1_VOTING_PERIOD",
+            phase0_preset.epochs_per_eth1_voting_period,
+            self.epochs_per_eth1_voting_period,
+        );
+        check(
+            "MAX_VALIDATORS_PER_COMMITTEE",
+            phase0_preset.max_validators_per_committee as u64,
+            self.max_validators_per_committee as u64,
+        );
+        check(
+            "SLOTS_PER_HISTORICAL_ROOT",
+            phase0_preset.slots_per_historical_root,
+            self.slots_per_historical_root,
+        );
+        check(
+            "EPOCHS_PER_HISTORICAL_VECTOR",
+            phase0_preset.epochs_per_historical_vector,
+            self.epochs_per_historical_vector,
+        );
+        check(
+            "EPOCHS_PER_SLASHINGS_VECTOR",
+            phase0_preset.epochs_per_slashings_vector,
+            self.epochs_per_slashings_vector,
+        );
+        check(
+            "HISTORICAL_ROOTS_LIMIT",
+            phase0_preset.historical_roots_limit as u64,
+            self.historical_roots_limit as u64,
+        );
+        check(
+            "VALIDATOR_REGISTRY_LIMIT",
+            phase0_preset.validator_registry_limit as u64,
+            self.validator_registry_limit as u64,
+        );
+        check(
+            "MAX_PROPOSER_SLASHINGS",
+            phase0_preset.max_proposer_slashings as u64,
+            self.max_proposer_slashings as u64,
+        );
+        check(
+            "MAX_ATTESTER_SLASHINGS",
+            phase0_preset.max_attester_slashings as u64,
+            self.max_attester_slashings as u64,
+        );
+        check(
+            "MAX_ATTESTATIONS",
+            phase0_preset.max_attestations as u64,
+            self.max_attestations as u64,
+        );
+        check("MAX_DEPOSITS", phase0_preset.max_deposits as u64, self.max_deposits as u64);
+        check(
+            "MAX_VOLUNTARY_EXITS",
+            phase0_preset.max_voluntary_exits as u64,
+            self.max_voluntary_exits as u64,
+        );
+        // altair
+        check(
+            "SYNC_COMMITTEE_SIZE",
+            altair_preset.sync_committee_size as u64,
+            self.sync_committee_size as u64,
+        );
+        // bellatrix
+        check(
+            "MAX_BYTES_PER_TRANSACTION",
+            bellatrix_preset.max_bytes_per_transaction as u64,
+            self.max_bytes_per_transaction as u64,
+        );
+        check(
+            "MAX_TRANSACTIONS_PER_PAYLOAD",
+            bellatrix_preset.max_transactions_per_payload as u64,
+            self.max_transactions_per_payload as u64,
+        );
+        check(
+            "BYTES_PER_LOGS_BLOOM",
+            bellatrix_preset.bytes_per_logs_bloom as u64,
+            self.bytes_per_logs_bloom as u64,
+        );
+        check(
+            "MAX_EXTRA_DATA_BYTES",
+            bellatrix_preset.max_extra_data_bytes as u64,
+            self.max_extra_data_bytes as u64,
+        );
+        // capella
+        check(
+            "MAX_BLS_TO_EXECUTION_CHANGES",
+            capella_preset.max_bls_to_execution_changes as u64,
+            self.max_bls_to_execution_changes as u64,
+        );
+        check(
+            "MAX_WITHDRAWALS_PER_PAYLOAD",
+            capella_preset.max_withdrawals_per_payload as u64,
+            self.max_withdrawals_per_payload as u64,
+        );
+        // deneb
+        check(
+            "FIELD_ELEMENTS_PER_BLOB",
+            deneb_preset.field_elements_per_blob as u64,
+            self.field_elements_per_blob as u64,
+        );
+        check(
+            "MAX_BLOB_COMMITMENTS_PER_BLOCK",
+            deneb_preset.max_blob_commitments_per_block as u64,
+            self.max_blob_commitments_per_block as u64,
+        );
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::IncompatiblePreset(mismatches))
+        }
+    }
+
+    /// Returns the runtime configuration of this `Context`.
+    pub fn config(&self) -> Config {
+        Config {
+            preset_base: self.preset_base.clone(),
+            name: self.name.clone(),
+            terminal_total_difficulty: self.terminal_total_difficulty,
+            terminal_block_hash: self.terminal_block_hash.clone(),
+            terminal_block_hash_activation_epoch: self.terminal_block_hash_activation_epoch,
+            min_genesis_active_validator_count: self.min_genesis_active_validator_count,
+            min_genesis_time: self.min_genesis_time,
+            genesis_fork_version: self.genesis_fork_version,
+            genesis_delay: self.genesis_delay,
+            altair_fork_version: self.altair_fork_version,
+            altair_fork_epoch: self.altair_fork_epoch,
+            bellatrix_fork_version: self.bellatrix_fork_version,
+            bellatrix_fork_epoch: self.bellatrix_fork_epoch,
+            capella_fork_version: self.capella_fork_version,
+            capella_fork_epoch: self.capella_fork_epoch,
+            deneb_fork_version: self.deneb_fork_version,
+            deneb_fork_epoch: self.deneb_fork_epoch,
+            seconds_per_slot: self.seconds_per_slot,
+            seconds_per_eth1_block: self.seconds_per_eth1_block,
+            min_validator_withdrawability_delay: self.min_validator_withdrawability_delay,
+            shard_committee_period: self.shard_committee_period,
+            eth1_follow_distance: self.eth1_follow_distance,
+            inactivity_score_bias: self.inactivity_score_bias,
+            inactivity_score_recovery_rate: self.inactivity_score_recovery_rate,
+            ejection_balance: self.ejection_balance,
+            min_per_epoch_churn_limit: self.min_per_epoch_churn_limit,
+            max_per_epoch_activation_churn_limit: self.max_per_epoch_activation_churn_limit,
+            churn_limit_quotient: self.churn_limit_quotient,
+            proposer_score_boost: self.proposer_score_boost,
+            deposit_chain_id: self.deposit_chain_id,
+            deposit_network_id: self.deposit_network_id,
+            deposit_contract_address: self.deposit_contract_address.clone(),
+        }
+    }
+
+    /// Renders the runtime configuration in the format of a `config.yaml` from `consensus-specs`.
+    #[cfg(feature = "serde")]
+    pub fn to_config_yaml(&self) -> Result<String, Error> {
+        Ok(serde_yaml::to_string(&self.config())?)
+    }
+
+    /// Renders the preset for `fork` in the format of the preset files from `consensus-specs`.
+    #[cfg(feature = "serde")]
+    pub fn to_preset_yaml(&self, fork: Fork) -> Result<String, Error> {
+        let yaml = match fork {
+            Fork::Phase0 => serde_yaml::to_string(&self.phase0_preset())?,
+            Fork::Altair => serde_yaml::to_string(&self.altair_preset())?,
+            Fork::Bellatrix => serde_yaml::to_string(&self.bellatrix_preset())?,
+            Fork::Capella => serde_yaml::to_string(&self.capella_preset())?,
+            Fork::Deneb => serde_yaml::to_string(&self.deneb_preset())?,
+        };
+        Ok(yaml)
+    }
+
     pub fn fork_for(&self, slot: Slot) -> Fork {
         let epoch = slot / self.slots_per_epoch;
         if epoch >= self.deneb_fork_epoch {
@@ -372,31 +820,288 @@ impl Context {
         }
     }
 
+    pub fn fork_epoch_for(&self, fork: Fork) -> Epoch {
+        match fork {
+            Fork::Phase0 => GENESIS_EPOCH,
+            Fork::Altair => self.altair_fork_epoch,
+            Fork::Bellatrix => self.bellatrix_fork_epoch,
+            Fork::Capella => self.capella_fork_epoch,
+            Fork::Deneb => self.deneb_fork_epoch,
+        }
+    }
+
+    pub fn fork_schedule(&self) -> ForkSchedule {
+        ForkSchedule::new(self)
+    }
+
+    /// Sets the genesis time of the network, e.g. from the `genesis_time` of a genesis
+    /// `BeaconState` or the `GenesisDetails` served by a beacon node.
+    pub fn with_genesis_time(mut self, genesis_time: u64) -> Self {
+        self.custom_genesis_time = Some(genesis_time);
+        self
+    }
+
+    /// Sets the genesis time of the network from an SSZ-encoded genesis `BeaconState`.
+    pub fn with_genesis_state_ssz(self, encoding: &[u8]) -> Result<Self, Error> {
+        let genesis_time = genesis_time_from_state_ssz(encoding)?;
+        Ok(self.with_genesis_time(genesis_time))
+    }
+
     pub fn genesis_time(&self) -> Result<u64, Error> {
+        if let Some(genesis_time) = self.custom_genesis_time {
+            return Ok(genesis_time)
+        }
         match &self.name {
             Network::Mainnet => Ok(crate::clock::MAINNET_GENESIS_TIME),
             Network::Sepolia => Ok(crate::clock::SEPOLIA_GENESIS_TIME),
             Network::Goerli => Ok(crate::clock::GOERLI_GENESIS_TIME),
             Network::Holesky => Ok(crate::clock::HOLESKY_GENESIS_TIME),
+            Network::Gnosis => Ok(crate::clock::GNOSIS_GENESIS_TIME),
+            Network::Chiado => Ok(crate::clock::CHIADO_GENESIS_TIME),
             name => Err(Error::UnknownGenesisTime(name.to_string())),
         }
     }
 
     pub fn clock(&self) -> Option<Clock<SystemTimeProvider>> {
+        if let Some(genesis_time) = self.custom_genesis_time {
+            return Some(self.clock_at(genesis_time))
+        }
         match self.name {
             Network::Mainnet => Some(clock::for_mainnet()),
             Network::Sepolia => Some(clock::for_sepolia()),
             Network::Goerli => Some(clock::for_goerli()),
             Network::Holesky => Some(clock::for_holesky()),
+            Network::Gnosis => Some(clock::for_gnosis()),
+            Network::Chiado => Some(clock::for_chiado()),
             _ => None,
         }
     }
 
     pub fn clock_at(&self, genesis_time: u64) -> Clock<SystemTimeProvider> {
-        clock::from_system_time(genesis_time, self.seconds_per_slot, self.slots_per_epoch)
+        clock::from_system_time_with_sync_committee_period(
+            genesis_time,
+            self.seconds_per_slot,
+            self.slots_per_epoch,
+            self.epochs_per_sync_committee_period,
+        )
     }
 
     pub fn network(&self) -> &Network {
         &self.name
     }
 }
+
+/// Reads the `genesis_time` from an SSZ-encoded `BeaconState` of any fork.
+///
+/// NOTE: `genesis_time` is the first field of the `BeaconState` across all forks, so only the
+/// first 8 bytes of the encoding are read.
+pub fn genesis_time_from_state_ssz(encoding: &[u8]) -> Result<u64, Error> {
+    let encoding = encoding.get(..8).unwrap_or(encoding);
+    let genesis_time = u64::deserialize(encoding).map_err(SimpleSerializeError::from)?;
+    Ok(genesis_time)
+}
+
+#[cfg(feature = "serde")]
+fn find_preset_file(preset_dirs: &[PathBuf], fork: Fork) -> Option<PathBuf> {
+    preset_dirs.iter().map(|dir| dir.join(format!("{fork}.yaml"))).find(|path| path.exists())
+}
+
+#[cfg(feature = "serde")]
+fn load_preset<P: serde::Serialize + serde::de::DeserializeOwned>(
+    preset_file: Option<PathBuf>,
+    base: Option<P>,
+    preset_base: &str,
+) -> Result<P, Error> {
+    let Some(preset_file) = preset_file else {
+        return base.ok_or_else(|| Error::UnknownPreset(preset_base.to_string()))
+    };
+    let overrides: serde_yaml::Mapping = serde_yaml::from_reader(File::open(preset_file)?)?;
+    let mut values = match base.map(serde_yaml::to_value).transpose()? {
+        Some(serde_yaml::Value::Mapping(values)) => values,
+        _ => serde_yaml::Mapping::new(),
+    };
+    for (key, value) in overrides {
+        values.insert(key, value);
+    }
+    Ok(serde_yaml::from_value(serde_yaml::Value::Mapping(values))?)
+}
+
+// NOTE: a remote node may not serve the preset for forks it does not support yet, in which case
+// none of the keys of the preset are present and there is nothing to compare
+#[cfg(feature = "serde")]
+fn remote_preset_matches<P: serde::Serialize + serde::de::DeserializeOwned + PartialEq>(
+    values: &serde_json::Value,
+    expected: &P,
+) -> bool {
+    let Ok(serde_json::Value::Object(expected_values)) = serde_json::to_value(expected) else {
+        return false
+    };
+    if !expected_values.keys().any(|key| values.get(key).is_some()) {
+        return true
+    }
+    serde_json::from_value::<P>(values.clone()).map(|preset| &preset == expected).unwrap_or(false)
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::ssz::prelude::serialize;
+
+    fn to_spec<T: serde::Serialize>(value: &T, spec: &mut HashMap<String, String>) {
+        let serde_json::Value::Object(values) = serde_json::to_value(value).unwrap() else {
+            panic!("expected an object")
+        };
+        for (key, value) in values {
+            let value = match value {
+                serde_json::Value::String(value) => value,
+                other => other.to_string(),
+            };
+            spec.insert(key, value);
+        }
+    }
+
+    #[test]
+    fn test_context_from_spec() {
+        let context = Context::for_mainnet();
+        let mut spec = HashMap::new();
+        to_spec(&context.config(), &mut spec);
+        to_spec(&context.phase0_preset(), &mut spec);
+        to_spec(&context.deneb_preset(), &mut spec);
+
+        let recovered = Context::try_from_spec(&spec).unwrap();
+        assert_eq!(recovered.preset_base, "mainnet");
+        assert_eq!(recovered.config().capella_fork_epoch, context.capella_fork_epoch);
+        assert_eq!(recovered.to_config_yaml().unwrap(), context.to_config_yaml().unwrap());
+
+        // a partially served preset does not match
+        let mut partial_spec = spec.clone();
+        partial_spec.remove("MAX_BLOBS_PER_BLOCK").unwrap();
+        assert!(matches!(
+            Context::try_from_spec(&partial_spec),
+            Err(Error::PresetMismatch { fork: Fork::Deneb, .. })
+        ));
+
+        spec.insert("SLOTS_PER_EPOCH".to_string(), "8".to_string());
+        assert!(matches!(
+            Context::try_from_spec(&spec),
+            Err(Error::PresetMismatch { fork: Fork::Phase0, .. })
+        ));
+    }
+
+    fn write_config_dir(context: &Context, config_dir: &Path) {
+        let preset_dir = config_dir.join("presets");
+        std::fs::create_dir_all(&preset_dir).unwrap();
+        std::fs::write(config_dir.join("config.yaml"), context.to_config_yaml().unwrap()).unwrap();
+        for fork in Fork::ALL {
+            let preset = context.to_preset_yaml(fork).unwrap();
+            std::fs::write(preset_dir.join(format!("{fork}.yaml")), preset).unwrap();
+        }
+    }
+
+    fn assert_same_yaml(a: &Context, b: &Context) {
+        assert_eq!(a.to_config_yaml().unwrap(), b.to_config_yaml().unwrap());
+        for fork in Fork::ALL {
+            assert_eq!(a.to_preset_yaml(fork).unwrap(), b.to_preset_yaml(fork).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_config_roundtrip() {
+        for context in [Context::for_mainnet(), Context::for_minimal(), Context::for_holesky()] {
+            let config: Config = serde_yaml::from_str(&context.to_config_yaml().unwrap()).unwrap();
+            let recovered = Context::try_from_config(&config).unwrap();
+            assert_eq!(recovered.preset_base, context.preset_base);
+            assert_same_yaml(&recovered, &context);
+        }
+
+        // a context with preset overrides keeps its `PRESET_BASE`
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source_dir = temp_dir.path().join("source");
+        let mut custom = Context::for_minimal();
+        custom.name = Network::Custom("devnet".to_string());
+        write_config_dir(&custom, &source_dir);
+        std::fs::write(source_dir.join("presets/phase0.yaml"), "SHUFFLE_ROUND_COUNT: 1\n").unwrap();
+        let custom = Context::try_from_dir(&source_dir).unwrap();
+        assert_eq!(custom.preset_base, "minimal");
+        assert_eq!(custom.shuffle_round_count, 1);
+
+        let target_dir = temp_dir.path().join("target");
+        write_config_dir(&custom, &target_dir);
+        let recovered = Context::try_from_dir(&target_dir).unwrap();
+        assert_eq!(recovered.preset_base, "minimal");
+        assert_eq!(recovered.shuffle_round_count, 1);
+        assert_same_yaml(&recovered, &custom);
+    }
+
+    #[test]
+    fn test_context_from_dir_with_custom_presets() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_dir = temp_dir.path();
+        let preset_dir = config_dir.join("presets");
+        std::fs::create_dir_all(&preset_dir).unwrap();
+        let context = Context::for_mainnet();
+        std::fs::write(config_dir.join("config.yaml"), context.to_config_yaml().unwrap()).unwrap();
+
+        std::fs::write(preset_dir.join("phase0.yaml"), "SHUFFLE_ROUND_COUNT: 10\n").unwrap();
+        let custom = Context::try_from_dir(config_dir).unwrap();
+        assert_eq!(custom.shuffle_round_count, 10);
+        assert_eq!(custom.slots_per_epoch, context.slots_per_epoch);
+        assert!(crate::state_transition::mainnet::check_compatibility(&custom).is_ok());
+
+        std::fs::write(preset_dir.join("altair.yaml"), "SYNC_COMMITTEE_SIZE: 32\n").unwrap();
+        let custom = Context::try_from_dir(config_dir).unwrap();
+        let Err(Error::IncompatiblePreset(mismatches)) =
+            crate::state_transition::mainnet::check_compatibility(&custom)
+        else {
+            panic!("expected incompatible preset")
+        };
+        assert_eq!(
+            mismatches,
+            vec![PresetValueMismatch { name: "SYNC_COMMITTEE_SIZE", expected: 512, provided: 32 }]
+        );
+    }
+
+    #[test]
+    fn test_context_from_dir_with_genesis_state() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_dir = temp_dir.path();
+        let mut context = Context::for_minimal();
+        context.name = Network::Custom("devnet".to_string());
+        std::fs::write(config_dir.join("config.yaml"), context.to_config_yaml().unwrap()).unwrap();
+
+        let custom = Context::try_from_dir(config_dir).unwrap();
+        assert!(matches!(custom.genesis_time(), Err(Error::UnknownGenesisTime(..))));
+        assert!(custom.clock().is_none());
+
+        let genesis_time = 1700000000;
+        let state = crate::phase0::minimal::BeaconState { genesis_time, ..Default::default() };
+        let encoding = serialize(&state).unwrap();
+        std::fs::write(config_dir.join("genesis.ssz"), &encoding).unwrap();
+
+        let custom = Context::try_from_dir(config_dir).unwrap();
+        assert_eq!(custom.genesis_time().unwrap(), genesis_time);
+        let clock = custom.clock().unwrap();
+        assert_eq!(clock.epoch_for(16), 2);
+        assert!(!clock.before_genesis());
+
+        assert!(genesis_time_from_state_ssz(&encoding[..4]).is_err());
+    }
+
+    #[test]
+    fn test_gnosis_context() {
+        let context = Context::for_gnosis();
+        assert_eq!(context.preset_base, "gnosis");
+        assert_eq!(context.slots_per_epoch, 16);
+        assert_eq!(context.seconds_per_slot, 5);
+        assert!(crate::state_transition::gnosis::check_compatibility(&context).is_ok());
+        assert!(crate::state_transition::mainnet::check_compatibility(&context).is_err());
+
+        let recovered: Config = serde_yaml::from_str(&context.to_config_yaml().unwrap()).unwrap();
+        assert_eq!(recovered.name, Network::Gnosis);
+        assert_eq!(Context::try_from_config(&recovered).unwrap().preset_base, "gnosis");
+
+        let chiado = Context::for_chiado();
+        assert_eq!(chiado.preset_base, "gnosis");
+        assert_eq!(chiado.genesis_time().unwrap(), crate::clock::CHIADO_GENESIS_TIME);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    phase0::{self, helpers::compute_fork_digest, networking::EnrForkId},
+    primitives::{Epoch, ForkDigest, Root, Slot, Version, FAR_FUTURE_EPOCH},
+    state_transition::{Context, Result},
+    Fork,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledFork {
+    pub fork: Fork,
+    pub version: Version,
+    pub epoch: Epoch,
+}
+
+/// The forks of a network with a known activation epoch, in activation order.
+///
+/// Forks that are not yet scheduled (i.e. with an epoch of `FAR_FUTURE_EPOCH`) are omitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkSchedule {
+    forks: Vec<ScheduledFork>,
+    slots_per_epoch: Slot,
+}
+
+impl ForkSchedule {
+    pub fn new(context: &Context) -> Self {
+        let forks = Fork::ALL
+            .into_iter()
+            .map(|fork| ScheduledFork {
+                fork,
+                version: context.fork_version_for(fork),
+                epoch: context.fork_epoch_for(fork),
+            })
+            .filter(|scheduled_fork| scheduled_fork.epoch != FAR_FUTURE_EPOCH)
+            .collect();
+        Self { forks, slots_per_epoch: context.slots_per_epoch }
+    }
+
+    pub fn forks(&self) -> &[ScheduledFork] {
+        &self.forks
+    }
+
+    /// Returns the forks that have activated as of `epoch`.
+    pub fn activated_forks(&self, epoch: Epoch) -> &[ScheduledFork] {
+        let count = self.forks.partition_point(|scheduled_fork| scheduled_fork.epoch <= epoch);
+        &self.forks[..count]
+    }
+
+    /// Returns the forks that are scheduled to activate after `epoch`.
+    pub fn scheduled_forks(&self, epoch: Epoch) -> &[ScheduledFork] {
+        let count = self.activated_forks(epoch).len();
+        &self.forks[count..]
+    }
+
+    pub fn fork_at_epoch(&self, epoch: Epoch) -> &ScheduledFork {
+        // NOTE: `Phase0` always activates at genesis so there is at least one activated fork
+        self.activated_forks(epoch).last().expect("genesis fork is always scheduled")
+    }
+
+    pub fn fork_at_slot(&self, slot: Slot) -> &ScheduledFork {
+        self.fork_at_epoch(slot / self.slots_per_epoch)
+    }
+
+    pub fn next_fork(&self, epoch: Epoch) -> Option<&ScheduledFork> {
+        self.scheduled_forks(epoch).first()
+    }
+
+    pub fn get(&self, fork: Fork) -> Option<&ScheduledFork> {
+        self.forks.iter().find(|scheduled_fork| scheduled_fork.fork == fork)
+    }
+
+    pub fn fork_for_version(&self, version: &Version) -> Option<&ScheduledFork> {
+        self.forks.iter().find(|scheduled_fork| &scheduled_fork.version == version)
+    }
+
+    pub fn fork_digest(
+        &self,
+        scheduled_fork: &ScheduledFork,
+        genesis_validators_root: Root,
+    ) -> Result<ForkDigest> {
+        compute_fork_digest(scheduled_fork.version, genesis_validators_root)
+    }
+
+    pub fn fork_for_digest(
+        &self,
+        fork_digest: &ForkDigest,
+        genesis_validators_root: Root,
+    ) -> Result<Option<&ScheduledFork>> {
+        for scheduled_fork in &self.forks {
+            if &self.fork_digest(scheduled_fork, genesis_validators_root)? == fork_digest {
+                return Ok(Some(scheduled_fork))
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the `Fork` as it would appear in a `BeaconState` at `epoch`.
+    pub fn fork_data(&self, epoch: Epoch) -> phase0::Fork {
+        let activated_forks = self.activated_forks(epoch);
+        let current = activated_forks.last().expect("genesis fork is always scheduled");
+        let previous = activated_forks.iter().rev().nth(1).unwrap_or(current);
+        phase0::Fork {
+            previous_version: previous.version,
+            current_version: current.version,
+            epoch: current.epoch,
+        }
+    }
+
+    /// Returns the `ENRForkID` to advertise at `epoch`.
+    pub fn enr_fork_id(&self, epoch: Epoch, genesis_validators_root: Root) -> Result<EnrForkId> {
+        let current = self.fork_at_epoch(epoch);
+        let fork_digest = self.fork_digest(current, genesis_validators_root)?;
+        let (next_fork_version, next_fork_epoch) = match self.next_fork(epoch) {
+            Some(next) => (next.version, next.epoch),
+            None => (current.version, FAR_FUTURE_EPOCH),
+        };
+        Ok(EnrForkId { fork_digest, next_fork_version, next_fork_epoch })
+    }
+
+    /// Returns `true` if an object from `fork` may claim to be from `slot`.
+    pub fn is_fork_for_slot(&self, fork: Fork, slot: Slot) -> bool {
+        self.fork_at_slot(slot).fork == fork
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::try_bytes_from_hex_str;
+
+    #[test]
+    fn test_mainnet_fork_schedule() {
+        let context = Context::for_mainnet();
+        let schedule = context.fork_schedule();
+        let genesis_validators_root = Root::try_from(
+            try_bytes_from_hex_str(
+                "0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95",
+            )
+            .unwrap()
+            .as_ref(),
+        )
+        .unwrap();
+
+        assert_eq!(schedule.fork_at_epoch(0).fork, Fork::Phase0);
+        assert_eq!(schedule.fork_at_epoch(74240).fork, Fork::Altair);
+        assert_eq!(schedule.fork_at_slot(194048 * 32 - 1).fork, Fork::Bellatrix);
+        assert!(schedule.is_fork_for_slot(Fork::Capella, 194048 * 32));
+        assert_eq!(schedule.next_fork(100000).unwrap().fork, Fork::Bellatrix);
+
+        let fork = schedule.fork_data(150000);
+        assert_eq!(fork.previous_version, [1, 0, 0, 0]);
+        assert_eq!(fork.current_version, [2, 0, 0, 0]);
+        assert_eq!(fork.epoch, 144896);
+
+        let enr_fork_id = schedule.enr_fork_id(200000, genesis_validators_root).unwrap();
+        assert_eq!(enr_fork_id.fork_digest, [0xbb, 0xa4, 0xda, 0x96]);
+        let capella = schedule.fork_for_digest(&enr_fork_id.fork_digest, genesis_validators_root);
+        assert_eq!(capella.unwrap().unwrap().fork, Fork::Capella);
+        assert_eq!(schedule.fork_for_version(&[2, 0, 0, 0]).unwrap().fork, Fork::Bellatrix);
+    }
+}
@@ -3,6 +3,7 @@ mod beacon_state;
 mod context;
 mod execution_engine;
 mod executor;
+mod fork_schedule;
 mod presets;
 
 pub use beacon_block::*;
@@ -10,6 +11,7 @@ pub use beacon_state::*;
 pub use context::*;
 pub use execution_engine::*;
 pub use executor::*;
+pub use fork_schedule::*;
 
 pub type Result<T> = std::result::Result<T, crate::Error>;
 
@@ -19,6 +21,10 @@ pub enum Validation {
     Disabled,
 }
 
+pub mod gnosis {
+    pub use super::presets::gnosis::*;
+}
+
 pub mod mainnet {
     pub use super::presets::mainnet::*;
 }
@@ -0,0 +1,53 @@
+use crate::{
+    altair,
+    altair::gnosis::SYNC_COMMITTEE_SIZE,
+    bellatrix,
+    bellatrix::gnosis::{
+        BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+    },
+    capella, deneb, phase0,
+    phase0::gnosis::{
+        EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
+        HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS,
+        MAX_PROPOSER_SLASHINGS, MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+        PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT, VALIDATOR_REGISTRY_LIMIT,
+    },
+    state_transition,
+};
+
+pub use crate::Error;
+pub use state_transition::{BeaconState, Context, ExecutionEngine, SignedBeaconBlock, Validation};
+
+pub type Executor<B> = state_transition::Executor<
+    SLOTS_PER_HISTORICAL_ROOT,
+    HISTORICAL_ROOTS_LIMIT,
+    ETH1_DATA_VOTES_BOUND,
+    VALIDATOR_REGISTRY_LIMIT,
+    EPOCHS_PER_HISTORICAL_VECTOR,
+    EPOCHS_PER_SLASHINGS_VECTOR,
+    MAX_VALIDATORS_PER_COMMITTEE,
+    PENDING_ATTESTATIONS_BOUND,
+    SYNC_COMMITTEE_SIZE,
+    BYTES_PER_LOGS_BLOOM,
+    MAX_EXTRA_DATA_BYTES,
+    MAX_BYTES_PER_TRANSACTION,
+    MAX_TRANSACTIONS_PER_PAYLOAD,
+    MAX_PROPOSER_SLASHINGS,
+    MAX_ATTESTER_SLASHINGS,
+    MAX_ATTESTATIONS,
+    MAX_DEPOSITS,
+    MAX_VOLUNTARY_EXITS,
+    B,
+>;
+
+/// Ensures the type-level preset values of `context` match the types instantiated in this module.
+pub fn check_compatibility(context: &Context) -> Result<(), Error> {
+    context.check_type_level_presets(
+        &phase0::gnosis::PRESET,
+        &altair::gnosis::PRESET,
+        &bellatrix::gnosis::PRESET,
+        &capella::gnosis::PRESET,
+        &deneb::gnosis::PRESET,
+    )
+}
@@ -1,9 +1,12 @@
 use crate::{
+    altair,
     altair::mainnet::SYNC_COMMITTEE_SIZE,
+    bellatrix,
     bellatrix::mainnet::{
         BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
         MAX_TRANSACTIONS_PER_PAYLOAD,
     },
+    capella, deneb, phase0,
     phase0::mainnet::{
         EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
         HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS,
@@ -37,3 +40,14 @@ pub type Executor<B> = state_transition::Executor<
     MAX_VOLUNTARY_EXITS,
     B,
 >;
+
+/// Ensures the type-level preset values of `context` match the types instantiated in this module.
+pub fn check_compatibility(context: &Context) -> Result<(), Error> {
+    context.check_type_level_presets(
+        &phase0::mainnet::PRESET,
+        &altair::mainnet::PRESET,
+        &bellatrix::mainnet::PRESET,
+        &capella::mainnet::PRESET,
+        &deneb::mainnet::PRESET,
+    )
+}
@@ -0,0 +1,480 @@
+//! Weak subjectivity helpers, following the consensus-specs weak subjectivity guide.
+use crate::{
+    phase0::{
+        helpers::{compute_epoch_at_slot, compute_start_slot_at_epoch, is_active_validator},
+        Checkpoint,
+    },
+    primitives::{Epoch, Gwei, Root, Slot},
+    ssz::prelude::*,
+    state_transition::Context,
+    types::{BeaconState, SignedBeaconBlock},
+};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+pub const SAFETY_DECAY: u64 = 10;
+pub const ETH_TO_GWEI: Gwei = 10u64.pow(9);
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("could not parse weak subjectivity checkpoint `{0}`, expected `root:epoch`")]
+    InvalidCheckpoint(String),
+    #[error("block root {provided:?} does not match the checkpoint root {expected:?}")]
+    BlockRootMismatch { expected: Root, provided: Root },
+    #[error("state root {provided:?} does not match the state root {expected:?} committed to by the checkpoint block")]
+    StateRootMismatch { expected: Root, provided: Root },
+    #[error("state at slot {state_slot} does not match the checkpoint block at slot {block_slot}")]
+    StateNotForBlock { state_slot: Slot, block_slot: Slot },
+    #[error("slot {slot} is not aligned with the checkpoint at epoch {epoch}")]
+    SlotMismatch { slot: Slot, epoch: Epoch },
+    #[error("checkpoint at epoch {epoch} is outside of the weak subjectivity period of {period} epochs as of epoch {current_epoch}")]
+    OutsideWeakSubjectivityPeriod { epoch: Epoch, period: u64, current_epoch: Epoch },
+    #[error("{0}")]
+    Merkleization(#[from] MerkleizationError),
+}
+
+/// A trusted checkpoint, conventionally written as `root:epoch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeakSubjectivityCheckpoint {
+    pub root: Root,
+    pub epoch: Epoch,
+}
+
+impl fmt::Display for WeakSubjectivityCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        for byte in self.root.iter() {
+            write!(f, "{byte:02x}")?;
+        }
+        write!(f, ":{}", self.epoch)
+    }
+}
+
+impl FromStr for WeakSubjectivityCheckpoint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_checkpoint = || Error::InvalidCheckpoint(s.to_string());
+        let (root, epoch) = s.trim().split_once(':').ok_or_else(invalid_checkpoint)?;
+        let root = crate::serde::try_bytes_from_hex_str(root).map_err(|_| invalid_checkpoint())?;
+        let root = Root::try_from(root.as_ref()).map_err(|_| invalid_checkpoint())?;
+        let epoch = epoch.parse().map_err(|_| invalid_checkpoint())?;
+        Ok(Self { root, epoch })
+    }
+}
+
+impl From<WeakSubjectivityCheckpoint> for Checkpoint {
+    fn from(checkpoint: WeakSubjectivityCheckpoint) -> Self {
+        Self { epoch: checkpoint.epoch, root: checkpoint.root }
+    }
+}
+
+impl From<Checkpoint> for WeakSubjectivityCheckpoint {
+    fn from(checkpoint: Checkpoint) -> Self {
+        Self { root: checkpoint.root, epoch: checkpoint.epoch }
+    }
+}
+
+/// Returns the weak subjectivity period in epochs for the current `state`.
+///
+/// This accounts for the validator set churn, bounded by the churn limit per epoch, and for
+/// balance top-ups, bounded by `MAX_DEPOSITS * SLOTS_PER_EPOCH` per epoch.
+pub fn compute_weak_subjectivity_period<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    context: &Context,
+) -> u64 {
+    let mut ws_period = context.min_validator_withdrawability_delay;
+
+    let current_epoch = compute_epoch_at_slot(state.slot(), context);
+    let (active_validator_count, total_active_balance) = state
+        .validators()
+        .iter()
+        .filter(|validator| is_active_validator(validator, current_epoch))
+        .fold((0u64, 0), |(count, balance), validator| {
+            (count + 1, balance + validator.effective_balance)
+        });
+    // NOTE: the spec assumes a non-empty validator set
+    if active_validator_count == 0 {
+        return ws_period
+    }
+    let total_active_balance = Gwei::max(context.effective_balance_increment, total_active_balance);
+
+    let n = active_validator_count;
+    let t = total_active_balance / n / ETH_TO_GWEI;
+    let max_t = context.max_effective_balance / ETH_TO_GWEI;
+    let delta = u64::max(context.min_per_epoch_churn_limit, n / context.churn_limit_quotient);
+    let max_delta = context.max_deposits as u64 * context.slots_per_epoch;
+    let d = SAFETY_DECAY;
+
+    if max_t * (200 + 3 * d) < t * (200 + 12 * d) {
+        let epochs_for_validator_set_churn =
+            n * (t * (200 + 12 * d) - max_t * (200 + 3 * d)) / (600 * delta * (2 * t + max_t));
+        let epochs_for_balance_top_ups = n * (200 + 3 * d) / (600 * max_delta);
+        ws_period += u64::max(epochs_for_validator_set_churn, epochs_for_balance_top_ups);
+    } else {
+        ws_period += 3 * n * d * t / (200 * max_delta * (max_t - t));
+    }
+
+    ws_period
+}
+
+/// Returns `true` if `current_epoch` is within the weak subjectivity period of `ws_state`.
+pub fn is_within_weak_subjectivity_period<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    ws_state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    current_epoch: Epoch,
+    context: &Context,
+) -> bool {
+    let ws_period = compute_weak_subjectivity_period(ws_state, context);
+    let ws_state_epoch = compute_epoch_at_slot(ws_state.slot(), context);
+    current_epoch <= ws_state_epoch + ws_period
+}
+
+/// Verifies a finalized `state` and `block` downloaded to start syncing from against a trusted
+/// `checkpoint`, refusing anchors that do not match it or that are no longer within the weak
+/// subjectivity period as of `current_epoch`.
+///
+/// The `state` must be the post-state of `block`, so that its root can be checked against the
+/// state root committed to by the block. Callers holding the post-state can advance a copy to the
+/// checkpoint epoch with `process_slots` once the anchor is verified.
+pub fn verify_checkpoint_anchor<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    checkpoint: &WeakSubjectivityCheckpoint,
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    block: &mut SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    current_epoch: Epoch,
+    context: &Context,
+) -> Result<(), Error> {
+    let mut message = block.message_mut();
+    let block_root = message.hash_tree_root()?;
+    if block_root != checkpoint.root {
+        return Err(Error::BlockRootMismatch { expected: checkpoint.root, provided: block_root })
+    }
+
+    // NOTE: the checkpoint root is the latest block at or before the start of the checkpoint epoch
+    let block_slot = message.slot();
+    let checkpoint_slot = compute_start_slot_at_epoch(checkpoint.epoch, context);
+    if block_slot > checkpoint_slot {
+        return Err(Error::SlotMismatch { slot: block_slot, epoch: checkpoint.epoch })
+    }
+
+    // NOTE: only the post-state of `block` can be bound to the trusted block root; a state
+    // advanced past it would only be tied to the block through its latest block header
+    let state_slot = state.slot();
+    if state_slot != block_slot {
+        return Err(Error::StateNotForBlock { state_slot, block_slot })
+    }
+
+    let mut latest_block_header = state.latest_block_header().clone();
+    if latest_block_header.slot != block_slot {
+        return Err(Error::StateNotForBlock { state_slot, block_slot })
+    }
+    let state_root = state.hash_tree_root()?;
+    let expected_state_root = message.state_root();
+    if state_root != expected_state_root {
+        return Err(Error::StateRootMismatch { expected: expected_state_root, provided: state_root })
+    }
+    // NOTE: the header's `state_root` is only filled in once the state advances past the block
+    latest_block_header.state_root = state_root;
+    if latest_block_header.hash_tree_root()? != block_root {
+        return Err(Error::StateNotForBlock { state_slot, block_slot })
+    }
+
+    if !is_within_weak_subjectivity_period(state, current_epoch, context) {
+        return Err(Error::OutsideWeakSubjectivityPeriod {
+            epoch: checkpoint.epoch,
+            period: compute_weak_subjectivity_period(state, context),
+            current_epoch,
+        })
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phase0::{BeaconBlockHeader, Validator};
+
+    const CHECKPOINT_EPOCH: Epoch = 10;
+
+    // Returns a state at the start of `CHECKPOINT_EPOCH` with the block applied at that slot.
+    fn anchor(
+        context: &Context,
+    ) -> (crate::types::mainnet::BeaconState, crate::types::mainnet::SignedBeaconBlock) {
+        anchor_at(compute_start_slot_at_epoch(CHECKPOINT_EPOCH, context), context)
+    }
+
+    // Returns the post-state of a block at `slot`.
+    fn anchor_at(
+        slot: Slot,
+        context: &Context,
+    ) -> (crate::types::mainnet::BeaconState, crate::types::mainnet::SignedBeaconBlock) {
+        let validator = Validator {
+            effective_balance: context.max_effective_balance,
+            exit_epoch: u64::MAX,
+            withdrawable_epoch: u64::MAX,
+            ..Default::default()
+        };
+        let mut block = crate::phase0::mainnet::SignedBeaconBlock::default();
+        block.message.slot = slot;
+        block.message.parent_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+
+        let mut state = crate::phase0::mainnet::BeaconState::default();
+        state.slot = slot;
+        state.validators = vec![validator; 64].try_into().unwrap();
+        state.latest_block_header = BeaconBlockHeader {
+            slot,
+            parent_root: block.message.parent_root,
+            body_root: block.message.body.hash_tree_root().unwrap(),
+            ..Default::default()
+        };
+        block.message.state_root = state.hash_tree_root().unwrap();
+        (
+            crate::types::mainnet::BeaconState::Phase0(state),
+            crate::types::mainnet::SignedBeaconBlock::Phase0(block),
+        )
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let input = "0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95:74240";
+        let checkpoint: WeakSubjectivityCheckpoint = input.parse().unwrap();
+        assert_eq!(checkpoint.epoch, 74240);
+        assert_eq!(checkpoint.root[0], 0x4b);
+        assert_eq!(checkpoint.to_string(), input);
+
+        assert!("0x4b363db9:74240".parse::<WeakSubjectivityCheckpoint>().is_err());
+        assert!("74240".parse::<WeakSubjectivityCheckpoint>().is_err());
+        assert!(format!("{}:latest", &input[..66]).parse::<WeakSubjectivityCheckpoint>().is_err());
+    }
+
+    #[test]
+    fn test_weak_subjectivity_period() {
+        let context = Context::for_mainnet();
+        // values from the table in the weak subjectivity guide for an average balance of 32 ETH
+        for (validator_count, expected_period) in
+            [(32768, 665), (65536, 1075), (131072, 1894), (262144, 3532)]
+        {
+            let validator = Validator {
+                effective_balance: context.max_effective_balance,
+                exit_epoch: u64::MAX,
+                withdrawable_epoch: u64::MAX,
+                ..Default::default()
+            };
+            let mut inner = crate::phase0::mainnet::BeaconState::default();
+            inner.validators = vec![validator; validator_count].try_into().unwrap();
+            let state = crate::types::mainnet::BeaconState::Phase0(inner);
+            assert_eq!(compute_weak_subjectivity_period(&state, &context), expected_period);
+            assert!(is_within_weak_subjectivity_period(&state, expected_period, &context));
+            assert!(!is_within_weak_subjectivity_period(&state, expected_period + 1, &context));
+        }
+    }
+
+    #[test]
+    fn test_verify_checkpoint_anchor() {
+        let context = Context::for_mainnet();
+        let (mut state, mut block) = anchor(&context);
+        let root = block.message_mut().hash_tree_root().unwrap();
+        let checkpoint = WeakSubjectivityCheckpoint { root, epoch: CHECKPOINT_EPOCH };
+        verify_checkpoint_anchor(&checkpoint, &mut state, &mut block, CHECKPOINT_EPOCH, &context)
+            .unwrap();
+
+        let period = compute_weak_subjectivity_period(&state, &context);
+        let current_epoch = CHECKPOINT_EPOCH + period;
+        verify_checkpoint_anchor(&checkpoint, &mut state, &mut block, current_epoch, &context)
+            .unwrap();
+        assert!(matches!(
+            verify_checkpoint_anchor(
+                &checkpoint,
+                &mut state,
+                &mut block,
+                current_epoch + 1,
+                &context
+            ),
+            Err(Error::OutsideWeakSubjectivityPeriod { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_checkpoint_anchor_mismatch() {
+        let context = Context::for_mainnet();
+        let (mut state, mut block) = anchor(&context);
+        let checkpoint =
+            WeakSubjectivityCheckpoint { root: Root::default(), epoch: CHECKPOINT_EPOCH };
+        assert!(matches!(
+            verify_checkpoint_anchor(
+                &checkpoint,
+                &mut state,
+                &mut block,
+                CHECKPOINT_EPOCH,
+                &context
+            ),
+            Err(Error::BlockRootMismatch { .. })
+        ));
+
+        // a block committing to another state
+        let crate::types::SignedBeaconBlock::Phase0(inner) = &mut block else {
+            panic!("expected a phase0 block")
+        };
+        inner.message.state_root = Root::default();
+        let root = block.message_mut().hash_tree_root().unwrap();
+        let checkpoint = WeakSubjectivityCheckpoint { root, epoch: CHECKPOINT_EPOCH };
+        assert!(matches!(
+            verify_checkpoint_anchor(
+                &checkpoint,
+                &mut state,
+                &mut block,
+                CHECKPOINT_EPOCH,
+                &context
+            ),
+            Err(Error::StateRootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_checkpoint_anchor_rejects_forged_state() {
+        let context = Context::for_mainnet();
+        // the last block before the checkpoint epoch, followed by a skipped slot
+        let checkpoint_slot = compute_start_slot_at_epoch(CHECKPOINT_EPOCH, &context);
+        let (state, mut block) = anchor_at(checkpoint_slot - 1, &context);
+        let root = block.message_mut().hash_tree_root().unwrap();
+        let checkpoint = WeakSubjectivityCheckpoint { root, epoch: CHECKPOINT_EPOCH };
+        verify_checkpoint_anchor(
+            &checkpoint,
+            &mut state.clone(),
+            &mut block,
+            CHECKPOINT_EPOCH,
+            &context,
+        )
+        .unwrap();
+
+        // the state advanced to the checkpoint slot, keeping its real latest block header
+        let mut advanced = state.clone();
+        let crate::types::BeaconState::Phase0(inner) = &mut advanced else {
+            panic!("expected a phase0 state")
+        };
+        inner.slot = checkpoint_slot;
+        inner.latest_block_header.state_root = block.message_mut().state_root();
+        inner.validators[0].effective_balance = 0;
+        assert!(matches!(
+            verify_checkpoint_anchor(
+                &checkpoint,
+                &mut advanced,
+                &mut block,
+                CHECKPOINT_EPOCH,
+                &context
+            ),
+            Err(Error::StateNotForBlock { .. })
+        ));
+
+        // the post-state of the block with another validator set
+        let mut forged = state;
+        forged.validators_mut()[0].effective_balance = 0;
+        assert!(matches!(
+            verify_checkpoint_anchor(
+                &checkpoint,
+                &mut forged,
+                &mut block,
+                CHECKPOINT_EPOCH,
+                &context
+            ),
+            Err(Error::StateRootMismatch { .. })
+        ));
+    }
+}
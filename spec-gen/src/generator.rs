@@ -405,11 +405,15 @@ impl Spec {
                 let mut all_arguments = vec![];
                 for name in type_names {
                     if let Some(target_module) = index.get(&name) {
-                        let target_module = self.diff.modules.get(target_module).unwrap();
-                        let container = target_module
+                        // containers inherited into this module are only merged after this loop
+                        let container = module
                             .containers
                             .iter()
                             .find(|&c| c.name == name)
+                            .or_else(|| {
+                                let target_module = self.diff.modules.get(target_module)?;
+                                target_module.containers.iter().find(|&c| c.name == name)
+                            })
                             .expect("internal state integrity");
 
                         let arguments = generics_to_arguments(&container.item.generics);
@@ -594,6 +598,8 @@ fn parse_fork_diff_with_symbol_index(fork: &Fork) -> (ForkDiff, HashMap<String,
                 Item::Impl(_) => {
                     println!("skipping item: `impl` block in {source_path}");
                 }
+                // NOTE: modules like `mod tests` are local to the source file
+                Item::Mod(_) => {}
                 Item::Trait(item) => {
                     let item = TraitDef::new(item, *fork);
                     if item.is_pub() {